@@ -40,6 +40,9 @@ pub struct CredentialDefinition {
     pub tag: String,
     pub value: CredentialDefinitionData,
     pub issuer_id: IssuerId,
+    /// Credential definition whose keys were rotated to create this one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rotated_from: Option<CredentialDefinitionId>,
 }
 
 impl CredentialDefinition {
@@ -57,6 +60,10 @@ impl Validatable for CredentialDefinition {
     fn validate(&self) -> Result<(), ValidationError> {
         self.schema_id.validate()?;
         self.issuer_id.validate()?;
+        self.rotated_from
+            .as_ref()
+            .map(Validatable::validate)
+            .transpose()?;
 
         Ok(())
     }
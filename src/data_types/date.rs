@@ -0,0 +1,167 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::ConversionError;
+
+/// Calendar date of a credential attribute
+///
+/// Dates are encoded as the integer `YYYYMMDD`, which preserves their order and fits the
+/// `i32` values supported by predicates. A date issued with
+/// [`MakeCredentialValues::add_date`](crate::types::MakeCredentialValues::add_date) has the raw
+/// value `YYYY-MM-DD` and this encoding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Date {
+    year: u16,
+    month: u8,
+    day: u8,
+}
+
+impl Date {
+    pub fn new(year: u16, month: u8, day: u8) -> Result<Self, ConversionError> {
+        if !(1..=9999).contains(&year) {
+            return Err(ConversionError::from_msg(format!("Invalid year: {}", year)));
+        }
+        if !(1..=12).contains(&month) {
+            return Err(ConversionError::from_msg(format!(
+                "Invalid month: {}",
+                month
+            )));
+        }
+        if day == 0 || day > days_in_month(year, month) {
+            return Err(ConversionError::from_msg(format!(
+                "Invalid day: {}-{:02}-{:02}",
+                year, month, day
+            )));
+        }
+        Ok(Self { year, month, day })
+    }
+
+    #[must_use]
+    pub const fn year(&self) -> u16 {
+        self.year
+    }
+
+    #[must_use]
+    pub const fn month(&self) -> u8 {
+        self.month
+    }
+
+    #[must_use]
+    pub const fn day(&self) -> u8 {
+        self.day
+    }
+
+    /// The `YYYYMMDD` encoding of the date
+    #[must_use]
+    pub fn to_encoded(&self) -> i32 {
+        i32::from(self.year) * 10000 + i32::from(self.month) * 100 + i32::from(self.day)
+    }
+
+    pub fn from_encoded(value: i32) -> Result<Self, ConversionError> {
+        if value <= 0 {
+            return Err(ConversionError::from_msg(format!(
+                "Invalid encoded date: {}",
+                value
+            )));
+        }
+        Self::new(
+            (value / 10000) as u16,
+            (value / 100 % 100) as u8,
+            (value % 100) as u8,
+        )
+        .map_err(|_| ConversionError::from_msg(format!("Invalid encoded date: {}", value)))
+    }
+
+    /// The encoding of this date `years` years earlier: someone born on or before it is at
+    /// least `years` old on this date
+    pub(crate) fn encoded_years_before(&self, years: u16) -> Result<i32, ConversionError> {
+        if years >= self.year {
+            return Err(ConversionError::from_msg(format!(
+                "Invalid number of years before {}: {}",
+                self, years
+            )));
+        }
+        // February 29th is kept as is, so that it also covers February 28th of common years
+        Ok(self.to_encoded() - i32::from(years) * 10000)
+    }
+}
+
+impl fmt::Display for Date {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:04}-{:02}-{:02}", self.year, self.month, self.day)
+    }
+}
+
+impl FromStr for Date {
+    type Err = ConversionError;
+
+    /// Parse a `YYYY-MM-DD` date
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ConversionError::from_msg(format!("Invalid date: {}", s));
+        let mut parts = s.splitn(3, '-');
+        let mut next = |len: usize| {
+            parts
+                .next()
+                .filter(|part| part.len() == len && part.bytes().all(|b| b.is_ascii_digit()))
+                .and_then(|part| part.parse::<u16>().ok())
+                .ok_or_else(invalid)
+        };
+        let year = next(4)?;
+        let month = next(2)?;
+        let day = next(2)?;
+        Self::new(year, month as u8, day as u8).map_err(|_| invalid())
+    }
+}
+
+const fn is_leap_year(year: u16) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+const fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_date_encoding() {
+        let date = Date::new(2004, 2, 29).unwrap();
+        assert_eq!(date.to_encoded(), 20040229);
+        assert_eq!(Date::from_encoded(20040229).unwrap(), date);
+        assert_eq!(date.to_string(), "2004-02-29");
+        assert_eq!("2004-02-29".parse::<Date>().unwrap(), date);
+
+        assert!(Date::new(2003, 2, 29).is_err());
+        assert!(Date::new(2000, 13, 1).is_err());
+        assert!(Date::from_encoded(20001301).is_err());
+        assert!(Date::from_encoded(-20000101).is_err());
+        assert!("2004-2-29".parse::<Date>().is_err());
+        assert!("2004-02-29T00:00".parse::<Date>().is_err());
+    }
+
+    #[test]
+    fn test_date_order_matches_encoding() {
+        let dates = ["1999-12-31", "2000-01-01", "2000-01-10", "2000-10-01"]
+            .iter()
+            .map(|d| d.parse::<Date>().unwrap())
+            .collect::<Vec<_>>();
+        for pair in dates.windows(2) {
+            assert!(pair[0] < pair[1]);
+            assert!(pair[0].to_encoded() < pair[1].to_encoded());
+        }
+    }
+
+    #[test]
+    fn test_encoded_years_before() {
+        let date = Date::new(2026, 10, 18).unwrap();
+        assert_eq!(date.encoded_years_before(18).unwrap(), 20081018);
+        assert!(date.encoded_years_before(2026).is_err());
+    }
+}
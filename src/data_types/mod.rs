@@ -7,6 +7,9 @@ pub mod cred_offer;
 /// Credential requests
 pub mod cred_request;
 
+/// Calendar dates encoded for predicates
+pub mod date;
+
 /// Credentials
 pub mod credential;
 
@@ -19,6 +22,9 @@ pub mod nonce;
 /// Presentation requests
 pub mod pres_request;
 
+/// DIF Presentation Exchange definitions and submissions
+pub mod pres_exchange;
+
 /// Presentations
 pub mod presentation;
 
@@ -28,12 +34,18 @@ pub mod rev_reg;
 /// Revocation registry definitions
 pub mod rev_reg_def;
 
+/// Revocation index allocation
+pub mod rev_reg_allocator;
+
 /// Revocation status list
 pub mod rev_status_list;
 
 /// Credential schemas
 pub mod schema;
 
+/// W3C Verifiable Credentials representation
+pub mod w3c;
+
 /// Macros for the data types
 pub mod macros;
 
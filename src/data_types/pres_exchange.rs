@@ -0,0 +1,134 @@
+use std::collections::HashSet;
+
+use serde_json::{Map, Number, Value};
+
+use crate::error::ValidationError;
+use crate::invalid;
+use crate::utils::validation::Validatable;
+
+/// Format of a legacy AnonCreds presentation in a presentation submission
+pub const AC_VP_FORMAT: &str = "ac_vp";
+/// Format of a credential presented within a legacy AnonCreds presentation
+pub const AC_VC_FORMAT: &str = "ac_vc";
+
+/// DIF Presentation Exchange v2 `presentation_definition`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PresentationDefinition {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    pub input_descriptors: Vec<InputDescriptor>,
+}
+
+impl Validatable for PresentationDefinition {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.id.is_empty() {
+            return Err("Presentation definition validation failed: `id` is empty".into());
+        }
+        if self.input_descriptors.is_empty() {
+            return Err(
+                "Presentation definition validation failed: `input_descriptors` is empty".into(),
+            );
+        }
+
+        let mut ids = HashSet::with_capacity(self.input_descriptors.len());
+        for descriptor in &self.input_descriptors {
+            if descriptor.id.is_empty() {
+                return Err(
+                    "Presentation definition validation failed: input descriptor `id` is empty"
+                        .into(),
+                );
+            }
+            if !ids.insert(descriptor.id.as_str()) {
+                return Err(invalid!(
+                    "Presentation definition validation failed: duplicate input descriptor: {}",
+                    descriptor.id
+                ));
+            }
+            for field in &descriptor.constraints.fields {
+                if field.path.is_empty() {
+                    return Err(invalid!(
+                        "Presentation definition validation failed: field without `path` in input descriptor: {}",
+                        descriptor.id
+                    ));
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct InputDescriptor {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(default)]
+    pub constraints: Constraints,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Constraints {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<Field>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit_disclosure: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Field {
+    pub path: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub purpose: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub filter: Option<Filter>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub optional: bool,
+}
+
+/// JSON schema filter of a [`Field`]
+///
+/// Only the keywords which can be expressed by a presentation request are modelled, any other
+/// keyword is kept in `other`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Filter {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_: Option<String>,
+    #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+    pub const_: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_minimum: Option<Number>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exclusive_maximum: Option<Number>,
+    #[serde(flatten)]
+    pub other: Map<String, Value>,
+}
+
+/// DIF Presentation Exchange v2 `presentation_submission`
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct PresentationSubmission {
+    pub id: String,
+    pub definition_id: String,
+    pub descriptor_map: Vec<DescriptorMapping>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DescriptorMapping {
+    pub id: String,
+    pub format: String,
+    pub path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path_nested: Option<Box<DescriptorMapping>>,
+}
@@ -5,8 +5,9 @@ use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
 use super::credential::Credential;
+use super::date::Date;
 use super::nonce::Nonce;
-use crate::error::ValidationError;
+use crate::error::{ConversionError, ValidationError};
 use crate::invalid;
 use crate::utils::{
     query::Query,
@@ -23,6 +24,53 @@ pub struct PresentationRequestPayload {
     #[serde(default)]
     pub requested_predicates: HashMap<String, PredicateInfo>,
     pub non_revoked: Option<NonRevokedInterval>,
+    /// Attributes which must hold the same value in the credentials presented for some
+    /// referents, without being revealed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub equal_attributes: Vec<EqualAttributes>,
+    /// Groups of requested attribute and predicate referents which must be answered by the same
+    /// credential
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub same_credential_referents: Vec<Vec<String>>,
+}
+
+impl PresentationRequestPayload {
+    /// Create a payload requesting no attribute or predicate yet, with no non revocation interval
+    ///
+    /// Unlike a struct literal, it keeps compiling when fields are added to the payload.
+    #[must_use]
+    pub fn new(nonce: Nonce, name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            nonce,
+            name: name.into(),
+            version: version.into(),
+            requested_attributes: HashMap::new(),
+            requested_predicates: HashMap::new(),
+            non_revoked: None,
+            equal_attributes: Vec::new(),
+            same_credential_referents: Vec::new(),
+        }
+    }
+
+    /// The names of the attributes proven equal by the credential presented for the referents
+    /// `presented` holds
+    pub(crate) fn equal_attribute_names(&self, presented: impl Fn(&str) -> bool) -> Vec<String> {
+        self.equal_attributes
+            .iter()
+            .filter(|equal| equal.referents.iter().any(|referent| presented(referent)))
+            .map(|equal| equal.name.clone())
+            .collect()
+    }
+}
+
+/// Attribute which must hold the same value in the credentials presented for the requested
+/// attribute and predicate `referents`, without being revealed by them
+///
+/// Other credentials of the presentation may hold another value for the attribute, or none.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct EqualAttributes {
+    pub name: String,
+    pub referents: Vec<String>,
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -193,10 +241,148 @@ pub struct PredicateInfo {
     pub name: String,
     pub p_type: PredicateTypes,
     pub p_value: i32,
+    /// Upper bound of a [`PredicateTypes::RANGE`] predicate, `p_value` being its lower bound
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub p_value_max: Option<i32>,
     pub restrictions: Option<Query>,
     pub non_revoked: Option<NonRevokedInterval>,
 }
 
+impl PredicateInfo {
+    /// Predicate `p_type` on the attribute `name` against `p_value`, without restrictions
+    ///
+    /// See [`PredicateInfo::not_equal`] for what a [`PredicateTypes::NE`] predicate reveals.
+    #[must_use]
+    pub fn new(name: impl Into<String>, p_type: PredicateTypes, p_value: i32) -> Self {
+        Self {
+            name: name.into(),
+            p_type,
+            p_value,
+            p_value_max: None,
+            restrictions: None,
+            non_revoked: None,
+        }
+    }
+
+    /// Predicate on the date attribute `name` being strictly before `date`
+    #[must_use]
+    pub fn born_before(name: impl Into<String>, date: Date) -> Self {
+        Self::new(name, PredicateTypes::LT, date.to_encoded())
+    }
+
+    /// Predicate on the date attribute `name` being strictly after `date`
+    #[must_use]
+    pub fn expires_after(name: impl Into<String>, date: Date) -> Self {
+        Self::new(name, PredicateTypes::GT, date.to_encoded())
+    }
+
+    /// Predicate on the birth date attribute `name` making its holder at least `age` years old
+    /// on `date`
+    pub fn age_at_least(
+        name: impl Into<String>,
+        age: u16,
+        date: Date,
+    ) -> Result<Self, ConversionError> {
+        Ok(Self::new(
+            name,
+            PredicateTypes::LE,
+            date.encoded_years_before(age)?,
+        ))
+    }
+
+    /// Predicate on the attribute `name` being between `min` and `max`, both included
+    #[must_use]
+    pub fn range(name: impl Into<String>, min: i32, max: i32) -> Self {
+        Self {
+            p_value_max: Some(max),
+            ..Self::new(name, PredicateTypes::RANGE, min)
+        }
+    }
+
+    /// Predicate on the attribute `name` being different from `value`
+    ///
+    /// # Privacy
+    ///
+    /// The presentation does not hide on which side of `value` the attribute is: it proves either
+    /// `<` or `>`, and tells the verifier which one in
+    /// [`RequestedProof::not_equal_predicates`](crate::data_types::presentation::RequestedProof::not_equal_predicates).
+    /// Only request it when that is acceptable to disclose.
+    #[must_use]
+    pub fn not_equal(name: impl Into<String>, value: i32) -> Self {
+        Self::new(name, PredicateTypes::NE, value)
+    }
+
+    /// Whether an encoded attribute value satisfies the predicate
+    #[must_use]
+    pub(crate) fn is_satisfied_by(&self, value: i64) -> bool {
+        let p_value = i64::from(self.p_value);
+        match self.p_type {
+            PredicateTypes::GE => value >= p_value,
+            PredicateTypes::GT => value > p_value,
+            PredicateTypes::LE => value <= p_value,
+            PredicateTypes::LT => value < p_value,
+            PredicateTypes::NE => value != p_value,
+            PredicateTypes::RANGE => {
+                value >= p_value
+                    && self
+                        .p_value_max
+                        .map_or(false, |max| value <= i64::from(max))
+            }
+        }
+    }
+
+    /// The predicate proven for the encoded attribute `value`: a not-equal predicate is proven
+    /// as either `<` or `>` its value, any other predicate is kept as is
+    pub(crate) fn resolve_for_value(&self, value: i64) -> Result<Self, ValidationError> {
+        if self.p_type != PredicateTypes::NE {
+            return Ok(self.clone());
+        }
+        match value.cmp(&i64::from(self.p_value)) {
+            std::cmp::Ordering::Less => Ok(self.resolve_not_equal(PredicateTypes::LT)),
+            std::cmp::Ordering::Greater => Ok(self.resolve_not_equal(PredicateTypes::GT)),
+            std::cmp::Ordering::Equal => Err(invalid!(
+                "Attribute \"{}\" does not satisfy the not-equal predicate",
+                self.name
+            )),
+        }
+    }
+
+    /// The predicate proven for a not-equal predicate, given the comparison chosen by the prover
+    #[must_use]
+    pub(crate) fn resolve_not_equal(&self, p_type: PredicateTypes) -> Self {
+        Self {
+            p_type,
+            ..self.clone()
+        }
+    }
+
+    /// The `(type, value)` pairs of the CL predicates proving this predicate
+    pub(crate) fn crypto_predicates(&self) -> Result<Vec<(PredicateTypes, i32)>, ValidationError> {
+        match self.p_type {
+            PredicateTypes::GE | PredicateTypes::LE | PredicateTypes::GT | PredicateTypes::LT => {
+                Ok(vec![(self.p_type.clone(), self.p_value)])
+            }
+            PredicateTypes::RANGE => {
+                let max = self.p_value_max.ok_or_else(|| {
+                    invalid!("Range predicate on \"{}\" has no upper bound", self.name)
+                })?;
+                Ok(vec![
+                    (PredicateTypes::GE, self.p_value),
+                    (PredicateTypes::LE, max),
+                ])
+            }
+            PredicateTypes::NE => Err(invalid!(
+                "Not-equal predicate on \"{}\" has no proven comparison",
+                self.name
+            )),
+        }
+    }
+}
+
+/// Comparison of an attribute with the constant `p_value` of a predicate
+///
+/// Predicates comparing two attributes are not supported: a CL sub proof only compares one hidden
+/// attribute with a constant.
 #[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
 pub enum PredicateTypes {
     #[serde(rename = ">=")]
@@ -207,6 +393,13 @@ pub enum PredicateTypes {
     GT,
     #[serde(rename = "<")]
     LT,
+    /// Proven as either `<` or `>` the predicate value, which tells the verifier on which side
+    /// of it the attribute is. See [`PredicateInfo::not_equal`]
+    #[serde(rename = "!=")]
+    NE,
+    /// Between `p_value` and `p_value_max`, both included
+    #[serde(rename = "range")]
+    RANGE,
 }
 
 impl fmt::Display for PredicateTypes {
@@ -216,6 +409,8 @@ impl fmt::Display for PredicateTypes {
             Self::GT => write!(f, "GT"),
             Self::LE => write!(f, "LE"),
             Self::LT => write!(f, "LT"),
+            Self::NE => write!(f, "NE"),
+            Self::RANGE => write!(f, "RANGE"),
         }
     }
 }
@@ -274,11 +469,84 @@ impl Validatable for PresentationRequest {
                     requested_predicate
                 ));
             }
+            match (&requested_predicate.p_type, requested_predicate.p_value_max) {
+                (PredicateTypes::RANGE, Some(max)) if max >= requested_predicate.p_value => {}
+                (PredicateTypes::RANGE, _) => {
+                    return Err(invalid!(
+                        "Presentation request validation failed: range predicate without a valid `p_value_max`: {:?}",
+                        requested_predicate
+                    ));
+                }
+                (_, Some(_)) => {
+                    return Err(invalid!(
+                        "Presentation request validation failed: `p_value_max` is only allowed for range predicates: {:?}",
+                        requested_predicate
+                    ));
+                }
+                _ => {}
+            }
             if let Some(ref restrictions) = requested_predicate.restrictions {
                 _process_operator(restrictions, &version)?;
             }
         }
 
+        for (idx, equal) in value.equal_attributes.iter().enumerate() {
+            if equal.name.is_empty() {
+                return Err(invalid!(
+                    "Presentation request validation failed: there is empty equal attribute"
+                ));
+            }
+            if equal.name == "master_secret" {
+                return Err(invalid!(
+                    "Presentation request validation failed: `master_secret` cannot be an equal attribute"
+                ));
+            }
+            if value.equal_attributes[..idx]
+                .iter()
+                .any(|other| other.name == equal.name)
+            {
+                return Err(invalid!(
+                    "Presentation request validation failed: duplicate equal attribute: {}",
+                    equal.name
+                ));
+            }
+            if equal.referents.is_empty() {
+                return Err(invalid!(
+                    "Presentation request validation failed: equal attribute {} has no referent",
+                    equal.name
+                ));
+            }
+            for referent in &equal.referents {
+                if !value.requested_attributes.contains_key(referent)
+                    && !value.requested_predicates.contains_key(referent)
+                {
+                    return Err(invalid!(
+                        "Presentation request validation failed: equal attribute {} refers to unknown referent {}",
+                        equal.name,
+                        referent
+                    ));
+                }
+            }
+        }
+
+        for referents in &value.same_credential_referents {
+            if referents.is_empty() {
+                return Err(invalid!(
+                    "Presentation request validation failed: there is empty group of same credential referents"
+                ));
+            }
+            for referent in referents {
+                if !value.requested_attributes.contains_key(referent)
+                    && !value.requested_predicates.contains_key(referent)
+                {
+                    return Err(invalid!(
+                        "Presentation request validation failed: same credential referents refer to unknown referent {}",
+                        referent
+                    ));
+                }
+            }
+        }
+
         Ok(())
     }
 }
@@ -418,4 +686,202 @@ mod tests {
         assert_eq!(int.from, narrow_int.from);
         assert_eq!(int.to, narrow_int.to);
     }
+
+    #[test]
+    fn predicate_info_date_helpers() {
+        let date = Date::new(2026, 10, 18).unwrap();
+
+        let predicate = PredicateInfo::age_at_least("birthdate", 18, date).unwrap();
+        assert_eq!(predicate.p_type, PredicateTypes::LE);
+        assert_eq!(predicate.p_value, 20081018);
+
+        let predicate = PredicateInfo::born_before("birthdate", date);
+        assert_eq!(predicate.p_type, PredicateTypes::LT);
+        assert_eq!(predicate.p_value, 20261018);
+
+        let predicate = PredicateInfo::expires_after("expiry", date);
+        assert_eq!(predicate.p_type, PredicateTypes::GT);
+        assert_eq!(predicate.p_value, 20261018);
+    }
+
+    #[test]
+    fn predicate_info_range_and_not_equal() {
+        let range = PredicateInfo::range("age", 18, 30);
+        assert_eq!(
+            range.crypto_predicates().unwrap(),
+            vec![(PredicateTypes::GE, 18), (PredicateTypes::LE, 30)]
+        );
+        assert!(range.is_satisfied_by(18));
+        assert!(range.is_satisfied_by(30));
+        assert!(!range.is_satisfied_by(31));
+
+        let not_equal = PredicateInfo::not_equal("age", 30);
+        assert!(not_equal.crypto_predicates().is_err());
+        assert!(not_equal.is_satisfied_by(29));
+        assert!(!not_equal.is_satisfied_by(30));
+        assert_eq!(
+            not_equal
+                .resolve_for_value(28)
+                .unwrap()
+                .crypto_predicates()
+                .unwrap(),
+            vec![(PredicateTypes::LT, 30)]
+        );
+        assert_eq!(
+            not_equal
+                .resolve_for_value(31)
+                .unwrap()
+                .crypto_predicates()
+                .unwrap(),
+            vec![(PredicateTypes::GT, 30)]
+        );
+        assert!(not_equal.resolve_for_value(30).is_err());
+
+        assert_eq!(
+            serde_json::to_value(&range).unwrap(),
+            json!({
+                "name": "age",
+                "p_type": "range",
+                "p_value": 18,
+                "p_value_max": 30,
+                "restrictions": null,
+                "non_revoked": null,
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(&not_equal).unwrap()["p_type"],
+            json!("!=")
+        );
+    }
+
+    #[test]
+    fn range_predicate_validation() {
+        let request = |predicate: Value| {
+            serde_json::from_value::<PresentationRequest>(json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "2.0",
+                "requested_predicates": {"predicate1_referent": predicate},
+            }))
+            .unwrap()
+        };
+
+        assert!(request(
+            json!({"name": "age", "p_type": "range", "p_value": 18, "p_value_max": 30})
+        )
+        .validate()
+        .is_ok());
+        assert!(
+            request(json!({"name": "age", "p_type": "range", "p_value": 18}))
+                .validate()
+                .is_err()
+        );
+        assert!(request(
+            json!({"name": "age", "p_type": "range", "p_value": 18, "p_value_max": 17})
+        )
+        .validate()
+        .is_err());
+        assert!(
+            request(json!({"name": "age", "p_type": ">=", "p_value": 18, "p_value_max": 30}))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn equal_attributes_validation() {
+        let request = |equal_attributes: Value| {
+            serde_json::from_value::<PresentationRequest>(json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "2.0",
+                "requested_attributes": {
+                    "attr1_referent": {"name": "name"},
+                    "attr2_referent": {"name": "sex"}
+                },
+                "equal_attributes": equal_attributes,
+            }))
+            .unwrap()
+        };
+        let equal = |name: &str, referents: Value| json!({"name": name, "referents": referents});
+
+        let req = request(json!([equal(
+            "national_id",
+            json!(["attr1_referent", "attr2_referent"])
+        )]));
+        req.validate().unwrap();
+        assert_eq!(
+            req.value().equal_attributes,
+            vec![EqualAttributes {
+                name: "national_id".to_string(),
+                referents: vec!["attr1_referent".to_string(), "attr2_referent".to_string()],
+            }]
+        );
+        assert_eq!(
+            req.value()
+                .equal_attribute_names(|referent| referent == "attr2_referent"),
+            vec!["national_id"]
+        );
+        assert!(req
+            .value()
+            .equal_attribute_names(|referent| referent == "attr3_referent")
+            .is_empty());
+        assert!(request(json!([])).validate().is_ok());
+        assert!(request(json!([equal("", json!(["attr1_referent"]))]))
+            .validate()
+            .is_err());
+        assert!(
+            request(json!([equal("master_secret", json!(["attr1_referent"]))]))
+                .validate()
+                .is_err()
+        );
+        assert!(request(json!([
+            equal("national_id", json!(["attr1_referent"])),
+            equal("national_id", json!(["attr2_referent"]))
+        ]))
+        .validate()
+        .is_err());
+        assert!(request(json!([equal("national_id", json!([]))]))
+            .validate()
+            .is_err());
+        assert!(
+            request(json!([equal("national_id", json!(["attr3_referent"]))]))
+                .validate()
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn same_credential_referents_validation() {
+        let request = |same_credential_referents: Value| {
+            serde_json::from_value::<PresentationRequest>(json!({
+                "nonce": "123456",
+                "name": "name",
+                "version": "2.0",
+                "requested_attributes": {
+                    "attr1_referent": {"name": "name"}
+                },
+                "requested_predicates": {
+                    "predicate1_referent": {"name": "age", "p_type": ">=", "p_value": 18}
+                },
+                "same_credential_referents": same_credential_referents,
+            }))
+            .unwrap()
+        };
+
+        let req = request(json!([["attr1_referent", "predicate1_referent"]]));
+        req.validate().unwrap();
+        assert_eq!(
+            req.value().same_credential_referents,
+            vec![vec![
+                "attr1_referent".to_string(),
+                "predicate1_referent".to_string()
+            ]]
+        );
+        assert!(request(json!([])).validate().is_ok());
+        assert!(request(json!([[]])).validate().is_err());
+        assert!(request(json!([["attr1_referent", "attr2_referent"]]))
+            .validate()
+            .is_err());
+    }
 }
@@ -3,7 +3,10 @@ use std::collections::HashMap;
 use crate::error::ValidationError;
 use crate::utils::validation::Validatable;
 
-use super::{cred_def::CredentialDefinitionId, rev_reg::RevocationRegistryId, schema::SchemaId};
+use super::{
+    cred_def::CredentialDefinitionId, pres_request::PredicateTypes, rev_reg::RevocationRegistryId,
+    schema::SchemaId,
+};
 
 #[derive(Debug, Deserialize, Serialize)]
 pub struct Presentation {
@@ -24,6 +27,29 @@ pub struct RequestedProof {
     pub unrevealed_attrs: HashMap<String, SubProofReferent>,
     #[serde(default)]
     pub predicates: HashMap<String, SubProofReferent>,
+    /// Comparison proven for each not-equal predicate, either `<` or `>` its value
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub not_equal_predicates: HashMap<String, PredicateTypes>,
+}
+
+impl RequestedProof {
+    /// The sub proof of the credential presented for a requested attribute or predicate
+    pub(crate) fn sub_proof_index(&self, referent: &str) -> Option<u32> {
+        self.revealed_attrs
+            .get(referent)
+            .map(|attr| attr.sub_proof_index)
+            .or_else(|| {
+                self.revealed_attr_groups
+                    .get(referent)
+                    .map(|group| group.sub_proof_index)
+            })
+            .or_else(|| {
+                self.unrevealed_attrs
+                    .get(referent)
+                    .or_else(|| self.predicates.get(referent))
+                    .map(|sub_proof| sub_proof.sub_proof_index)
+            })
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
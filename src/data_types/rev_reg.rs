@@ -10,7 +10,7 @@ pub struct RevocationRegistry {
     pub value: ursa::cl::RevocationRegistry,
 }
 
-#[derive(Clone, Copy, Debug, Serialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
 pub struct UrsaRevocationRegistry(Accumulator);
 
 impl TryFrom<&str> for UrsaRevocationRegistry {
@@ -0,0 +1,259 @@
+use std::collections::BTreeSet;
+
+use super::rev_reg_def::{RevocationRegistryDefinition, RevocationRegistryDefinitionId};
+use crate::error::{Result, ValidationError};
+use crate::invalid;
+
+/// Allocator of the revocation indices of the credentials issued with a revocation registry
+///
+/// The allocator is kept by the issuer next to the [`RevocationStatusList`] of the registry, and
+/// persisted along with it. It hands out each index once, from `1` to `max_cred_num - 1`: the
+/// indices a revocation status list of the registry holds. When the registry is full, the issuer
+/// rolls over to a new registry definition, either explicitly with
+/// [`roll_over`](Self::roll_over) or on demand with
+/// [`allocate_or_roll_over`](Self::allocate_or_roll_over).
+///
+/// [`RevocationStatusList`]: super::rev_status_list::RevocationStatusList
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", try_from = "AllocatorState")]
+pub struct RevocationRegistryAllocator {
+    rev_reg_def_id: RevocationRegistryDefinitionId,
+    max_cred_num: u32,
+    used: BTreeSet<u32>,
+    /// Registries which were rolled over from, the most recent one last
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    previous: Vec<RevocationRegistryDefinitionId>,
+}
+
+impl RevocationRegistryAllocator {
+    pub fn new(
+        rev_reg_def_id: impl TryInto<RevocationRegistryDefinitionId, Error = ValidationError>,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<Self> {
+        let rev_reg_def_id = rev_reg_def_id.try_into()?;
+        check_max_cred_num(&rev_reg_def_id, rev_reg_def)?;
+        Ok(Self {
+            rev_reg_def_id,
+            max_cred_num: rev_reg_def.value.max_cred_num,
+            used: BTreeSet::new(),
+            previous: Vec::new(),
+        })
+    }
+
+    /// The revocation registry definition the indices are allocated in
+    #[must_use]
+    pub const fn rev_reg_def_id(&self) -> &RevocationRegistryDefinitionId {
+        &self.rev_reg_def_id
+    }
+
+    /// The revocation registry definitions which were rolled over from, the most recent one last
+    #[must_use]
+    pub fn previous_rev_reg_def_ids(&self) -> &[RevocationRegistryDefinitionId] {
+        &self.previous
+    }
+
+    #[must_use]
+    pub fn is_used(&self, idx: u32) -> bool {
+        self.used.contains(&idx)
+    }
+
+    /// The number of indices which can still be allocated
+    #[must_use]
+    pub fn remaining(&self) -> u32 {
+        self.max_cred_num
+            .saturating_sub(1)
+            .saturating_sub(self.used.len() as u32)
+    }
+
+    /// Allocate the lowest free index of the registry
+    pub fn allocate(&mut self) -> Result<u32> {
+        let idx = (1..self.max_cred_num)
+            .find(|idx| !self.used.contains(idx))
+            .ok_or_else(|| {
+                err_msg!(
+                    RevocationRegistryFull,
+                    "No revocation index left in revocation registry {}",
+                    self.rev_reg_def_id
+                )
+            })?;
+        self.used.insert(idx);
+        Ok(idx)
+    }
+
+    /// Record an index allocated outside of the allocator, such as for the credentials issued
+    /// before it was used
+    pub fn mark_used(&mut self, idx: u32) -> Result<()> {
+        if idx == 0 || idx >= self.max_cred_num {
+            return Err(err_msg!(
+                InvalidUserRevocId,
+                "Revocation index {} is out of the range of revocation registry {}",
+                idx,
+                self.rev_reg_def_id
+            ));
+        }
+        if !self.used.insert(idx) {
+            return Err(err_msg!(
+                InvalidUserRevocId,
+                "Revocation index {} is already used in revocation registry {}",
+                idx,
+                self.rev_reg_def_id
+            ));
+        }
+        Ok(())
+    }
+
+    /// Hand out again an index whose credential was never issued
+    pub(crate) fn release(&mut self, idx: u32) {
+        self.used.remove(&idx);
+    }
+
+    /// Allocate the indices of a new revocation registry definition from now on
+    pub fn roll_over(
+        &mut self,
+        rev_reg_def_id: impl TryInto<RevocationRegistryDefinitionId, Error = ValidationError>,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<()> {
+        self.roll_over_to(rev_reg_def_id.try_into()?, rev_reg_def)
+    }
+
+    fn roll_over_to(
+        &mut self,
+        rev_reg_def_id: RevocationRegistryDefinitionId,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<()> {
+        if rev_reg_def_id == self.rev_reg_def_id || self.previous.contains(&rev_reg_def_id) {
+            return Err(err_msg!(
+                "Revocation registry {} was already allocated from",
+                rev_reg_def_id
+            ));
+        }
+        check_max_cred_num(&rev_reg_def_id, rev_reg_def)?;
+
+        let previous = std::mem::replace(&mut self.rev_reg_def_id, rev_reg_def_id);
+        self.previous.push(previous);
+        self.max_cred_num = rev_reg_def.value.max_cred_num;
+        self.used.clear();
+        Ok(())
+    }
+
+    /// Allocate an index, rolling over to the revocation registry definition created by
+    /// `create_registry` when the registry is full
+    ///
+    /// The registry definition the index is allocated in is returned along with the index.
+    pub fn allocate_or_roll_over<F>(
+        &mut self,
+        create_registry: F,
+    ) -> Result<(RevocationRegistryDefinitionId, u32)>
+    where
+        F: FnOnce() -> Result<(RevocationRegistryDefinitionId, RevocationRegistryDefinition)>,
+    {
+        if self.remaining() == 0 {
+            let (rev_reg_def_id, rev_reg_def) = create_registry()?;
+            self.roll_over_to(rev_reg_def_id, &rev_reg_def)?;
+        }
+        let idx = self.allocate()?;
+        Ok((self.rev_reg_def_id.clone(), idx))
+    }
+}
+
+/// The persisted state of an allocator, checked before it is allocated from
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct AllocatorState {
+    rev_reg_def_id: RevocationRegistryDefinitionId,
+    max_cred_num: u32,
+    used: BTreeSet<u32>,
+    #[serde(default)]
+    previous: Vec<RevocationRegistryDefinitionId>,
+}
+
+impl TryFrom<AllocatorState> for RevocationRegistryAllocator {
+    type Error = ValidationError;
+
+    fn try_from(state: AllocatorState) -> std::result::Result<Self, Self::Error> {
+        if state.max_cred_num < 2 {
+            return Err(invalid!(
+                "Revocation registry {} cannot hold any credential",
+                state.rev_reg_def_id
+            ));
+        }
+        if let Some(idx) = state
+            .used
+            .iter()
+            .find(|idx| **idx == 0 || **idx >= state.max_cred_num)
+        {
+            return Err(invalid!(
+                "Revocation index {} is out of the range of revocation registry {}",
+                idx,
+                state.rev_reg_def_id
+            ));
+        }
+        Ok(Self {
+            rev_reg_def_id: state.rev_reg_def_id,
+            max_cred_num: state.max_cred_num,
+            used: state.used,
+            previous: state.previous,
+        })
+    }
+}
+
+fn check_max_cred_num(
+    rev_reg_def_id: &RevocationRegistryDefinitionId,
+    rev_reg_def: &RevocationRegistryDefinition,
+) -> Result<()> {
+    if rev_reg_def.value.max_cred_num < 2 {
+        return Err(err_msg!(
+            "Revocation registry {} cannot hold any credential",
+            rev_reg_def_id
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn allocator_json(max_cred_num: u32, used: &[u32]) -> serde_json::Value {
+        serde_json::json!({
+            "revRegDefId": "revreg:government/reg1",
+            "maxCredNum": max_cred_num,
+            "used": used,
+        })
+    }
+
+    #[test]
+    fn test_deserialize_allocator() {
+        let allocator: RevocationRegistryAllocator =
+            serde_json::from_value(allocator_json(4, &[1, 3])).unwrap();
+        assert!(allocator.is_used(3));
+        assert_eq!(allocator.remaining(), 1);
+        assert_eq!(
+            serde_json::to_value(&allocator).unwrap(),
+            allocator_json(4, &[1, 3])
+        );
+    }
+
+    #[test]
+    fn test_deserialize_allocator_without_capacity() {
+        for max_cred_num in [0, 1] {
+            assert!(
+                serde_json::from_value::<RevocationRegistryAllocator>(allocator_json(
+                    max_cred_num,
+                    &[]
+                ))
+                .is_err()
+            );
+        }
+    }
+
+    #[test]
+    fn test_deserialize_allocator_with_index_out_of_range() {
+        for idx in [0, 4, 5] {
+            assert!(
+                serde_json::from_value::<RevocationRegistryAllocator>(allocator_json(4, &[1, idx]))
+                    .is_err()
+            );
+        }
+    }
+}
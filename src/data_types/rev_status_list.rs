@@ -20,6 +20,31 @@ pub struct RevocationStatusList {
     timestamp: Option<u64>,
 }
 
+/// Changes between two versions of a [`RevocationStatusList`].
+///
+/// Registries which publish revocation incrementally, like the legacy Indy ledger, only provide the
+/// indices issued and revoked since a previous accumulator. A delta can be applied to the previous
+/// list with [`RevocationStatusList::apply_delta`] or used directly by the prover to update its
+/// revocation state.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationStatusListDelta {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev_reg_def_id: Option<RevocationRegistryDefinitionId>,
+    #[serde(default)]
+    pub issued: BTreeSet<u32>,
+    #[serde(default)]
+    pub revoked: BTreeSet<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_accumulator: Option<UrsaRevocationRegistry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_accumulator: Option<UrsaRevocationRegistry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_timestamp: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_timestamp: Option<u64>,
+}
+
 impl TryFrom<&RevocationStatusList> for Option<ursa::cl::RevocationRegistry> {
     type Error = Error;
 
@@ -120,6 +145,106 @@ impl RevocationStatusList {
         Ok(())
     }
 
+    /// Compute the delta which turns this list into `other`.
+    pub fn diff(&self, other: &Self) -> Result<RevocationStatusListDelta> {
+        let rev_reg_def_id = match (&self.rev_reg_def_id, &other.rev_reg_def_id) {
+            (Some(id), Some(other_id)) if id != other_id => {
+                return Err(err_msg!(
+                    "Revocation status lists belong to different registries: {} and {}",
+                    id,
+                    other_id
+                ));
+            }
+            (id, other_id) => other_id.clone().or_else(|| id.clone()),
+        };
+        if self.revocation_list.len() != other.revocation_list.len() {
+            return Err(err_msg!(
+                "Revocation status lists have different sizes: {} and {}",
+                self.revocation_list.len(),
+                other.revocation_list.len()
+            ));
+        }
+
+        let mut issued = BTreeSet::new();
+        let mut revoked = BTreeSet::new();
+        let changed = self.revocation_list.clone() ^ other.revocation_list.clone();
+        for idx in changed.iter_ones() {
+            // true means the credential has been revoked
+            if other.revocation_list[idx] {
+                revoked.insert(idx as u32);
+            } else {
+                issued.insert(idx as u32);
+            }
+        }
+
+        Ok(RevocationStatusListDelta {
+            rev_reg_def_id,
+            issued,
+            revoked,
+            from_accumulator: self.registry,
+            to_accumulator: other.registry,
+            from_timestamp: self.timestamp,
+            to_timestamp: other.timestamp,
+        })
+    }
+
+    /// Apply a delta computed against this list, moving it to the delta's accumulator and
+    /// timestamp.
+    pub fn apply_delta(&mut self, delta: &RevocationStatusListDelta) -> Result<()> {
+        if let (Some(id), Some(delta_id)) = (&self.rev_reg_def_id, &delta.rev_reg_def_id) {
+            if id != delta_id {
+                return Err(err_msg!(
+                    "Revocation status list delta is for registry {}, not {}",
+                    delta_id,
+                    id
+                ));
+            }
+        }
+        if let (Some(registry), Some(from)) = (&self.registry, &delta.from_accumulator) {
+            if registry != from {
+                return Err(err_msg!(
+                    "Revocation status list delta does not start at the current accumulator"
+                ));
+            }
+        }
+        if let (Some(timestamp), Some(from)) = (self.timestamp, delta.from_timestamp) {
+            if timestamp != from {
+                return Err(err_msg!(
+                    "Revocation status list delta starts at timestamp {}, not {}",
+                    from,
+                    timestamp
+                ));
+            }
+        }
+        if let Some(idx) = delta.issued.intersection(&delta.revoked).next() {
+            return Err(err_msg!(
+                "Revocation status list delta both issues and revokes index {}",
+                idx
+            ));
+        }
+        if let Some(idx) = delta
+            .issued
+            .iter()
+            .chain(delta.revoked.iter())
+            .find(|&&idx| idx as usize >= self.revocation_list.len())
+        {
+            return Err(err_msg!(
+                "Revocation status list delta index {} is out of range",
+                idx
+            ));
+        }
+
+        if delta.to_accumulator.is_some() {
+            self.registry = delta.to_accumulator;
+        }
+        self.update(
+            None,
+            Some(delta.issued.clone()),
+            Some(delta.revoked.clone()),
+            delta.to_timestamp,
+        )
+    }
+
     pub fn new(
         rev_reg_def_id: Option<&str>,
         issuer_id: IssuerId,
@@ -242,6 +367,70 @@ mod rev_reg_tests {
         assert_eq!(ser, ser2)
     }
 
+    #[test]
+    fn rev_status_list_diff_and_apply_delta_works() {
+        let list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        let mut updated = list.clone();
+        updated
+            .update(None, Some(BTreeSet::from([0u32, 2])), None, Some(1245))
+            .unwrap();
+        let mut revoked = updated.clone();
+        revoked
+            .update(None, None, Some(BTreeSet::from([2u32])), Some(1300))
+            .unwrap();
+
+        let delta = list.diff(&updated).unwrap();
+        assert_eq!(delta.issued, BTreeSet::from([0u32, 2]));
+        assert!(delta.revoked.is_empty());
+        assert_eq!(delta.from_timestamp, Some(1234));
+        assert_eq!(delta.to_timestamp, Some(1245));
+
+        let delta = list.diff(&revoked).unwrap();
+        assert_eq!(delta.issued, BTreeSet::from([0u32]));
+        assert!(delta.revoked.is_empty());
+
+        let delta = updated.diff(&revoked).unwrap();
+        assert!(delta.issued.is_empty());
+        assert_eq!(delta.revoked, BTreeSet::from([2u32]));
+
+        let ser = serde_json::to_string(&delta).unwrap();
+        assert_eq!(
+            serde_json::from_str::<RevocationStatusListDelta>(&ser).unwrap(),
+            delta
+        );
+
+        let mut applied = updated.clone();
+        applied.apply_delta(&delta).unwrap();
+        assert_eq!(applied.state(), revoked.state());
+        assert_eq!(applied.timestamp(), Some(1300));
+
+        // the delta no longer applies once the list moved past its start
+        assert!(applied.apply_delta(&delta).is_err());
+    }
+
+    #[test]
+    fn rev_status_list_delta_rejects_invalid_indices() {
+        let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
+        let delta = RevocationStatusListDelta {
+            rev_reg_def_id: None,
+            issued: BTreeSet::from([4u32]),
+            revoked: BTreeSet::new(),
+            from_accumulator: None,
+            to_accumulator: None,
+            from_timestamp: None,
+            to_timestamp: None,
+        };
+        assert!(list.apply_delta(&delta).is_err());
+
+        let delta = RevocationStatusListDelta {
+            issued: BTreeSet::from([1u32]),
+            revoked: BTreeSet::from([1u32]),
+            ..delta
+        };
+        assert!(list.apply_delta(&delta).is_err());
+        assert_eq!(list.state(), &bitvec![1; 4]);
+    }
+
     #[test]
     fn update_rev_status_list_works() {
         let mut list = serde_json::from_str::<RevocationStatusList>(REVOCATION_LIST).unwrap();
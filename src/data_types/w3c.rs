@@ -0,0 +1,344 @@
+use std::collections::HashMap;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::error::{ConversionError, ValidationError};
+use crate::invalid;
+use crate::utils::validation::Validatable;
+
+use super::{
+    cred_def::CredentialDefinitionId,
+    credential::ShortCredentialValues,
+    issuer_id::IssuerId,
+    presentation::{Identifier, RequestedProof},
+    rev_reg::RevocationRegistryId,
+    schema::SchemaId,
+};
+
+pub const W3C_CONTEXT: &str = "https://www.w3.org/ns/credentials/v2";
+pub const ANONCREDS_CONTEXT: &str =
+    "https://raw.githubusercontent.com/hyperledger/anoncreds-spec/main/data/anoncreds-w3c-context.json";
+
+pub const W3C_CREDENTIAL_TYPE: &str = "VerifiableCredential";
+pub const ANONCREDS_CREDENTIAL_TYPE: &str = "AnonCredsCredential";
+pub const ANONCREDS_DEFINITION_TYPE: &str = "AnonCredsDefinition";
+pub const W3C_PRESENTATION_TYPE: &str = "VerifiablePresentation";
+pub const ANONCREDS_PRESENTATION_TYPE: &str = "AnonCredsPresentation";
+
+pub const DATA_INTEGRITY_PROOF_TYPE: &str = "DataIntegrityProof";
+pub const ANONCREDS_CRYPTOSUITE: &str = "AnonCredsProof2023";
+pub const ASSERTION_METHOD_PURPOSE: &str = "assertionMethod";
+pub const AUTHENTICATION_PURPOSE: &str = "authentication";
+
+/// AnonCreds credential expressed in the W3C Verifiable Credentials Data Model
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct W3CCredential {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    pub issuer: IssuerId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    pub credential_schema: CredentialSchemaReference,
+    pub credential_subject: ShortCredentialValues,
+    pub proof: DataIntegrityProof,
+}
+
+impl W3CCredential {
+    /// Validate everything but the attributes, which may all be withheld when the credential is
+    /// part of a presentation
+    pub(crate) fn validate_envelope(&self) -> Result<(), ValidationError> {
+        validate_context_and_types(
+            "W3C credential",
+            &self.context,
+            &self.types,
+            &[W3C_CREDENTIAL_TYPE, ANONCREDS_CREDENTIAL_TYPE],
+        )?;
+        self.issuer.validate()?;
+        self.credential_schema.validate()?;
+        self.proof.validate()?;
+        if self.proof.verification_method != self.credential_schema.definition {
+            return Err("W3C credential validation failed: proof `verificationMethod` does not match the credential definition".into());
+        }
+
+        Ok(())
+    }
+}
+
+impl Validatable for W3CCredential {
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.validate_envelope()?;
+        if self.credential_subject.is_empty() {
+            return Err("W3C credential validation failed: `credentialSubject` is empty".into());
+        }
+
+        Ok(())
+    }
+}
+
+/// AnonCreds presentation expressed in the W3C Verifiable Credentials Data Model
+///
+/// Each entry of `verifiableCredential` carries the sub-proof of one presented credential and
+/// its revealed attributes, while the presentation `proof` carries the aggregated proof.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct W3CPresentation {
+    #[serde(rename = "@context")]
+    pub context: Vec<String>,
+    #[serde(rename = "type")]
+    pub types: Vec<String>,
+    pub verifiable_credential: Vec<W3CCredential>,
+    pub proof: PresentationProof,
+}
+
+impl Validatable for W3CPresentation {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_context_and_types(
+            "W3C presentation",
+            &self.context,
+            &self.types,
+            &[W3C_PRESENTATION_TYPE, ANONCREDS_PRESENTATION_TYPE],
+        )?;
+        for credential in &self.verifiable_credential {
+            credential.validate_envelope()?;
+        }
+        self.proof.validate()?;
+
+        Ok(())
+    }
+}
+
+fn validate_context_and_types(
+    object: &str,
+    context: &[String],
+    types: &[String],
+    required_types: &[&str],
+) -> Result<(), ValidationError> {
+    if !context.iter().any(|c| c == W3C_CONTEXT) {
+        return Err(invalid!(
+            "{} validation failed: `@context` must include {}",
+            object,
+            W3C_CONTEXT
+        ));
+    }
+    for required in required_types {
+        if !types.iter().any(|t| t == required) {
+            return Err(invalid!(
+                "{} validation failed: `type` must include {}",
+                object,
+                required
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// The AnonCreds objects a W3C credential was issued against
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CredentialSchemaReference {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub definition: CredentialDefinitionId,
+    pub schema: SchemaId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revocation: Option<RevocationRegistryId>,
+}
+
+impl Validatable for CredentialSchemaReference {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.type_ != ANONCREDS_DEFINITION_TYPE {
+            return Err(invalid!(
+                "W3C credential validation failed: unsupported `credentialSchema` type {}",
+                self.type_
+            ));
+        }
+        self.definition.validate()?;
+        self.schema.validate()?;
+        self.revocation
+            .as_ref()
+            .map(Validatable::validate)
+            .transpose()?;
+
+        Ok(())
+    }
+}
+
+/// Data integrity proof carrying an encoded [`CredentialSignatureProof`]
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DataIntegrityProof {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub cryptosuite: String,
+    pub proof_purpose: String,
+    pub verification_method: CredentialDefinitionId,
+    pub proof_value: String,
+}
+
+impl DataIntegrityProof {
+    pub fn new_credential_proof(
+        verification_method: CredentialDefinitionId,
+        value: &CredentialSignatureProof,
+    ) -> Result<Self, ConversionError> {
+        Self::new(verification_method, encode_proof_value(value)?)
+    }
+
+    pub fn get_credential_proof(&self) -> Result<CredentialSignatureProof, ConversionError> {
+        decode_proof_value(&self.proof_value)
+    }
+
+    /// Proof of a credential presented within a [`W3CPresentation`]
+    pub fn new_sub_proof(
+        verification_method: CredentialDefinitionId,
+        value: &ursa::cl::SubProof,
+    ) -> Result<Self, ConversionError> {
+        Self::new(verification_method, encode_proof_value(value)?)
+    }
+
+    pub fn get_sub_proof(&self) -> Result<ursa::cl::SubProof, ConversionError> {
+        decode_proof_value(&self.proof_value)
+    }
+
+    fn new(
+        verification_method: CredentialDefinitionId,
+        proof_value: String,
+    ) -> Result<Self, ConversionError> {
+        Ok(Self {
+            type_: DATA_INTEGRITY_PROOF_TYPE.to_string(),
+            cryptosuite: ANONCREDS_CRYPTOSUITE.to_string(),
+            proof_purpose: ASSERTION_METHOD_PURPOSE.to_string(),
+            verification_method,
+            proof_value,
+        })
+    }
+}
+
+impl Validatable for DataIntegrityProof {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_proof_kind(
+            "W3C credential",
+            &self.type_,
+            &self.cryptosuite,
+            &self.proof_purpose,
+            ASSERTION_METHOD_PURPOSE,
+        )
+    }
+}
+
+/// Data integrity proof of a [`W3CPresentation`], carrying an encoded
+/// [`PresentationProofValue`]
+///
+/// The `challenge` is the nonce of the presentation request.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PresentationProof {
+    #[serde(rename = "type")]
+    pub type_: String,
+    pub cryptosuite: String,
+    pub proof_purpose: String,
+    pub challenge: String,
+    pub proof_value: String,
+}
+
+impl PresentationProof {
+    pub fn new(challenge: String, value: &PresentationProofValue) -> Result<Self, ConversionError> {
+        Ok(Self {
+            type_: DATA_INTEGRITY_PROOF_TYPE.to_string(),
+            cryptosuite: ANONCREDS_CRYPTOSUITE.to_string(),
+            proof_purpose: AUTHENTICATION_PURPOSE.to_string(),
+            challenge,
+            proof_value: encode_proof_value(value)?,
+        })
+    }
+
+    pub fn get_value(&self) -> Result<PresentationProofValue, ConversionError> {
+        decode_proof_value(&self.proof_value)
+    }
+}
+
+impl Validatable for PresentationProof {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_proof_kind(
+            "W3C presentation",
+            &self.type_,
+            &self.cryptosuite,
+            &self.proof_purpose,
+            AUTHENTICATION_PURPOSE,
+        )
+    }
+}
+
+fn validate_proof_kind(
+    object: &str,
+    type_: &str,
+    cryptosuite: &str,
+    proof_purpose: &str,
+    expected_purpose: &str,
+) -> Result<(), ValidationError> {
+    if type_ != DATA_INTEGRITY_PROOF_TYPE {
+        return Err(invalid!(
+            "{} validation failed: unsupported proof type {}",
+            object,
+            type_
+        ));
+    }
+    if cryptosuite != ANONCREDS_CRYPTOSUITE {
+        return Err(invalid!(
+            "{} validation failed: unsupported cryptosuite {}",
+            object,
+            cryptosuite
+        ));
+    }
+    if proof_purpose != expected_purpose {
+        return Err(invalid!(
+            "{} validation failed: unsupported proof purpose {}",
+            object,
+            proof_purpose
+        ));
+    }
+
+    Ok(())
+}
+
+fn encode_proof_value<T: Serialize>(value: &T) -> Result<String, ConversionError> {
+    let json = serde_json::to_vec(value)?;
+    Ok(URL_SAFE_NO_PAD.encode(json))
+}
+
+fn decode_proof_value<T: DeserializeOwned>(proof_value: &str) -> Result<T, ConversionError> {
+    let json = URL_SAFE_NO_PAD
+        .decode(proof_value)
+        .map_err(|e| ConversionError::from_msg_err("Invalid proof value", e))?;
+    Ok(serde_json::from_slice(&json)?)
+}
+
+/// Signature material of an AnonCreds credential, as carried by the `proofValue`
+///
+/// `encoded` only lists the attributes whose encoding differs from the default encoding of
+/// their raw value.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CredentialSignatureProof {
+    pub signature: ursa::cl::CredentialSignature,
+    pub signature_correctness_proof: ursa::cl::SignatureCorrectnessProof,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rev_reg: Option<ursa::cl::RevocationRegistry>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub witness: Option<ursa::cl::Witness>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub encoded: HashMap<String, String>,
+}
+
+/// Presentation level content of a [`W3CPresentation`]: the aggregated proof along with the
+/// requested proof and identifiers of the legacy presentation
+#[derive(Debug, Deserialize, Serialize)]
+pub struct PresentationProofValue {
+    pub aggregated_proof: ursa::cl::AggregatedProof,
+    pub requested_proof: RequestedProof,
+    pub identifiers: Vec<Identifier>,
+}
@@ -20,6 +20,7 @@ pub enum ErrorKind {
     InvalidUserRevocId,
     ProofRejected,
     RevocationRegistryFull,
+    InvalidTailsFile,
 }
 
 impl ErrorKind {
@@ -34,6 +35,7 @@ impl ErrorKind {
             Self::InvalidUserRevocId => "Invalid revocation accumulator index",
             Self::ProofRejected => "Proof rejected",
             Self::RevocationRegistryFull => "Revocation registry full",
+            Self::InvalidTailsFile => "Invalid tails file",
         }
     }
 }
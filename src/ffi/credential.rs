@@ -10,6 +10,7 @@ use crate::data_types::link_secret::LinkSecret;
 use crate::data_types::rev_reg::RevocationRegistryId;
 use crate::error::Result;
 use crate::services::{
+    encoding::parse_encoder,
     issuer::create_credential,
     prover::process_credential,
     tails::TailsFileReader,
@@ -150,23 +151,45 @@ pub extern "C" fn anoncreds_encode_credential_attributes(
     result_p: *mut *const c_char,
 ) -> ErrorCode {
     catch_error(|| {
-        let mut result = String::new();
-        for raw_val in attr_raw_values.as_slice() {
-            let enc_val = encode_credential_attribute(
-                raw_val
-                    .as_opt_str()
-                    .ok_or_else(|| err_msg!("Missing attribute raw value"))?,
-            )?;
-            if !result.is_empty() {
-                result.push(',');
-            }
-            result.push_str(enc_val.as_str());
-        }
+        let result = encode_attributes(attr_raw_values.as_slice(), &[])?;
+        unsafe { *result_p = rust_string_to_c(result) };
+        Ok(())
+    })
+}
+
+/// Encode the raw values with the encoder named at the same index of `attr_encoders`, as
+/// accepted by `parse_encoder`. Missing entries select the legacy encoding.
+#[no_mangle]
+pub extern "C" fn anoncreds_encode_credential_attributes_with_encoders(
+    attr_raw_values: FfiStrList,
+    attr_encoders: FfiStrList,
+    result_p: *mut *const c_char,
+) -> ErrorCode {
+    catch_error(|| {
+        let result = encode_attributes(attr_raw_values.as_slice(), attr_encoders.as_slice())?;
         unsafe { *result_p = rust_string_to_c(result) };
         Ok(())
     })
 }
 
+fn encode_attributes(attr_raw_values: &[FfiStr], attr_encoders: &[FfiStr]) -> Result<String> {
+    let mut result = String::new();
+    for (attr_idx, raw_val) in attr_raw_values.iter().enumerate() {
+        let raw_val = raw_val
+            .as_opt_str()
+            .ok_or_else(|| err_msg!("Missing attribute raw value"))?;
+        let enc_val = match attr_encoders.get(attr_idx).and_then(FfiStr::as_opt_str) {
+            Some(encoder) => parse_encoder(encoder)?.encode(raw_val)?,
+            None => encode_credential_attribute(raw_val)?,
+        };
+        if !result.is_empty() {
+            result.push(',');
+        }
+        result.push_str(enc_val.as_str());
+    }
+    Ok(result)
+}
+
 #[no_mangle]
 pub extern "C" fn anoncreds_process_credential(
     cred: ObjectHandle,
@@ -22,6 +22,7 @@ pub enum ErrorCode {
     InvalidUserRevocId = 6,
     ProofRejected = 7,
     RevocationRegistryFull = 8,
+    InvalidTailsFile = 9,
 }
 
 impl From<ErrorKind> for ErrorCode {
@@ -35,6 +36,7 @@ impl From<ErrorKind> for ErrorCode {
             ErrorKind::InvalidUserRevocId => Self::InvalidUserRevocId,
             ErrorKind::ProofRejected => Self::ProofRejected,
             ErrorKind::RevocationRegistryFull => Self::RevocationRegistryFull,
+            ErrorKind::InvalidTailsFile => Self::InvalidTailsFile,
         }
     }
 }
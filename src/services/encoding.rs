@@ -0,0 +1,370 @@
+use std::fmt::Debug;
+
+use crate::data_types::date::Date;
+use crate::error::Result;
+use crate::ursa::bn::BigNumber;
+use crate::utils::hash::SHA256;
+
+/// Scheme turning the raw value of a credential attribute into the decimal value which is
+/// signed, and which predicates compare
+///
+/// Encoders which preserve the order of their values let predicates be expressed over raw
+/// values with [`encode_predicate_value`](AttributeEncoder::encode_predicate_value).
+pub trait AttributeEncoder: Debug + Send + Sync {
+    /// Encode a raw attribute value
+    fn encode(&self, raw: &str) -> Result<String>;
+
+    /// Recover the raw value of an encoded attribute value, when the encoding is reversible
+    fn decode(&self, encoded: &str) -> Result<String>;
+
+    /// Encode a raw value as the `p_value` of a predicate
+    fn encode_predicate_value(&self, raw: &str) -> Result<i32> {
+        self.encode(raw)?.parse::<i32>().map_err(|_| {
+            err_msg!(
+                "Encoded value of \"{}\" is out of the range of predicates",
+                raw
+            )
+        })
+    }
+}
+
+/// The historical encoding: `i32` values are kept as is, anything else is hashed with SHA-256
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LegacyEncoder;
+
+impl AttributeEncoder for LegacyEncoder {
+    fn encode(&self, raw: &str) -> Result<String> {
+        if let Ok(val) = raw.parse::<i32>() {
+            Ok(val.to_string())
+        } else {
+            let digest = SHA256::digest(raw.as_bytes());
+            #[cfg(target_endian = "big")]
+            let digest = {
+                let mut d = digest;
+                d.reverse();
+                d
+            };
+            Ok(BigNumber::from_bytes(&digest)?.to_dec()?)
+        }
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        encoded
+            .parse::<i32>()
+            .map(|val| val.to_string())
+            .map_err(|_| err_msg!("Hashed attribute value cannot be decoded: {}", encoded))
+    }
+}
+
+/// Signed `i64` values, shifted by `offset` so that they encode to non-negative values
+///
+/// Predicates only compare encodings in the `i32` range: an offset of `2^30` lets them be used
+/// over the values from `-2^30` to `2^30 - 1`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct IntegerEncoder {
+    offset: i64,
+}
+
+impl IntegerEncoder {
+    #[must_use]
+    pub const fn new(offset: i64) -> Self {
+        Self { offset }
+    }
+
+    #[must_use]
+    pub const fn offset(&self) -> i64 {
+        self.offset
+    }
+}
+
+impl AttributeEncoder for IntegerEncoder {
+    fn encode(&self, raw: &str) -> Result<String> {
+        let value = raw
+            .parse::<i64>()
+            .map_err(|_| err_msg!("Invalid integer attribute value: {}", raw))?;
+        encode_offset(raw, i128::from(value), self.offset)
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        let value = decode_offset(encoded, self.offset)?;
+        i64::try_from(value)
+            .map(|value| value.to_string())
+            .map_err(|_| err_msg!("Invalid encoded integer attribute value: {}", encoded))
+    }
+}
+
+/// Decimal values with up to `scale` fractional digits, encoded as the integer
+/// `value * 10^scale` shifted by `offset`
+///
+/// Raw values are parsed exactly: a value with more fractional digits than `scale` is rejected
+/// rather than rounded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecimalEncoder {
+    scale: u32,
+    offset: i64,
+}
+
+impl DecimalEncoder {
+    pub const MAX_SCALE: u32 = 18;
+
+    pub fn new(scale: u32, offset: i64) -> Result<Self> {
+        if scale > Self::MAX_SCALE {
+            return Err(err_msg!(
+                "Decimal scale {} exceeds the maximum of {}",
+                scale,
+                Self::MAX_SCALE
+            ));
+        }
+        Ok(Self { scale, offset })
+    }
+
+    #[must_use]
+    pub const fn scale(&self) -> u32 {
+        self.scale
+    }
+
+    #[must_use]
+    pub const fn offset(&self) -> i64 {
+        self.offset
+    }
+}
+
+impl AttributeEncoder for DecimalEncoder {
+    fn encode(&self, raw: &str) -> Result<String> {
+        let invalid = || err_msg!("Invalid decimal attribute value: {}", raw);
+        let (negative, digits) = match raw.strip_prefix('-') {
+            Some(digits) => (true, digits),
+            None => (false, raw.strip_prefix('+').unwrap_or(raw)),
+        };
+        let (int_part, frac_part) = digits.split_once('.').unwrap_or((digits, ""));
+        if int_part.is_empty()
+            || !int_part.bytes().all(|b| b.is_ascii_digit())
+            || !frac_part.bytes().all(|b| b.is_ascii_digit())
+            || frac_part.len() > self.scale as usize
+            || digits.ends_with('.')
+        {
+            return Err(invalid());
+        }
+        let padded = format!(
+            "{}{:0<width$}",
+            int_part,
+            frac_part,
+            width = self.scale as usize
+        );
+        let magnitude = padded.parse::<i128>().map_err(|_| invalid())?;
+        let value = if negative { -magnitude } else { magnitude };
+        encode_offset(raw, value, self.offset)
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        let value = decode_offset(encoded, self.offset)?;
+        let sign = if value < 0 { "-" } else { "" };
+        let magnitude = value.unsigned_abs();
+        if self.scale == 0 {
+            return Ok(format!("{}{}", sign, magnitude));
+        }
+        let factor = 10u128.pow(self.scale);
+        Ok(format!(
+            "{}{}.{:0width$}",
+            sign,
+            magnitude / factor,
+            magnitude % factor,
+            width = self.scale as usize
+        ))
+    }
+}
+
+/// Boolean values `true` and `false`, encoded as `1` and `0`
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BooleanEncoder;
+
+impl AttributeEncoder for BooleanEncoder {
+    fn encode(&self, raw: &str) -> Result<String> {
+        match raw {
+            "true" => Ok("1".to_string()),
+            "false" => Ok("0".to_string()),
+            _ => Err(err_msg!("Invalid boolean attribute value: {}", raw)),
+        }
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        match encoded.trim_start_matches('0') {
+            "1" => Ok("true".to_string()),
+            "" => Ok("false".to_string()),
+            _ => Err(err_msg!(
+                "Invalid encoded boolean attribute value: {}",
+                encoded
+            )),
+        }
+    }
+}
+
+/// ISO `YYYY-MM-DD` dates, encoded as `YYYYMMDD` as done by [`Date`]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DateEncoder;
+
+impl AttributeEncoder for DateEncoder {
+    fn encode(&self, raw: &str) -> Result<String> {
+        Ok(raw.parse::<Date>()?.to_encoded().to_string())
+    }
+
+    fn decode(&self, encoded: &str) -> Result<String> {
+        let value = encoded
+            .parse::<i32>()
+            .map_err(|_| err_msg!("Invalid encoded date: {}", encoded))?;
+        Ok(Date::from_encoded(value)?.to_string())
+    }
+}
+
+/// Select a built-in encoder by name: `legacy`, `boolean`, `date`, `integer[:<offset>]` or
+/// `decimal:<scale>[:<offset>]`
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::encoding::parse_encoder;
+///
+/// let encoder = parse_encoder("integer:1000").expect("Unable to parse encoder");
+/// assert_eq!(encoder.encode("-10").unwrap(), "990");
+/// ```
+pub fn parse_encoder(name: &str) -> Result<Box<dyn AttributeEncoder>> {
+    let invalid = || err_msg!("Invalid attribute encoder: {}", name);
+    let mut parts = name.split(':');
+    let kind = parts.next().unwrap_or_default();
+    let args = parts
+        .map(|arg| arg.parse::<i64>().map_err(|_| invalid()))
+        .collect::<Result<Vec<_>>>()?;
+
+    let encoder: Box<dyn AttributeEncoder> = match (kind, args.as_slice()) {
+        ("legacy", []) => Box::new(LegacyEncoder),
+        ("boolean", []) => Box::new(BooleanEncoder),
+        ("date", []) => Box::new(DateEncoder),
+        ("integer", []) => Box::new(IntegerEncoder::default()),
+        ("integer", [offset]) => Box::new(IntegerEncoder::new(*offset)),
+        ("decimal", [scale]) | ("decimal", [scale, _]) => {
+            let scale = u32::try_from(*scale).map_err(|_| invalid())?;
+            Box::new(DecimalEncoder::new(
+                scale,
+                args.get(1).copied().unwrap_or(0),
+            )?)
+        }
+        _ => return Err(invalid()),
+    };
+    Ok(encoder)
+}
+
+fn encode_offset(raw: &str, value: i128, offset: i64) -> Result<String> {
+    let encoded = value + i128::from(offset);
+    if encoded < 0 {
+        return Err(err_msg!(
+            "Attribute value {} is below the encoding offset {}",
+            raw,
+            offset
+        ));
+    }
+    Ok(encoded.to_string())
+}
+
+fn decode_offset(encoded: &str, offset: i64) -> Result<i128> {
+    encoded
+        .parse::<i128>()
+        .ok()
+        .filter(|value| *value >= 0)
+        .map(|value| value - i128::from(offset))
+        .ok_or_else(|| err_msg!("Invalid encoded attribute value: {}", encoded))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_legacy_encoder() {
+        assert_eq!(LegacyEncoder.encode("42").unwrap(), "42");
+        assert_eq!(LegacyEncoder.decode("42").unwrap(), "42");
+        assert_eq!(
+            LegacyEncoder.encode("SLC").unwrap(),
+            "101327353979588246869873249766058188995681113722618593621043638294296500696424"
+        );
+        assert!(LegacyEncoder
+            .decode(
+                "101327353979588246869873249766058188995681113722618593621043638294296500696424"
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn test_integer_encoder() {
+        let encoder = IntegerEncoder::new(1 << 30);
+        assert_eq!(encoder.encode("-1073741824").unwrap(), "0");
+        assert_eq!(encoder.encode("-1").unwrap(), "1073741823");
+        assert_eq!(encoder.decode("1073741823").unwrap(), "-1");
+        assert_eq!(encoder.encode("9000000000").unwrap(), "10073741824");
+        assert!(encoder.encode("-1073741825").is_err());
+        assert!(encoder.encode("1.5").is_err());
+        assert!(encoder.encode_predicate_value("9000000000").is_err());
+        assert!(
+            encoder.encode_predicate_value("-5").unwrap()
+                < encoder.encode_predicate_value("3").unwrap()
+        );
+
+        assert!(IntegerEncoder::default().encode("-1").is_err());
+    }
+
+    #[test]
+    fn test_decimal_encoder() {
+        let encoder = DecimalEncoder::new(2, 100_000).unwrap();
+        assert_eq!(encoder.encode("12.5").unwrap(), "101250");
+        assert_eq!(encoder.encode("-12.34").unwrap(), "98766");
+        assert_eq!(encoder.encode("7").unwrap(), "100700");
+        assert_eq!(encoder.decode("101250").unwrap(), "12.50");
+        assert_eq!(encoder.decode("98766").unwrap(), "-12.34");
+        assert!(encoder.encode("1.234").is_err());
+        assert!(encoder.encode("1.").is_err());
+        assert!(encoder.encode(".5").is_err());
+        assert!(encoder.encode("1e3").is_err());
+
+        assert_eq!(
+            DecimalEncoder::new(0, 0).unwrap().decode("15").unwrap(),
+            "15"
+        );
+        assert!(DecimalEncoder::new(19, 0).is_err());
+    }
+
+    #[test]
+    fn test_boolean_and_date_encoders() {
+        assert_eq!(BooleanEncoder.encode("true").unwrap(), "1");
+        assert_eq!(BooleanEncoder.encode("false").unwrap(), "0");
+        assert_eq!(BooleanEncoder.decode("1").unwrap(), "true");
+        assert!(BooleanEncoder.encode("yes").is_err());
+
+        assert_eq!(DateEncoder.encode("2000-02-29").unwrap(), "20000229");
+        assert_eq!(DateEncoder.decode("20000229").unwrap(), "2000-02-29");
+        assert_eq!(
+            DateEncoder.encode_predicate_value("2000-02-29").unwrap(),
+            20000229
+        );
+        assert!(DateEncoder.encode("2001-02-29").is_err());
+    }
+
+    #[test]
+    fn test_parse_encoder() {
+        assert_eq!(parse_encoder("legacy").unwrap().encode("1").unwrap(), "1");
+        assert_eq!(parse_encoder("integer").unwrap().encode("3").unwrap(), "3");
+        assert_eq!(
+            parse_encoder("decimal:1:10")
+                .unwrap()
+                .encode("-0.5")
+                .unwrap(),
+            "5"
+        );
+        assert_eq!(
+            parse_encoder("date").unwrap().encode("2000-01-01").unwrap(),
+            "20000101"
+        );
+        assert!(parse_encoder("integer:x").is_err());
+        assert!(parse_encoder("decimal").is_err());
+        assert!(parse_encoder("decimal:-1").is_err());
+        assert!(parse_encoder("sha256").is_err());
+    }
+}
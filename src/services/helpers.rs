@@ -1,20 +1,24 @@
+use std::cmp::Ordering;
 use std::collections::HashMap;
 
 use crate::data_types::{
-    credential::AttributeValues,
+    cred_def::CredentialDefinition,
+    credential::{AttributeValues, Credential},
     nonce::Nonce,
-    pres_request::{AttributeInfo, NonRevokedInterval, PredicateInfo, PresentationRequestPayload},
+    pres_request::{
+        AttributeInfo, NonRevokedInterval, PredicateInfo, PredicateTypes,
+        PresentationRequestPayload,
+    },
     presentation::RequestedProof,
+    schema::Schema,
 };
-use crate::utils::hash::SHA256;
+use crate::utils::query::Query;
 
+use super::encoding::{AttributeEncoder, LegacyEncoder};
 use crate::error::Result;
-use crate::ursa::{
-    bn::BigNumber,
-    cl::{
-        issuer, verifier, CredentialSchema, CredentialValues as CryptoCredentialValues,
-        MasterSecret as CryptoMasterSecret, NonCredentialSchema, SubProofRequest,
-    },
+use crate::ursa::cl::{
+    issuer, verifier, CredentialSchema, CredentialValues as CryptoCredentialValues,
+    MasterSecret as CryptoMasterSecret, NonCredentialSchema, SubProofRequest,
 };
 
 pub fn attr_common_view(attr: &str) -> String {
@@ -74,28 +78,19 @@ pub fn build_credential_values(
 }
 
 pub fn encode_credential_attribute(raw_value: &str) -> Result<String> {
-    if let Ok(val) = raw_value.parse::<i32>() {
-        Ok(val.to_string())
-    } else {
-        let digest = SHA256::digest(raw_value.as_bytes());
-        #[cfg(target_endian = "big")]
-        let digest = {
-            let mut d = digest;
-            d.reverse();
-            d
-        };
-        Ok(BigNumber::from_bytes(&digest)?.to_dec()?)
-    }
+    LegacyEncoder.encode(raw_value)
 }
 
 pub fn build_sub_proof_request(
     attrs_for_credential: &[AttributeInfo],
     predicates_for_credential: &[PredicateInfo],
+    equal_attributes: &[String],
 ) -> Result<SubProofRequest> {
     trace!(
-        "build_sub_proof_request >>> attrs_for_credential: {:?}, predicates_for_credential: {:?}",
+        "build_sub_proof_request >>> attrs_for_credential: {:?}, predicates_for_credential: {:?}, equal_attributes: {:?}",
         attrs_for_credential,
-        predicates_for_credential
+        predicates_for_credential,
+        equal_attributes
     );
 
     let mut sub_proof_request_builder = verifier::Verifier::new_sub_proof_request_builder()?;
@@ -116,18 +111,19 @@ pub fn build_sub_proof_request(
         };
 
         for name in names {
+            check_not_equal_attribute(&name, equal_attributes)?;
             sub_proof_request_builder.add_revealed_attr(&attr_common_view(&name))?;
         }
     }
 
     for predicate in predicates_for_credential {
-        let p_type = format!("{}", predicate.p_type);
-
-        sub_proof_request_builder.add_predicate(
-            &attr_common_view(&predicate.name),
-            &p_type,
-            predicate.p_value,
-        )?;
+        for (p_type, p_value) in predicate.crypto_predicates()? {
+            sub_proof_request_builder.add_predicate(
+                &attr_common_view(&predicate.name),
+                &p_type.to_string(),
+                p_value,
+            )?;
+        }
     }
 
     let res = sub_proof_request_builder.finalize()?;
@@ -137,6 +133,20 @@ pub fn build_sub_proof_request(
     Ok(res)
 }
 
+/// Attributes proven equal across credentials are only compared through their hidden values
+pub fn check_not_equal_attribute(name: &str, equal_attributes: &[String]) -> Result<()> {
+    if equal_attributes
+        .iter()
+        .any(|equal| attr_common_view(equal) == attr_common_view(name))
+    {
+        return Err(err_msg!(
+            "Attribute \"{}\" is proven equal across credentials and cannot be revealed",
+            name
+        ));
+    }
+    Ok(())
+}
+
 pub fn new_nonce() -> Result<Nonce> {
     Nonce::new().map_err(err_map!(Unexpected))
 }
@@ -221,7 +231,15 @@ pub fn get_predicates_for_credential(
                     .contains_key(predicate_referent)
         })
         .map(|(predicate_referent, _)| {
-            let info = pres_req.requested_predicates[predicate_referent].clone();
+            let mut info = pres_req.requested_predicates[predicate_referent].clone();
+            // Only a strict comparison proves a not-equal predicate
+            if info.p_type == PredicateTypes::NE {
+                if let Some(p_type @ (PredicateTypes::LT | PredicateTypes::GT)) =
+                    requested_proof.not_equal_predicates.get(predicate_referent)
+                {
+                    info = info.resolve_not_equal(p_type.clone());
+                }
+            }
             if let Some(int) = &info.non_revoked {
                 match non_revoked_interval.as_mut() {
                     Some(ni) => {
@@ -242,3 +260,178 @@ pub fn get_predicates_for_credential(
 
     (predicates_for_credential, non_revoked_interval)
 }
+
+/// Build the tags a credential can be searched and restricted by, the tag names are the ones
+/// used in the `restrictions` of a presentation request
+pub fn build_credential_tags(
+    credential: &Credential,
+    schema: Option<&Schema>,
+    cred_def: Option<&CredentialDefinition>,
+) -> HashMap<String, String> {
+    let mut tags = HashMap::new();
+
+    tags.insert("schema_id".to_owned(), credential.schema_id.to_string());
+    tags.insert("cred_def_id".to_owned(), credential.cred_def_id.to_string());
+    if let Some(rev_reg_id) = &credential.rev_reg_id {
+        tags.insert("rev_reg_id".to_owned(), rev_reg_id.to_string());
+    }
+
+    if let Some(schema) = schema {
+        tags.insert("schema_name".to_owned(), schema.name.clone());
+        tags.insert("schema_version".to_owned(), schema.version.clone());
+        tags.insert("schema_issuer_id".to_owned(), schema.issuer_id.to_string());
+        // The legacy tags are only supported for legacy identifiers, as in the verifier
+        if schema.issuer_id.is_legacy_did_identifier() {
+            tags.insert("schema_issuer_did".to_owned(), schema.issuer_id.to_string());
+        }
+    }
+
+    if let Some(cred_def) = cred_def {
+        tags.insert("issuer_id".to_owned(), cred_def.issuer_id.to_string());
+        if cred_def.issuer_id.is_legacy_did_identifier() {
+            tags.insert("issuer_did".to_owned(), cred_def.issuer_id.to_string());
+        }
+    }
+
+    for (name, value) in &credential.values.0 {
+        let name = attr_common_view(name);
+        tags.insert(format!("attr::{name}::marker"), "1".to_owned());
+        tags.insert(format!("attr::{name}::value"), value.raw.clone());
+    }
+
+    tags
+}
+
+/// Evaluate a restriction query against the tags of a credential
+pub fn match_credential_tags(query: &Query, tags: &HashMap<String, String>) -> bool {
+    let tag = |name: &str| tags.get(&normalize_tag_name(name));
+
+    match query {
+        Query::And(queries) => queries.iter().all(|q| match_credential_tags(q, tags)),
+        Query::Or(queries) => queries.iter().any(|q| match_credential_tags(q, tags)),
+        Query::Not(query) => !match_credential_tags(query, tags),
+        Query::Eq(name, value) => tag(name) == Some(value),
+        Query::Neq(name, value) => tag(name) != Some(value),
+        Query::Gt(name, value) => {
+            tag(name).map_or(false, |t| compare_tag_values(t, value) == Ordering::Greater)
+        }
+        Query::Gte(name, value) => {
+            tag(name).map_or(false, |t| compare_tag_values(t, value) != Ordering::Less)
+        }
+        Query::Lt(name, value) => {
+            tag(name).map_or(false, |t| compare_tag_values(t, value) == Ordering::Less)
+        }
+        Query::Lte(name, value) => {
+            tag(name).map_or(false, |t| compare_tag_values(t, value) != Ordering::Greater)
+        }
+        Query::Like(name, pattern) => tag(name).map_or(false, |t| like_match(t, pattern)),
+        Query::In(name, values) => tag(name).map_or(false, |t| values.contains(t)),
+        Query::Exist(names) => names.iter().all(|name| tag(name).is_some()),
+    }
+}
+
+/// Attribute names in `attr::<name>::value` and `attr::<name>::marker` tags are compared in
+/// their common view, as attribute names are in the credential
+fn normalize_tag_name(name: &str) -> String {
+    match name
+        .strip_prefix("attr::")
+        .and_then(|rest| rest.rsplit_once("::"))
+    {
+        Some((attr, suffix)) => format!("attr::{}::{}", attr_common_view(attr), suffix),
+        None => name.to_owned(),
+    }
+}
+
+/// Compare two tag values numerically when both are integers and lexicographically otherwise
+pub fn compare_tag_values(left: &str, right: &str) -> Ordering {
+    match (left.parse::<i128>(), right.parse::<i128>()) {
+        (Ok(left), Ok(right)) => left.cmp(&right),
+        _ => left.cmp(right),
+    }
+}
+
+/// Match a value against an SQL `LIKE` pattern, where `%` matches any sequence of characters
+/// and `_` matches a single character
+pub fn like_match(value: &str, pattern: &str) -> bool {
+    let value: Vec<char> = value.chars().collect();
+    let pattern: Vec<char> = pattern.chars().collect();
+
+    // matches[j] is true when the processed part of the value matches pattern[..j]
+    let mut matches = vec![false; pattern.len() + 1];
+    matches[0] = true;
+    for j in 1..=pattern.len() {
+        matches[j] = matches[j - 1] && pattern[j - 1] == '%';
+    }
+
+    for c in value {
+        let mut previous = matches[0];
+        matches[0] = false;
+        for j in 1..=pattern.len() {
+            let current = matches[j];
+            matches[j] = match pattern[j - 1] {
+                '%' => matches[j - 1] || current,
+                '_' => previous,
+                p => previous && p == c,
+            };
+            previous = current;
+        }
+    }
+
+    matches[pattern.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_like_match() {
+        assert!(like_match("did:web:example.com", "did:web:%"));
+        assert!(like_match("did:web:example.com", "%example%"));
+        assert!(like_match("abc", "a_c"));
+        assert!(like_match("", "%"));
+        assert!(!like_match("abc", "a_"));
+        assert!(!like_match("did:key:z6Mk", "did:web:%"));
+    }
+
+    #[test]
+    fn test_compare_tag_values() {
+        assert_eq!(compare_tag_values("9", "10"), Ordering::Less);
+        assert_eq!(compare_tag_values("-5", "3"), Ordering::Less);
+        assert_eq!(compare_tag_values("b", "a"), Ordering::Greater);
+        assert_eq!(compare_tag_values("9", "10a"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_match_credential_tags() {
+        let tags: HashMap<String, String> = [
+            ("cred_def_id", "creddef:government"),
+            ("attr::firstname::marker", "1"),
+            ("attr::firstname::value", "Alex"),
+            ("attr::age::value", "28"),
+        ]
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let query = Query::And(vec![
+            Query::Eq("cred_def_id".to_owned(), "creddef:government".to_owned()),
+            Query::Eq("attr::First Name::value".to_owned(), "Alex".to_owned()),
+            Query::Gte("attr::age::value".to_owned(), "18".to_owned()),
+            Query::Exist(vec!["attr::firstname::marker".to_owned()]),
+        ]);
+        assert!(match_credential_tags(&query, &tags));
+
+        let query = Query::Or(vec![
+            Query::Eq("schema_id".to_owned(), "schema:government".to_owned()),
+            Query::Lt("attr::age::value".to_owned(), "18".to_owned()),
+        ]);
+        assert!(!match_credential_tags(&query, &tags));
+
+        let query = Query::Not(Box::new(Query::In(
+            "cred_def_id".to_owned(),
+            vec!["creddef:employee".to_owned()],
+        )));
+        assert!(match_credential_tags(&query, &tags));
+    }
+}
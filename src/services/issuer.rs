@@ -13,16 +13,19 @@ use crate::error::{Error, ErrorKind, Result, ValidationError};
 use crate::services::helpers::{
     build_credential_schema, build_credential_values, build_non_credential_schema,
 };
-use crate::types::{CredentialDefinitionConfig, CredentialRevocationConfig};
+use crate::types::{
+    CredentialBatchRevocationConfig, CredentialDefinitionConfig, CredentialRevocationConfig,
+};
 use crate::ursa::cl::{
     issuer::Issuer as CryptoIssuer, RevocationRegistryDelta as CryptoRevocationRegistryDelta,
-    Witness,
+    RevocationTailsAccessor, Tail, Witness,
 };
+use crate::ursa::errors::{UrsaCryptoError, UrsaCryptoErrorKind};
 use crate::utils::validation::Validatable;
 use bitvec::bitvec;
 use std::collections::{BTreeSet, HashSet};
 
-use super::tails::{TailsFileReader, TailsWriter};
+use super::tails::{TailsFileReader, TailsReader, TailsWriter};
 use super::types::{
     AttributeNames, Credential, CredentialDefinitionPrivate, CredentialKeyCorrectnessProof,
     CredentialOffer, CredentialRequest, CredentialValues, RegistryType,
@@ -158,6 +161,7 @@ where
             primary: credential_public_key.get_primary_key()?.try_clone()?,
             revocation: credential_public_key.get_revocation_key()?,
         },
+        rotated_from: None,
     };
 
     let cred_def_private = CredentialDefinitionPrivate {
@@ -176,6 +180,101 @@ where
     Ok((cred_def, cred_def_private, cred_key_proof))
 }
 
+/// Rotate the keys of a credential definition
+///
+/// The new credential definition is created for the same schema and issuer, with new keys and
+/// the tag of `cred_def` versioned: `<tag>-v2` for a credential definition which was never
+/// rotated, then `<tag>-v3` and so on. It links to `cred_def_id` through its `rotated_from`
+/// property, so that verifiers can accept credentials issued with any credential definition of
+/// the rotation chain (see
+/// [`credential_definition_chain`](crate::verifier::credential_definition_chain)).
+///
+/// `schema` must be the schema of `cred_def`. The new credential definition supports revocation
+/// if `cred_def` does.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, _cred_def_priv, _key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let (rotated_cred_def, _rotated_cred_def_priv, _rotated_key_correctness_proof) =
+///     issuer::rotate_credential_definition("did:web:xyz/resource/cred-def",
+///                                          &cred_def,
+///                                          &schema
+///                                          ).expect("Unable to rotate Credential Definition");
+///
+/// assert_eq!(rotated_cred_def.tag, "default-tag-v2");
+/// ```
+pub fn rotate_credential_definition<CI>(
+    cred_def_id: CI,
+    cred_def: &CredentialDefinition,
+    schema: &Schema,
+) -> Result<(
+    CredentialDefinition,
+    CredentialDefinitionPrivate,
+    CredentialKeyCorrectnessProof,
+)>
+where
+    CI: TryInto<CredentialDefinitionId, Error = ValidationError>,
+{
+    trace!(
+        "rotate_credential_definition >>> cred_def: {:?}, schema: {:?}",
+        cred_def,
+        schema
+    );
+    let cred_def_id = cred_def_id.try_into()?;
+
+    let (mut rotated_cred_def, cred_def_private, cred_key_proof) = create_credential_definition(
+        cred_def.schema_id.to_string(),
+        schema,
+        cred_def.issuer_id.to_string(),
+        &rotated_tag(&cred_def.tag),
+        cred_def.signature_type,
+        CredentialDefinitionConfig::new(cred_def.value.revocation.is_some()),
+    )?;
+    rotated_cred_def.rotated_from = Some(cred_def_id);
+
+    trace!(
+        "rotate_credential_definition <<< cred_def: {:?}, cred_def_private: {:?}, key_correctness_proof: {:?}",
+        rotated_cred_def,
+        secret!(&cred_def_private),
+        cred_key_proof
+    );
+
+    Ok((rotated_cred_def, cred_def_private, cred_key_proof))
+}
+
+/// The tag of a rotated credential definition: `<tag>-v<n + 1>` for a tag `<tag>-v<n>`, or
+/// `<tag>-v2` otherwise
+fn rotated_tag(tag: &str) -> String {
+    let version = tag
+        .rsplit_once("-v")
+        .filter(|(_, version)| version.bytes().all(|b| b.is_ascii_digit()))
+        .and_then(|(base, version)| Some((base, version.parse::<u32>().ok()?.checked_add(1)?)));
+    match version {
+        Some((base, version)) => format!("{base}-v{version}"),
+        None => format!("{tag}-v2"),
+    }
+}
+
 /// Create an Anoncreds revocation registry definition according to the [Anoncreds v1.0 -
 /// Revocation Registry
 /// Definition](https://hyperledger.github.io/anoncreds-spec/#issuer-create-and-publish-revocation-registry-objects).
@@ -297,6 +396,9 @@ where
 ///
 /// This object can be stored on a VDR, verifiable data registry, to be used later on.
 ///
+/// When `issuance_by_default` is set, the tails file at `tails_location` in `rev_reg_def` is read.
+/// Use [`create_revocation_status_list_with_tails_reader`] to read it from another source.
+///
 /// # Example
 ///
 /// ```rust
@@ -349,6 +451,28 @@ pub fn create_revocation_status_list(
     issuer_id: impl TryInto<IssuerId, Error = ValidationError>,
     timestamp: Option<u64>,
     issuance_by_default: bool,
+) -> Result<RevocationStatusList> {
+    create_revocation_status_list_with_tails_reader(
+        rev_reg_def_id,
+        rev_reg_def,
+        issuer_id,
+        timestamp,
+        issuance_by_default,
+        &TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location),
+    )
+}
+
+/// Create an Anoncreds Revocation Status List, reading the tails file through `tails_reader`
+///
+/// See [`create_revocation_status_list`] for the list itself. The tails are only read when
+/// `issuance_by_default` is set.
+pub fn create_revocation_status_list_with_tails_reader(
+    rev_reg_def_id: impl TryInto<RevocationRegistryDefinitionId, Error = ValidationError>,
+    rev_reg_def: &RevocationRegistryDefinition,
+    issuer_id: impl TryInto<IssuerId, Error = ValidationError>,
+    timestamp: Option<u64>,
+    issuance_by_default: bool,
+    tails_reader: &TailsReader,
 ) -> Result<RevocationStatusList> {
     let rev_reg = UrsaRevocationRegistry::try_from(ACCUM_NO_ISSUED)?;
     let max_cred_num = rev_reg_def.value.max_cred_num;
@@ -363,7 +487,7 @@ pub fn create_revocation_status_list(
     }
 
     let list = if issuance_by_default {
-        let tails_reader = TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location);
+        tails_reader.ensure_verified(rev_reg_def)?;
         let issued = (1..=max_cred_num).collect::<BTreeSet<_>>();
 
         CryptoIssuer::update_revocation_registry(
@@ -371,7 +495,7 @@ pub fn create_revocation_status_list(
             max_cred_num,
             issued,
             BTreeSet::new(),
-            &tails_reader,
+            tails_reader,
         )?;
         bitvec![0; max_cred_num as usize ]
     } else {
@@ -391,7 +515,6 @@ pub fn create_revocation_status_list(
 /// Revocation Status
 /// List](https://hyperledger.github.io/anoncreds-spec/#creating-the-initial-revocation-status-list-object).
 ///
-///
 /// # Example
 ///
 /// ```rust
@@ -455,6 +578,8 @@ pub fn update_revocation_status_list_timestamp_only(
 /// Update an Anoncreds Revocation Status List according to the [Anoncreds v1.0 - Revocation Status
 /// List](https://hyperledger.github.io/anoncreds-spec/#creating-the-initial-revocation-status-list-object).
 ///
+/// The tails file at `tails_location` in `rev_reg_def` is read. Use
+/// [`update_revocation_status_list_with_tails_reader`] to read it from another source.
 ///
 /// # Example
 ///
@@ -518,6 +643,27 @@ pub fn update_revocation_status_list(
     revoked: Option<BTreeSet<u32>>,
     rev_reg_def: &RevocationRegistryDefinition,
     current_list: &RevocationStatusList,
+) -> Result<RevocationStatusList> {
+    update_revocation_status_list_with_tails_reader(
+        timestamp,
+        issued,
+        revoked,
+        rev_reg_def,
+        current_list,
+        &TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location),
+    )
+}
+
+/// Update an Anoncreds Revocation Status List, reading the tails file through `tails_reader`
+///
+/// See [`update_revocation_status_list`] for the update itself.
+pub fn update_revocation_status_list_with_tails_reader(
+    timestamp: Option<u64>,
+    issued: Option<BTreeSet<u32>>,
+    revoked: Option<BTreeSet<u32>>,
+    rev_reg_def: &RevocationRegistryDefinition,
+    current_list: &RevocationStatusList,
+    tails_reader: &TailsReader,
 ) -> Result<RevocationStatusList> {
     let mut new_list = current_list.clone();
     let issued = issued.map(|i_list| {
@@ -541,7 +687,7 @@ pub fn update_revocation_status_list(
             "Require Accumulator Value to update Rev Status List",
         )
     })?;
-    let tails_reader = TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location);
+    tails_reader.ensure_verified(rev_reg_def)?;
     let max_cred_num = rev_reg_def.value.max_cred_num;
 
     CryptoIssuer::update_revocation_registry(
@@ -549,7 +695,7 @@ pub fn update_revocation_status_list(
         max_cred_num,
         issued.clone().unwrap_or_default(),
         revoked.clone().unwrap_or_default(),
-        &tails_reader,
+        tails_reader,
     )?;
     new_list.update(Some(rev_reg), issued, revoked, timestamp)?;
 
@@ -706,6 +852,9 @@ pub fn create_credential(
         if let (Some(revocation_config), Some(rev_status_list)) =
             (revocation_config, rev_status_list)
         {
+            revocation_config
+                .tails_reader
+                .ensure_verified(revocation_config.reg_def)?;
             let rev_reg_def = &revocation_config.reg_def.value;
             let rev_reg: Option<UrsaRevocationRegistry> = rev_status_list.into();
             let rev_reg = rev_reg.ok_or_else(|| {
@@ -740,7 +889,8 @@ pub fn create_credential(
             // `issuance_by_default`.
             let issuance_by_default = !status;
 
-            let (credential_signature, signature_correctness_proof, delta) =
+            // The returned delta only holds the credential being issued, when issued on demand
+            let (credential_signature, signature_correctness_proof, _) =
                 CryptoIssuer::sign_credential_with_revoc(
                     &cred_request.entropy()?,
                     &cred_request.blinded_ms,
@@ -758,25 +908,17 @@ pub fn create_credential(
                     &revocation_config.tails_reader,
                 )?;
 
-            let witness = {
-                // `delta` is None if `issuance_type == issuance_by_default`
-                // So in this case the delta goes from none to the new one,
-                // which is all issued (by default) and non is revoked
-                //
-                // Note: delta is actually never used but we keep it so it is inline with
-                // ursa::cl::Witness type
-                let rev_reg_delta = delta.unwrap_or_else(|| {
-                    let empty = HashSet::new();
-                    CryptoRevocationRegistryDelta::from_parts(None, &rev_reg, &empty, &empty)
-                });
-                Witness::new(
-                    revocation_config.registry_idx,
-                    rev_reg_def.max_cred_num,
-                    issuance_by_default,
-                    &rev_reg_delta,
-                    &revocation_config.tails_reader,
-                )?
-            };
+            let witness = Witness::new(
+                revocation_config.registry_idx,
+                rev_reg_def.max_cred_num,
+                issuance_by_default,
+                &witness_delta(
+                    rev_status_list.state(),
+                    &rev_reg,
+                    &[revocation_config.registry_idx],
+                ),
+                &revocation_config.tails_reader,
+            )?;
             (
                 credential_signature,
                 signature_correctness_proof,
@@ -816,12 +958,383 @@ pub fn create_credential(
     Ok(credential)
 }
 
+/// Create many Anoncreds credentials of the same credential definition at once, as
+/// [`create_credential`]
+///
+/// The credential definition public key is parsed once for the batch. For revocable
+/// credentials, each credential is assigned the next free revocation index of the registry's
+/// [`RevocationRegistryAllocator`](crate::types::RevocationRegistryAllocator), and the
+/// revocation status list is updated once with all the indices issued, instead of once per
+/// credential. The credentials are returned in the order of `issuances`, along with the updated
+/// revocation status list, which must replace the previous one. The revocation index of a
+/// credential is given by its signature.
+///
+/// The witness of each revocable credential still sums the tails of every credential issued in
+/// the registry, so the cost of a batch grows with both its size and the number of credentials
+/// issued in the registry. The tails are read into memory once for the batch, which holds
+/// `2 * max_cred_num + 1` tails at a time. With the `rayon` feature enabled, the signatures and
+/// the witnesses are computed in parallel.
+///
+/// If any credential cannot be created, no revocation index is kept by the allocator.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::prover;
+/// use anoncreds::types::MakeCredentialValues;
+///
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let link_secret =
+///     prover::create_link_secret().expect("Unable to create link secret");
+///
+/// let mut requests = Vec::new();
+/// let mut values = Vec::new();
+/// for name in ["john", "jane"] {
+///     let credential_offer =
+///         issuer::create_credential_offer("did:web:xyz/resource/schema",
+///                                         "did:web:xyz/resource/cred-def",
+///                                         &key_correctness_proof,
+///                                         ).expect("Unable to create Credential Offer");
+///     let (credential_request, _) =
+///         prover::create_credential_request(Some("entropy"),
+///                                           None,
+///                                           &cred_def,
+///                                           &link_secret,
+///                                           "my-secret-id",
+///                                           &credential_offer,
+///                                           ).expect("Unable to create credential request");
+///     let mut credential_values = MakeCredentialValues::default();
+///     credential_values.add_raw("name", name).expect("Unable to add credential value");
+///     credential_values.add_raw("age", "28").expect("Unable to add credential value");
+///     requests.push((credential_offer, credential_request));
+///     values.push(credential_values.into());
+/// }
+///
+/// let (credentials, _) =
+///     issuer::create_credentials_batch(&cred_def,
+///                                      &cred_def_priv,
+///                                      requests
+///                                          .iter()
+///                                          .zip(values)
+///                                          .map(|((offer, request), values)| (offer, request, values))
+///                                          .collect(),
+///                                      None
+///                                      ).expect("Unable to create credentials");
+/// assert_eq!(credentials.len(), 2);
+/// ```
+pub fn create_credentials_batch(
+    cred_def: &CredentialDefinition,
+    cred_def_private: &CredentialDefinitionPrivate,
+    issuances: Vec<(&CredentialOffer, &CredentialRequest, CredentialValues)>,
+    revocation_config: Option<CredentialBatchRevocationConfig>,
+) -> Result<(Vec<Credential>, Option<RevocationStatusList>)> {
+    trace!(
+        "create_credentials_batch >>> cred_def: {:?}, cred_def_private: {:?}, issuances: {}, revocation_config: {:?}",
+        cred_def,
+        secret!(&cred_def_private),
+        issuances.len(),
+        revocation_config,
+    );
+
+    let cred_public_key = cred_def.get_public_key().map_err(err_map!(
+        Unexpected,
+        "Error fetching public key from credential definition"
+    ))?;
+    let issuances = issuances
+        .into_iter()
+        .map(|(cred_offer, cred_request, cred_values)| {
+            let credential_values = build_credential_values(&cred_values.0, None)?;
+            Ok((cred_offer, cred_request, cred_values, credential_values))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let CredentialBatchRevocationConfig {
+        reg_def,
+        reg_def_private,
+        rev_status_list,
+        allocator,
+        tails_reader,
+    } = if let Some(revocation_config) = revocation_config {
+        revocation_config
+    } else {
+        let signatures = map_issuances(
+            &issuances,
+            |(cred_offer, cred_request, _, values)| -> Result<_> {
+                Ok(CryptoIssuer::sign_credential(
+                    &cred_request.entropy()?,
+                    &cred_request.blinded_ms,
+                    &cred_request.blinded_ms_correctness_proof,
+                    cred_offer.nonce.as_native(),
+                    cred_request.nonce.as_native(),
+                    values,
+                    &cred_public_key,
+                    &cred_def_private.value,
+                )?)
+            },
+        );
+        let credentials = issuances
+            .into_iter()
+            .zip(signatures)
+            .map(|((cred_offer, _, cred_values, _), signature)| {
+                let (signature, signature_correctness_proof) = signature?;
+                Ok(Credential {
+                    schema_id: cred_offer.schema_id.clone(),
+                    cred_def_id: cred_offer.cred_def_id.clone(),
+                    rev_reg_id: None,
+                    values: cred_values,
+                    signature,
+                    signature_correctness_proof,
+                    rev_reg: None,
+                    witness: None,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        trace!(
+            "create_credentials_batch <<< credentials: {:?}",
+            secret!(&credentials)
+        );
+        return Ok((credentials, None));
+    };
+    let rev_reg_def_id = allocator.rev_reg_def_id().clone();
+    if rev_status_list
+        .id()
+        .map_or(false, |list_id| list_id != rev_reg_def_id)
+    {
+        return Err(err_msg!(
+            "Revocation status list is not the one of revocation registry {}",
+            rev_reg_def_id
+        ));
+    }
+
+    let mut indices = Vec::with_capacity(issuances.len());
+    for _ in 0..issuances.len() {
+        match allocator.allocate() {
+            Ok(idx) => indices.push(idx),
+            Err(err) => {
+                indices.into_iter().for_each(|idx| allocator.release(idx));
+                return Err(err);
+            }
+        }
+    }
+
+    let issue = || -> Result<(Vec<Credential>, RevocationStatusList)> {
+        tails_reader.ensure_verified(reg_def)?;
+        let max_cred_num = reg_def.value.max_cred_num;
+        let rev_reg: Option<UrsaRevocationRegistry> = rev_status_list.into();
+        let rev_reg: ursa::cl::RevocationRegistry = rev_reg
+            .ok_or_else(|| {
+                err_msg!(
+                    Unexpected,
+                    "RevocationStatusList should have accumulator value"
+                )
+            })?
+            .try_into()?;
+        // As in `create_credential`, an index which is set in the status list is issued on
+        // demand, and one which is not is issued by default
+        let issuance_by_default = indices
+            .iter()
+            .map(|&idx| {
+                rev_status_list
+                    .get(idx as usize)
+                    .map(|status| !status)
+                    .ok_or_else(|| {
+                        err_msg!("Revocation status list does not have the index {}", idx)
+                    })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let items = issuances.iter().zip(&indices).collect::<Vec<_>>();
+        let signatures = map_issuances(
+            &items,
+            |((cred_offer, cred_request, _, values), &idx)| -> Result<_> {
+                // The credentials are added to the accumulator all at once afterwards, so the
+                // signatures are made as if issued by default, which leaves the accumulator and
+                // the tails untouched
+                let mut rev_reg = rev_reg.clone();
+                let (signature, correctness_proof, _) = CryptoIssuer::sign_credential_with_revoc(
+                    &cred_request.entropy()?,
+                    &cred_request.blinded_ms,
+                    &cred_request.blinded_ms_correctness_proof,
+                    cred_offer.nonce.as_native(),
+                    cred_request.nonce.as_native(),
+                    values,
+                    &cred_public_key,
+                    &cred_def_private.value,
+                    idx,
+                    max_cred_num,
+                    true,
+                    &mut rev_reg,
+                    &reg_def_private.value,
+                    &UnusedTails,
+                )?;
+                Ok((signature, correctness_proof))
+            },
+        );
+        let signatures = signatures.into_iter().collect::<Result<Vec<_>>>()?;
+
+        let new_list = update_revocation_status_list_with_tails_reader(
+            None,
+            Some(indices.iter().copied().collect()),
+            None,
+            reg_def,
+            rev_status_list,
+            &tails_reader,
+        )?;
+        let rev_reg: Option<UrsaRevocationRegistry> = (&new_list).into();
+        let rev_reg: ursa::cl::RevocationRegistry = rev_reg
+            .ok_or_else(|| {
+                err_msg!(
+                    Unexpected,
+                    "RevocationStatusList should have accumulator value"
+                )
+            })?
+            .try_into()?;
+        let rev_reg_id = RevocationRegistryId::new_unchecked(rev_reg_def_id.to_string());
+
+        // The witnesses sum the tails of every credential issued in the registry, which are read
+        // once for the batch and shared by all its witnesses
+        let delta = witness_delta(new_list.state(), &rev_reg, &indices);
+        let tails = tails_reader.load(reg_def)?;
+        let items = indices.iter().zip(issuance_by_default).collect::<Vec<_>>();
+        let witnesses = map_issuances(&items, |&(&idx, issuance_by_default)| -> Result<_> {
+            Ok(Witness::new(
+                idx,
+                max_cred_num,
+                issuance_by_default,
+                &delta,
+                &tails,
+            )?)
+        });
+
+        let credentials = issuances
+            .into_iter()
+            .zip(signatures)
+            .zip(witnesses)
+            .map(
+                |(
+                    ((cred_offer, _, cred_values, _), (signature, signature_correctness_proof)),
+                    witness,
+                )| {
+                    Ok(Credential {
+                        schema_id: cred_offer.schema_id.clone(),
+                        cred_def_id: cred_offer.cred_def_id.clone(),
+                        rev_reg_id: Some(rev_reg_id.clone()),
+                        values: cred_values,
+                        signature,
+                        signature_correctness_proof,
+                        rev_reg: Some(rev_reg.clone()),
+                        witness: Some(witness?),
+                    })
+                },
+            )
+            .collect::<Result<Vec<_>>>()?;
+        Ok((credentials, new_list))
+    };
+    let (credentials, rev_status_list) = match issue() {
+        Ok(issued) => issued,
+        Err(err) => {
+            indices.into_iter().for_each(|idx| allocator.release(idx));
+            return Err(err);
+        }
+    };
+
+    trace!(
+        "create_credentials_batch <<< credentials: {:?}, rev_status_list: {:?}",
+        secret!(&credentials),
+        rev_status_list
+    );
+
+    Ok((credentials, Some(rev_status_list)))
+}
+
+/// Build the revocation registry delta the witnesses of credentials are computed from
+///
+/// The delta holds every credential issued and not revoked in the revocation status list
+/// `state`, along with the credentials being issued at the `issuing` indices, so that the
+/// witnesses are valid for the accumulator even when other credentials were issued on demand or
+/// revoked before.
+fn witness_delta(
+    state: &bitvec::vec::BitVec,
+    rev_reg: &ursa::cl::RevocationRegistry,
+    issuing: &[u32],
+) -> CryptoRevocationRegistryDelta {
+    let issued = state
+        .iter_zeros()
+        .map(|i| i as u32)
+        .filter(|&i| i != 0)
+        .chain(issuing.iter().copied())
+        .collect::<HashSet<_>>();
+    let revoked = state
+        .iter_ones()
+        .map(|i| i as u32)
+        .filter(|i| *i != 0 && !issuing.contains(i))
+        .collect::<HashSet<_>>();
+    CryptoRevocationRegistryDelta::from_parts(None, rev_reg, &issued, &revoked)
+}
+
+/// Tails accessor of the signatures which do not update the accumulator
+struct UnusedTails;
+
+impl RevocationTailsAccessor for UnusedTails {
+    fn access_tail(
+        &self,
+        _tail_id: u32,
+        _accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), UrsaCryptoError> {
+        Err(UrsaCryptoError::from_msg(
+            UrsaCryptoErrorKind::InvalidState,
+            "Tails are not accessible when signing a batch of credentials",
+        ))
+    }
+}
+
+#[cfg(feature = "rayon")]
+fn map_issuances<T: Sync, R: Send>(items: &[T], f: impl Fn(&T) -> R + Send + Sync) -> Vec<R> {
+    use rayon::prelude::*;
+
+    items.par_iter().map(f).collect()
+}
+
+#[cfg(not(feature = "rayon"))]
+fn map_issuances<T, R>(items: &[T], f: impl Fn(&T) -> R) -> Vec<R> {
+    items.iter().map(f).collect()
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{services::helpers::encode_credential_attribute, tails::TailsFileWriter};
 
     use super::*;
 
+    #[test]
+    fn test_rotated_tag() {
+        assert_eq!(rotated_tag("default"), "default-v2");
+        assert_eq!(rotated_tag("default-v2"), "default-v3");
+        assert_eq!(rotated_tag("default-v9"), "default-v10");
+        assert_eq!(rotated_tag("default-vx"), "default-vx-v2");
+        assert_eq!(rotated_tag("default-v+2"), "default-v+2-v2");
+    }
+
     #[test]
     fn test_issuer_id_equal_in_revocation_registry_definiton_and_credential_definition(
     ) -> Result<()> {
@@ -1,10 +1,18 @@
 mod helpers;
 
+pub mod encoding;
 pub mod issuer;
+pub mod pres_exchange;
+pub mod pres_request_builder;
 pub mod prover;
+pub mod resolver;
+pub mod rev_reg_set;
+pub mod storage;
 pub mod tails;
 pub mod types;
 pub mod verifier;
+pub mod w3c;
+pub mod wallet;
 
 pub mod utils {
     pub use super::helpers::encode_credential_attribute;
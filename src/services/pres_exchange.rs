@@ -0,0 +1,589 @@
+use std::collections::{BTreeSet, HashMap};
+use std::convert::TryFrom;
+
+use serde_json::{Number, Value};
+
+use crate::data_types::nonce::Nonce;
+use crate::data_types::pres_exchange::{
+    DescriptorMapping, Field, InputDescriptor, PresentationDefinition, PresentationSubmission,
+    AC_VC_FORMAT, AC_VP_FORMAT,
+};
+use crate::data_types::pres_request::{
+    AttributeInfo, PredicateInfo, PredicateTypes, PresentationRequest, PresentationRequestPayload,
+};
+use crate::data_types::presentation::Presentation;
+use crate::error::Result;
+use crate::services::helpers::new_nonce;
+use crate::utils::query::Query;
+use crate::utils::validation::Validatable;
+
+/// Translate a DIF Presentation Exchange v2 presentation definition into a presentation request
+///
+/// Each input descriptor is translated independently:
+///
+/// - the fields targeting `$.credentialSubject.<name>` are requested together, as the `names`
+///   of a requested attribute whose referent is the descriptor id
+/// - the numeric filters of such a field (`minimum`, `maximum`, `exclusiveMinimum` and
+///   `exclusiveMaximum`) become predicates instead, with referents `<descriptor id>_predicate_<n>`
+/// - a `const` filter on `$.issuer`, `$.credentialSchema.definition` or
+///   `$.credentialSchema.schema` restricts the credentials to the given issuer, credential
+///   definition or schema, and a `const` filter on an attribute restricts its value
+/// - the attribute and predicate referents of the descriptor must be answered by the same
+///   credential
+///
+/// Optional fields are not requested.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::data_types::pres_exchange::PresentationDefinition;
+/// use anoncreds::pres_exchange;
+/// use anoncreds::verifier;
+///
+/// let definition: PresentationDefinition = serde_json::from_value(serde_json::json!({
+///     "id": "age-verification",
+///     "input_descriptors": [{
+///         "id": "driver_license",
+///         "constraints": {
+///             "fields": [
+///                 { "path": ["$.credentialSubject.name"] },
+///                 {
+///                     "path": ["$.credentialSubject.age"],
+///                     "filter": { "type": "number", "minimum": 18 }
+///                 },
+///                 {
+///                     "path": ["$.issuer"],
+///                     "filter": { "type": "string", "const": "did:web:xyz" }
+///                 }
+///             ]
+///         }
+///     }]
+/// })).expect("Unable to parse presentation definition");
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = pres_exchange::create_presentation_request(&definition, nonce)
+///     .expect("Unable to create presentation request");
+///
+/// let pres_request = pres_request.value();
+/// assert!(pres_request.requested_attributes.contains_key("driver_license"));
+/// assert!(pres_request.requested_predicates.contains_key("driver_license_predicate_0"));
+/// ```
+pub fn create_presentation_request(
+    definition: &PresentationDefinition,
+    nonce: Nonce,
+) -> Result<PresentationRequest> {
+    trace!(
+        "create_presentation_request >>> definition: {:?}, nonce: {:?}",
+        definition,
+        nonce
+    );
+
+    definition.validate()?;
+
+    let mut requested_attributes = HashMap::new();
+    let mut requested_predicates = HashMap::new();
+    let mut same_credential_referents = Vec::new();
+    for descriptor in &definition.input_descriptors {
+        let request = DescriptorRequest::translate(descriptor)?;
+        let referents = request
+            .attribute
+            .is_some()
+            .then(|| descriptor.id.clone())
+            .into_iter()
+            .chain(request.predicates.keys().cloned().collect::<BTreeSet<_>>())
+            .collect::<Vec<_>>();
+        if referents.len() > 1 {
+            same_credential_referents.push(referents);
+        }
+        if let Some(attribute) = request.attribute {
+            requested_attributes.insert(descriptor.id.clone(), attribute);
+        }
+        requested_predicates.extend(request.predicates);
+    }
+
+    let pres_request = PresentationRequest::PresentationRequestV2(PresentationRequestPayload {
+        nonce,
+        name: definition
+            .name
+            .clone()
+            .unwrap_or_else(|| definition.id.clone()),
+        version: "1.0".to_string(),
+        requested_attributes,
+        requested_predicates,
+        non_revoked: None,
+        equal_attributes: Vec::new(),
+        same_credential_referents,
+    });
+    pres_request.validate()?;
+
+    trace!(
+        "create_presentation_request <<< pres_request: {:?}",
+        pres_request
+    );
+
+    Ok(pres_request)
+}
+
+/// Describe how `presentation`, created for the request translated by
+/// [`create_presentation_request`], satisfies each input descriptor of `definition`
+///
+/// Each input descriptor is mapped to the identifier of the credential which satisfied it.
+pub fn create_presentation_submission(
+    definition: &PresentationDefinition,
+    presentation: &Presentation,
+) -> Result<PresentationSubmission> {
+    trace!(
+        "create_presentation_submission >>> definition: {:?}, presentation: {:?}",
+        definition,
+        presentation
+    );
+
+    definition.validate()?;
+
+    let requested_proof = &presentation.requested_proof;
+    let mut descriptor_map = Vec::with_capacity(definition.input_descriptors.len());
+    for descriptor in &definition.input_descriptors {
+        let request = DescriptorRequest::translate(descriptor)?;
+
+        let mut sub_proof_indexes = BTreeSet::new();
+        if request.attribute.is_some() {
+            let group = requested_proof
+                .revealed_attr_groups
+                .get(&descriptor.id)
+                .ok_or_else(|| {
+                    err_msg!(
+                        "Input descriptor {} is not satisfied by the presentation",
+                        descriptor.id
+                    )
+                })?;
+            sub_proof_indexes.insert(group.sub_proof_index);
+        }
+        for referent in request.predicates.keys() {
+            let predicate = requested_proof.predicates.get(referent).ok_or_else(|| {
+                err_msg!(
+                    "Input descriptor {} is not satisfied by the presentation",
+                    descriptor.id
+                )
+            })?;
+            sub_proof_indexes.insert(predicate.sub_proof_index);
+        }
+
+        let sub_proof_index = match sub_proof_indexes.len() {
+            1 => *sub_proof_indexes.iter().next().unwrap(),
+            _ => {
+                return Err(err_msg!(
+                    "Input descriptor {} must be satisfied by a single credential",
+                    descriptor.id
+                ))
+            }
+        };
+        if sub_proof_index as usize >= presentation.identifiers.len() {
+            return Err(err_msg!(
+                "Invalid sub-proof index for input descriptor: {}",
+                descriptor.id
+            ));
+        }
+
+        descriptor_map.push(DescriptorMapping {
+            id: descriptor.id.clone(),
+            format: AC_VP_FORMAT.to_string(),
+            path: "$".to_string(),
+            path_nested: Some(Box::new(DescriptorMapping {
+                id: descriptor.id.clone(),
+                format: AC_VC_FORMAT.to_string(),
+                path: format!("$.identifiers[{}]", sub_proof_index),
+                path_nested: None,
+            })),
+        });
+    }
+
+    let submission = PresentationSubmission {
+        id: new_nonce()?.to_string(),
+        definition_id: definition.id.clone(),
+        descriptor_map,
+    };
+
+    trace!(
+        "create_presentation_submission <<< submission: {:?}",
+        submission
+    );
+
+    Ok(submission)
+}
+
+/// The requested attribute and predicates translated from an input descriptor
+#[derive(Debug)]
+struct DescriptorRequest {
+    attribute: Option<AttributeInfo>,
+    predicates: HashMap<String, PredicateInfo>,
+}
+
+impl DescriptorRequest {
+    fn translate(descriptor: &InputDescriptor) -> Result<Self> {
+        let mut names = Vec::new();
+        let mut predicates = Vec::new();
+        let mut restrictions = Vec::new();
+
+        for field in descriptor
+            .constraints
+            .fields
+            .iter()
+            .filter(|field| !field.optional)
+        {
+            let target = field
+                .path
+                .iter()
+                .find_map(|path| FieldTarget::parse(path))
+                .ok_or_else(|| {
+                    err_msg!(
+                        "Unsupported field path in input descriptor {}: {:?}",
+                        descriptor.id,
+                        field.path
+                    )
+                })?;
+            let filter = field.filter.clone().unwrap_or_default();
+            if let Some(keyword) = filter.other.keys().next() {
+                return Err(err_msg!(
+                    "Unsupported filter keyword in input descriptor {}: {}",
+                    descriptor.id,
+                    keyword
+                ));
+            }
+            let const_ = filter.const_.as_ref().map(const_value).transpose()?;
+
+            match target {
+                FieldTarget::Tag(tag) => {
+                    let value = const_.ok_or_else(|| {
+                        err_msg!(
+                            "Field {:?} of input descriptor {} requires a `const` filter",
+                            field.path,
+                            descriptor.id
+                        )
+                    })?;
+                    if has_bounds(field) {
+                        return Err(err_msg!(
+                            "Field {:?} of input descriptor {} only supports a `const` filter",
+                            field.path,
+                            descriptor.id
+                        ));
+                    }
+                    restrictions.push(Query::Eq(tag.to_string(), value));
+                }
+                FieldTarget::Attribute(name) => {
+                    let bounds = [
+                        (&filter.minimum, PredicateTypes::GE),
+                        (&filter.exclusive_minimum, PredicateTypes::GT),
+                        (&filter.maximum, PredicateTypes::LE),
+                        (&filter.exclusive_maximum, PredicateTypes::LT),
+                    ];
+                    for (bound, p_type) in bounds {
+                        if let Some(bound) = bound {
+                            predicates.push((name.clone(), p_type, predicate_value(bound)?));
+                        }
+                    }
+                    if let Some(value) = const_ {
+                        restrictions.push(Query::Eq(format!("attr::{}::value", name), value));
+                    }
+                    // Attributes are revealed unless only compared to bounds
+                    if (!has_bounds(field) || filter.const_.is_some()) && !names.contains(&name) {
+                        names.push(name);
+                    }
+                }
+            }
+        }
+
+        if names.is_empty() && predicates.is_empty() {
+            return Err(err_msg!(
+                "Input descriptor {} does not request any attribute",
+                descriptor.id
+            ));
+        }
+
+        let restrictions = match restrictions.len() {
+            0 => None,
+            1 => restrictions.pop(),
+            _ => Some(Query::And(restrictions)),
+        };
+        let attribute = (!names.is_empty()).then(|| AttributeInfo {
+            name: None,
+            names: Some(names),
+            restrictions: restrictions.clone(),
+            non_revoked: None,
+        });
+        let predicates = predicates
+            .into_iter()
+            .enumerate()
+            .map(|(idx, (name, p_type, p_value))| {
+                (
+                    format!("{}_predicate_{}", descriptor.id, idx),
+                    PredicateInfo {
+                        name,
+                        p_type,
+                        p_value,
+                        p_value_max: None,
+                        restrictions: restrictions.clone(),
+                        non_revoked: None,
+                    },
+                )
+            })
+            .collect();
+
+        Ok(Self {
+            attribute,
+            predicates,
+        })
+    }
+}
+
+/// What the path of a field refers to
+#[derive(Debug, PartialEq, Eq)]
+enum FieldTarget {
+    /// A credential attribute
+    Attribute(String),
+    /// A credential tag usable in restrictions
+    Tag(&'static str),
+}
+
+impl FieldTarget {
+    fn parse(path: &str) -> Option<Self> {
+        match path {
+            "$.issuer" | "$.issuer.id" => Some(Self::Tag("issuer_id")),
+            "$.credentialSchema.definition" | "$.cred_def_id" => Some(Self::Tag("cred_def_id")),
+            "$.credentialSchema.schema" | "$.schema_id" => Some(Self::Tag("schema_id")),
+            _ => {
+                let rest = path.strip_prefix("$.credentialSubject")?;
+                let name = match rest.strip_prefix('.') {
+                    Some(name) => name,
+                    None => rest
+                        .strip_prefix("['")
+                        .and_then(|name| name.strip_suffix("']"))
+                        .or_else(|| {
+                            rest.strip_prefix("[\"")
+                                .and_then(|name| name.strip_suffix("\"]"))
+                        })?,
+                };
+                if name.is_empty() || name.contains(['.', '[', ']'].as_ref()) {
+                    return None;
+                }
+                Some(Self::Attribute(name.to_string()))
+            }
+        }
+    }
+}
+
+fn has_bounds(field: &Field) -> bool {
+    field.filter.as_ref().map_or(false, |filter| {
+        filter.minimum.is_some()
+            || filter.maximum.is_some()
+            || filter.exclusive_minimum.is_some()
+            || filter.exclusive_maximum.is_some()
+    })
+}
+
+fn const_value(value: &Value) -> Result<String> {
+    match value {
+        Value::String(value) => Ok(value.clone()),
+        Value::Number(value) => Ok(value.to_string()),
+        _ => Err(err_msg!("Unsupported `const` filter value: {}", value)),
+    }
+}
+
+fn predicate_value(bound: &Number) -> Result<i32> {
+    bound
+        .as_i64()
+        .and_then(|bound| i32::try_from(bound).ok())
+        .ok_or_else(|| err_msg!("Unsupported numeric filter value: {}", bound))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::data_types::presentation::{
+        Identifier, RequestedProof, RevealedAttributeGroupInfo, SubProofReferent,
+    };
+
+    fn _definition() -> PresentationDefinition {
+        serde_json::from_value(json!({
+            "id": "definition",
+            "input_descriptors": [{
+                "id": "gvt",
+                "constraints": {
+                    "fields": [
+                        { "path": ["$.credentialSubject.name"] },
+                        { "path": ["$.credentialSubject['sex']"], "filter": { "const": "male" } },
+                        {
+                            "path": ["$.credentialSubject.age"],
+                            "filter": { "type": "number", "minimum": 18, "exclusiveMaximum": 65 }
+                        },
+                        {
+                            "path": ["$.credentialSchema.definition"],
+                            "filter": { "type": "string", "const": "did:web:xyz/resource/cred-def" }
+                        },
+                        { "path": ["$.credentialSubject.height"], "optional": true }
+                    ]
+                }
+            }]
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_field_target_parse() {
+        assert_eq!(
+            FieldTarget::parse("$.credentialSubject.name"),
+            Some(FieldTarget::Attribute("name".to_string()))
+        );
+        assert_eq!(
+            FieldTarget::parse("$.credentialSubject[\"first name\"]"),
+            Some(FieldTarget::Attribute("first name".to_string()))
+        );
+        assert_eq!(
+            FieldTarget::parse("$.issuer"),
+            Some(FieldTarget::Tag("issuer_id"))
+        );
+        assert_eq!(FieldTarget::parse("$.credentialSubject.address.city"), None);
+        assert_eq!(FieldTarget::parse("$.type"), None);
+    }
+
+    #[test]
+    fn test_create_presentation_request() {
+        let nonce = Nonce::new().unwrap();
+        let pres_request = create_presentation_request(&_definition(), nonce).unwrap();
+        let pres_request = pres_request.value();
+
+        let restrictions = Some(Query::And(vec![
+            Query::Eq("attr::sex::value".to_string(), "male".to_string()),
+            Query::Eq(
+                "cred_def_id".to_string(),
+                "did:web:xyz/resource/cred-def".to_string(),
+            ),
+        ]));
+        assert_eq!(pres_request.name, "definition");
+        assert_eq!(
+            pres_request.requested_attributes["gvt"],
+            AttributeInfo {
+                name: None,
+                names: Some(vec!["name".to_string(), "sex".to_string()]),
+                restrictions: restrictions.clone(),
+                non_revoked: None,
+            }
+        );
+        assert_eq!(
+            pres_request.requested_predicates["gvt_predicate_0"],
+            PredicateInfo {
+                name: "age".to_string(),
+                p_type: PredicateTypes::GE,
+                p_value: 18,
+                p_value_max: None,
+                restrictions: restrictions.clone(),
+                non_revoked: None,
+            }
+        );
+        assert_eq!(
+            pres_request.requested_predicates["gvt_predicate_1"].p_type,
+            PredicateTypes::LT
+        );
+        assert_eq!(pres_request.requested_predicates.len(), 2);
+        assert_eq!(
+            pres_request.same_credential_referents,
+            vec![vec![
+                "gvt".to_string(),
+                "gvt_predicate_0".to_string(),
+                "gvt_predicate_1".to_string()
+            ]]
+        );
+    }
+
+    #[test]
+    fn test_create_presentation_request_rejects_unsupported_fields() {
+        let mut definition = _definition();
+        definition.input_descriptors[0].constraints.fields[0]
+            .filter
+            .get_or_insert_with(Default::default)
+            .other
+            .insert("pattern".to_string(), json!("^A"));
+        assert!(create_presentation_request(&definition, Nonce::new().unwrap()).is_err());
+
+        let mut definition = _definition();
+        definition.input_descriptors[0].constraints.fields[2]
+            .filter
+            .as_mut()
+            .unwrap()
+            .minimum = Some(Number::from_f64(17.5).unwrap());
+        assert!(create_presentation_request(&definition, Nonce::new().unwrap()).is_err());
+
+        let mut definition = _definition();
+        definition.input_descriptors[0].constraints.fields[0].path =
+            vec!["$.credentialSubject.address.city".to_string()];
+        assert!(create_presentation_request(&definition, Nonce::new().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_create_presentation_submission() {
+        let definition = _definition();
+        let mut requested_proof = RequestedProof::default();
+        requested_proof.revealed_attr_groups.insert(
+            "gvt".to_string(),
+            RevealedAttributeGroupInfo {
+                sub_proof_index: 0,
+                values: HashMap::new(),
+            },
+        );
+        for referent in ["gvt_predicate_0", "gvt_predicate_1"] {
+            requested_proof.predicates.insert(
+                referent.to_string(),
+                SubProofReferent { sub_proof_index: 0 },
+            );
+        }
+        let identifiers = vec![Identifier {
+            schema_id: "did:web:xyz/resource/schema".try_into().unwrap(),
+            cred_def_id: "did:web:xyz/resource/cred-def".try_into().unwrap(),
+            rev_reg_id: None,
+            timestamp: None,
+        }];
+
+        let submission =
+            submission_for(&definition, requested_proof.clone(), identifiers.clone()).unwrap();
+        assert_eq!(submission.definition_id, "definition");
+        assert_eq!(submission.descriptor_map.len(), 1);
+        let mapping = &submission.descriptor_map[0];
+        assert_eq!(mapping.id, "gvt");
+        assert_eq!(mapping.format, AC_VP_FORMAT);
+        assert_eq!(
+            mapping.path_nested.as_ref().unwrap().path,
+            "$.identifiers[0]"
+        );
+
+        // All the referents of a descriptor must be satisfied by the same credential
+        requested_proof.predicates.insert(
+            "gvt_predicate_1".to_string(),
+            SubProofReferent { sub_proof_index: 1 },
+        );
+        assert!(submission_for(&definition, requested_proof.clone(), identifiers.clone()).is_err());
+
+        requested_proof.predicates.remove("gvt_predicate_1");
+        assert!(submission_for(&definition, requested_proof, identifiers).is_err());
+    }
+
+    fn submission_for(
+        definition: &PresentationDefinition,
+        requested_proof: RequestedProof,
+        identifiers: Vec<Identifier>,
+    ) -> Result<PresentationSubmission> {
+        // The proof itself is not inspected when mapping the descriptors
+        let proof = serde_json::from_value(json!({
+            "proofs": [],
+            "aggregated_proof": { "c_hash": "1", "c_list": [] }
+        }))
+        .unwrap();
+        create_presentation_submission(
+            definition,
+            &Presentation {
+                proof,
+                requested_proof,
+                identifiers,
+            },
+        )
+    }
+}
@@ -0,0 +1,392 @@
+use super::types::PresentationRequest;
+use super::verifier::generate_nonce;
+use crate::data_types::cred_def::CredentialDefinitionId;
+use crate::data_types::issuer_id::IssuerId;
+use crate::data_types::pres_request::{
+    AttributeInfo, EqualAttributes, NonRevokedInterval, PredicateInfo, PresentationRequestPayload,
+    PresentationRequestVersion,
+};
+use crate::data_types::schema::SchemaId;
+use crate::error::Result;
+use crate::utils::query::Query;
+use crate::utils::validation::Validatable;
+use std::collections::HashMap;
+
+/// Restrictions on the credentials which may satisfy a requested attribute or predicate
+///
+/// All the restrictions must hold, no restriction at all accepts any credential.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Restrictions(Vec<Query>);
+
+impl Restrictions {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Issued with the credential definition `cred_def_id`
+    #[must_use]
+    pub fn from_cred_def(self, cred_def_id: &CredentialDefinitionId) -> Self {
+        self.with(Query::Eq("cred_def_id".to_owned(), cred_def_id.to_string()))
+    }
+
+    /// Issued with any of the credential definitions `cred_def_ids`, such as the credential
+    /// definitions of a rotation chain
+    #[must_use]
+    pub fn from_any_cred_def(self, cred_def_ids: &[CredentialDefinitionId]) -> Self {
+        self.with(Query::In(
+            "cred_def_id".to_owned(),
+            cred_def_ids.iter().map(ToString::to_string).collect(),
+        ))
+    }
+
+    /// Issued by `issuer_id`
+    #[must_use]
+    pub fn from_issuer(self, issuer_id: &IssuerId) -> Self {
+        self.with(Query::Eq("issuer_id".to_owned(), issuer_id.to_string()))
+    }
+
+    /// Issued for the schema `schema_id`
+    #[must_use]
+    pub fn from_schema(self, schema_id: &SchemaId) -> Self {
+        self.with(Query::Eq("schema_id".to_owned(), schema_id.to_string()))
+    }
+
+    /// Holding the raw value `value` for the attribute `name`
+    #[must_use]
+    pub fn attr_value_eq(self, name: &str, value: impl Into<String>) -> Self {
+        self.with(Query::Eq(format!("attr::{name}::value"), value.into()))
+    }
+
+    /// Any other restriction, as a WQL query over the credential tags
+    #[must_use]
+    pub fn with(mut self, query: Query) -> Self {
+        self.0.push(query);
+        self
+    }
+
+    fn into_query(mut self) -> Option<Query> {
+        match self.0.len() {
+            0 => None,
+            1 => self.0.pop(),
+            _ => Some(Query::And(self.0)),
+        }
+    }
+}
+
+/// Builder of a [`PresentationRequest`]
+///
+/// The nonce is generated with [`generate_nonce`](super::verifier::generate_nonce) and the referents are assigned in the order
+/// the attributes and predicates are added: `attr1_referent`, `attr2_referent`, ... and
+/// `predicate1_referent`, `predicate2_referent`, ... The request is a version 2 request unless
+/// another version is selected, and it is validated when built.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::data_types::cred_def::CredentialDefinitionId;
+/// use anoncreds::data_types::pres_request::PredicateInfo;
+/// use anoncreds::pres_request_builder::{PresentationRequestBuilder, Restrictions};
+///
+/// let cred_def_id = CredentialDefinitionId::new("did:web:xyz/resource/cred-def")
+///     .expect("Invalid credential definition id");
+///
+/// let pres_request = PresentationRequestBuilder::new("pres_req_1", "0.1")
+///     .requested_attribute("name", Restrictions::new().from_cred_def(&cred_def_id))
+///     .requested_predicate(PredicateInfo::range("age", 18, 65), Restrictions::new())
+///     .build()
+///     .expect("Unable to build presentation request");
+///
+/// let pres_request = pres_request.value();
+/// assert!(pres_request.requested_attributes.contains_key("attr1_referent"));
+/// assert!(pres_request.requested_predicates.contains_key("predicate1_referent"));
+/// ```
+#[derive(Clone, Debug)]
+pub struct PresentationRequestBuilder {
+    name: String,
+    version: String,
+    request_version: PresentationRequestVersion,
+    requested_attributes: HashMap<String, AttributeInfo>,
+    requested_predicates: HashMap<String, PredicateInfo>,
+    non_revoked: Option<NonRevokedInterval>,
+    referent_non_revoked: Vec<(String, NonRevokedInterval)>,
+    equal_attributes: Vec<EqualAttributes>,
+    same_credential_referents: Vec<Vec<String>>,
+}
+
+impl PresentationRequestBuilder {
+    #[must_use]
+    pub fn new(name: impl Into<String>, version: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            version: version.into(),
+            request_version: PresentationRequestVersion::V2,
+            requested_attributes: HashMap::new(),
+            requested_predicates: HashMap::new(),
+            non_revoked: None,
+            referent_non_revoked: Vec::new(),
+            equal_attributes: Vec::new(),
+            same_credential_referents: Vec::new(),
+        }
+    }
+
+    /// Build a request of the given version instead of a version 2 request
+    ///
+    /// Version 1 requests cannot restrict credentials by fully qualified identifiers.
+    #[must_use]
+    pub fn request_version(mut self, request_version: PresentationRequestVersion) -> Self {
+        self.request_version = request_version;
+        self
+    }
+
+    /// Request the attribute `name` from a credential satisfying `restrictions`
+    #[must_use]
+    pub fn requested_attribute(self, name: impl Into<String>, restrictions: Restrictions) -> Self {
+        self.add_attribute(AttributeInfo {
+            name: Some(name.into()),
+            names: None,
+            restrictions: restrictions.into_query(),
+            non_revoked: None,
+        })
+    }
+
+    /// Request the attributes `names`, all from the same credential satisfying `restrictions`
+    #[must_use]
+    pub fn requested_attribute_group(
+        self,
+        names: impl IntoIterator<Item = impl Into<String>>,
+        restrictions: Restrictions,
+    ) -> Self {
+        self.add_attribute(AttributeInfo {
+            name: None,
+            names: Some(names.into_iter().map(Into::into).collect()),
+            restrictions: restrictions.into_query(),
+            non_revoked: None,
+        })
+    }
+
+    /// Request `predicate` to be proven from a credential satisfying `restrictions`, which replace
+    /// those of `predicate` unless empty
+    ///
+    /// A [`PredicateTypes::NE`](crate::data_types::pres_request::PredicateTypes::NE) predicate discloses whether the attribute is below or above its
+    /// value, see [`PredicateInfo::not_equal`].
+    #[must_use]
+    pub fn requested_predicate(
+        mut self,
+        predicate: PredicateInfo,
+        restrictions: Restrictions,
+    ) -> Self {
+        let referent = format!("predicate{}_referent", self.requested_predicates.len() + 1);
+        let restrictions = restrictions.into_query().or(predicate.restrictions);
+        self.requested_predicates.insert(
+            referent,
+            PredicateInfo {
+                restrictions,
+                ..predicate
+            },
+        );
+        self
+    }
+
+    /// Require every presented credential to be non-revoked within `interval`
+    #[must_use]
+    pub fn non_revoked(mut self, interval: NonRevokedInterval) -> Self {
+        self.non_revoked = Some(interval);
+        self
+    }
+
+    /// Require the credential presented for the requested attribute or predicate `referent` to be
+    /// non-revoked within `interval`, instead of the interval of the whole request
+    #[must_use]
+    pub fn non_revoked_for(
+        mut self,
+        referent: impl Into<String>,
+        interval: NonRevokedInterval,
+    ) -> Self {
+        self.referent_non_revoked.push((referent.into(), interval));
+        self
+    }
+
+    /// Require the attribute `name` to hold the same value in the credentials presented for the
+    /// requested attribute and predicate `referents`, without revealing it
+    #[must_use]
+    pub fn equal_attribute(
+        mut self,
+        name: impl Into<String>,
+        referents: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.equal_attributes.push(EqualAttributes {
+            name: name.into(),
+            referents: referents.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    /// Require the requested attribute and predicate `referents` to be answered by the same
+    /// credential
+    #[must_use]
+    pub fn same_credential(
+        mut self,
+        referents: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        self.same_credential_referents
+            .push(referents.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Generate the nonce and validate the presentation request
+    pub fn build(mut self) -> Result<PresentationRequest> {
+        trace!("PresentationRequestBuilder::build >>> builder: {:?}", self);
+
+        for (referent, interval) in self.referent_non_revoked {
+            if let Some(attribute) = self.requested_attributes.get_mut(&referent) {
+                attribute.non_revoked = Some(interval);
+            } else if let Some(predicate) = self.requested_predicates.get_mut(&referent) {
+                predicate.non_revoked = Some(interval);
+            } else {
+                return Err(err_msg!(
+                    "Non-revoked interval given for unknown referent {}",
+                    referent
+                ));
+            }
+        }
+
+        let payload = PresentationRequestPayload {
+            nonce: generate_nonce()?,
+            name: self.name,
+            version: self.version,
+            requested_attributes: self.requested_attributes,
+            requested_predicates: self.requested_predicates,
+            non_revoked: self.non_revoked,
+            equal_attributes: self.equal_attributes,
+            same_credential_referents: self.same_credential_referents,
+        };
+        let pres_request = match self.request_version {
+            PresentationRequestVersion::V1 => PresentationRequest::PresentationRequestV1(payload),
+            PresentationRequestVersion::V2 => PresentationRequest::PresentationRequestV2(payload),
+        };
+        pres_request.validate()?;
+
+        trace!(
+            "PresentationRequestBuilder::build <<< pres_request: {:?}",
+            pres_request
+        );
+
+        Ok(pres_request)
+    }
+
+    fn add_attribute(mut self, attribute: AttributeInfo) -> Self {
+        let referent = format!("attr{}_referent", self.requested_attributes.len() + 1);
+        self.requested_attributes.insert(referent, attribute);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CRED_DEF_ID: &str = "345";
+    const ISSUER_ID: &str = "1111111111111111111111";
+
+    #[test]
+    fn test_presentation_request_builder() {
+        let cred_def_id = CredentialDefinitionId::new_unchecked(CRED_DEF_ID);
+        let issuer_id = IssuerId::new_unchecked(ISSUER_ID);
+
+        let pres_request = PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .requested_attribute("name", Restrictions::new().from_cred_def(&cred_def_id))
+            .requested_attribute_group(
+                ["sex", "height"],
+                Restrictions::new()
+                    .from_issuer(&issuer_id)
+                    .attr_value_eq("sex", "male"),
+            )
+            .requested_predicate(PredicateInfo::range("age", 18, 65), Restrictions::new())
+            .equal_attribute("name", ["attr1_referent", "predicate1_referent"])
+            .build()
+            .unwrap();
+        assert_eq!(pres_request.version(), PresentationRequestVersion::V2);
+
+        let payload = pres_request.value();
+        assert_eq!(
+            payload.requested_attributes["attr1_referent"].restrictions,
+            Some(Query::Eq(
+                "cred_def_id".to_string(),
+                CRED_DEF_ID.to_string()
+            ))
+        );
+        assert_eq!(
+            payload.requested_attributes["attr2_referent"].restrictions,
+            Some(Query::And(vec![
+                Query::Eq("issuer_id".to_string(), ISSUER_ID.to_string()),
+                Query::Eq("attr::sex::value".to_string(), "male".to_string()),
+            ]))
+        );
+        assert_eq!(
+            payload.requested_predicates["predicate1_referent"].restrictions,
+            None
+        );
+        assert_eq!(
+            payload.equal_attributes,
+            vec![EqualAttributes {
+                name: "name".to_string(),
+                referents: vec![
+                    "attr1_referent".to_string(),
+                    "predicate1_referent".to_string()
+                ],
+            }]
+        );
+    }
+
+    #[test]
+    fn test_presentation_request_builder_validates() {
+        assert!(PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .build()
+            .is_err());
+
+        // Fully qualified identifiers are only supported by version 2 requests
+        let cred_def_id = CredentialDefinitionId::new_unchecked("did:web:xyz/resource/cred-def");
+        let builder = PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .requested_attribute("name", Restrictions::new().from_cred_def(&cred_def_id));
+        assert!(builder.clone().build().is_ok());
+        assert!(builder
+            .request_version(PresentationRequestVersion::V1)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_presentation_request_builder_non_revoked_for() {
+        let pres_request = PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .requested_attribute("name", Restrictions::new())
+            .requested_predicate(PredicateInfo::range("age", 18, 65), Restrictions::new())
+            .non_revoked(NonRevokedInterval::new(None, Some(200)))
+            .non_revoked_for(
+                "predicate1_referent",
+                NonRevokedInterval::new(Some(100), Some(150)),
+            )
+            .build()
+            .unwrap();
+
+        let payload = pres_request.value();
+        assert_eq!(
+            payload.non_revoked,
+            Some(NonRevokedInterval::new(None, Some(200)))
+        );
+        assert_eq!(
+            payload.requested_attributes["attr1_referent"].non_revoked,
+            None
+        );
+        assert_eq!(
+            payload.requested_predicates["predicate1_referent"].non_revoked,
+            Some(NonRevokedInterval::new(Some(100), Some(150)))
+        );
+
+        assert!(PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .requested_attribute("name", Restrictions::new())
+            .non_revoked_for("attr2_referent", NonRevokedInterval::new(None, Some(200)))
+            .build()
+            .is_err());
+    }
+}
@@ -1,4 +1,4 @@
-use super::tails::TailsFileReader;
+use super::tails::{TailsFileReader, TailsReader};
 use super::types::{
     Credential, CredentialOffer, CredentialRequest, CredentialRequestMetadata, LinkSecret,
     Presentation, PresentationRequest, RevocationRegistryDefinition,
@@ -6,7 +6,8 @@ use super::types::{
 use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
 use crate::data_types::credential::AttributeValues;
 use crate::data_types::pres_request::{
-    PresentationRequestPayload, RequestedAttributeInfo, RequestedPredicateInfo,
+    NonRevokedInterval, PredicateInfo, PredicateTypes, PresentationRequestPayload,
+    RequestedAttributeInfo, RequestedPredicateInfo,
 };
 use crate::data_types::presentation::AttributeValue;
 use crate::data_types::presentation::Identifier;
@@ -14,21 +15,29 @@ use crate::data_types::presentation::RequestedProof;
 use crate::data_types::presentation::RevealedAttributeGroupInfo;
 use crate::data_types::presentation::RevealedAttributeInfo;
 use crate::data_types::presentation::SubProofReferent;
-use crate::data_types::rev_status_list::RevocationStatusList;
+use crate::data_types::rev_reg::UrsaRevocationRegistry;
+use crate::data_types::rev_status_list::{RevocationStatusList, RevocationStatusListDelta};
 use crate::data_types::schema::{Schema, SchemaId};
+use crate::data_types::w3c::W3CPresentation;
 use crate::error::{Error, Result};
 use crate::services::helpers::{
-    attr_common_view, build_credential_schema, build_credential_values,
-    build_non_credential_schema, get_predicates_for_credential,
-    get_revealed_attributes_for_credential, new_nonce,
+    attr_common_view, build_credential_schema, build_credential_tags, build_credential_values,
+    build_non_credential_schema, check_not_equal_attribute, get_predicates_for_credential,
+    get_revealed_attributes_for_credential, match_credential_tags, new_nonce,
+};
+use crate::services::resolver::{AsyncObjectResolver, ObjectRefs, ObjectResolver, ResolvedObjects};
+use crate::services::w3c::presentation_to_w3c;
+use crate::types::{
+    CredentialCandidate, CredentialMatches, CredentialRevocationState, PresentCredentials,
+    ReferentMatches,
 };
-use crate::types::{CredentialRevocationState, PresentCredentials};
 use crate::ursa::cl::{
     issuer::Issuer as CryptoIssuer, prover::Prover as CryptoProver,
     verifier::Verifier as CryptoVerifier, CredentialPublicKey,
     RevocationRegistry as CryptoRevocationRegistry, RevocationRegistryDelta, SubProofRequest,
     Witness,
 };
+use crate::utils::query::Query;
 use crate::utils::validation::Validatable;
 use bitvec::bitvec;
 use std::collections::{HashMap, HashSet};
@@ -403,6 +412,10 @@ pub fn create_presentation(
     let pres_req_val = pres_req.value();
     let mut proof_builder = CryptoProver::new_proof_builder()?;
     proof_builder.add_common_attribute("master_secret")?;
+    // The blinding of an attribute proven equal is only shared by the sub proofs of the
+    // credentials presented for its referents: it is drawn again for any other credential
+    // holding the attribute, and cannot be restored once drawn again
+    let mut equal_blindings = vec![EqualBlinding::Unused; pres_req_val.equal_attributes.len()];
 
     let mut requested_proof = RequestedProof {
         self_attested_attrs: self_attested.unwrap_or_default(),
@@ -413,7 +426,21 @@ pub fn create_presentation(
     let non_credential_schema = build_non_credential_schema()?;
 
     let mut identifiers: Vec<Identifier> = Vec::with_capacity(credentials.len());
-    for present in credentials.0 {
+    let mut credentials = credentials.0;
+    // Prove the credentials of each equal attribute one after the other, before any other
+    credentials.sort_by_key(|present| {
+        pres_req_val
+            .equal_attributes
+            .iter()
+            .position(|equal| {
+                equal
+                    .referents
+                    .iter()
+                    .any(|referent| present.presents(referent))
+            })
+            .unwrap_or(usize::MAX)
+    });
+    for present in credentials {
         if present.is_empty() {
             continue;
         }
@@ -436,6 +463,45 @@ pub fn create_presentation(
             cred_def.value.revocation.as_ref(),
         )?;
 
+        let equal_attributes =
+            pres_req_val.equal_attribute_names(|referent| present.presents(referent));
+        for (equal, blinding) in pres_req_val
+            .equal_attributes
+            .iter()
+            .zip(equal_blindings.iter_mut())
+        {
+            let holds_attribute = credential
+                .values
+                .0
+                .keys()
+                .any(|attr| attr_common_view(attr) == attr_common_view(&equal.name));
+            if equal_attributes.contains(&equal.name) {
+                if !holds_attribute {
+                    return Err(err_msg!(
+                        "Credential {} does not contain the equal attribute \"{}\"",
+                        credential.cred_def_id,
+                        equal.name
+                    ));
+                }
+                match blinding {
+                    EqualBlinding::Shared => {}
+                    EqualBlinding::Unused => {
+                        proof_builder.add_common_attribute(&attr_common_view(&equal.name))?;
+                        *blinding = EqualBlinding::Shared;
+                    }
+                    EqualBlinding::Replaced => {
+                        return Err(err_msg!(
+                            "The credentials presented for the referents of the equal attribute \"{}\" cannot be proven one after the other",
+                            equal.name
+                        ));
+                    }
+                }
+            } else if holds_attribute && *blinding != EqualBlinding::Unused {
+                proof_builder.add_common_attribute(&attr_common_view(&equal.name))?;
+                *blinding = EqualBlinding::Replaced;
+            }
+        }
+
         let credential_schema = build_credential_schema(&schema.attr_names.0)?;
         let credential_values =
             build_credential_values(&credential.values.0, Some(&link_secret.try_into()?))?;
@@ -443,8 +509,10 @@ pub fn create_presentation(
             present.requested_attributes,
             present.requested_predicates,
             pres_req_val,
+            &credential.values.0,
         )?;
-        let sub_proof_request = build_sub_proof_request(&req_attrs, &req_predicates)?;
+        let sub_proof_request =
+            build_sub_proof_request(&req_attrs, &req_predicates, &equal_attributes)?;
 
         update_requested_proof(
             req_attrs,
@@ -518,6 +586,20 @@ pub fn create_presentation(
         sub_proof_index += 1;
     }
 
+    for referents in &pres_req_val.same_credential_referents {
+        let sub_proof_index = requested_proof.sub_proof_index(&referents[0]);
+        if let Some(referent) = referents
+            .iter()
+            .find(|referent| requested_proof.sub_proof_index(referent) != sub_proof_index)
+        {
+            return Err(err_msg!(
+                "Referents {} and {} must be answered by the same credential",
+                referents[0],
+                referent
+            ));
+        }
+    }
+
     let proof = proof_builder.finalize(pres_req_val.nonce.as_native())?;
 
     let full_proof = Presentation {
@@ -531,6 +613,365 @@ pub fn create_presentation(
     Ok(full_proof)
 }
 
+/// Create a presentation, resolving the schemas and credential definitions of the presented
+/// credentials through `resolver`
+///
+/// See [`create_presentation`] for the presentation itself.
+pub fn create_presentation_with_resolver(
+    pres_req: &PresentationRequest,
+    credentials: PresentCredentials,
+    self_attested: Option<HashMap<String, String>>,
+    link_secret: &LinkSecret,
+    resolver: &impl ObjectResolver,
+) -> Result<Presentation> {
+    trace!(
+        "create_presentation_with_resolver >>> pres_req: {:?}, credentials: {:?}",
+        pres_req,
+        credentials
+    );
+
+    let resolved = ResolvedObjects::resolve(ObjectRefs::for_credentials(&credentials), resolver)?;
+    let presentation = create_presentation(
+        pres_req,
+        credentials,
+        self_attested,
+        link_secret,
+        &resolved.schemas(),
+        &resolved.cred_defs(),
+    )?;
+
+    trace!(
+        "create_presentation_with_resolver <<< presentation: {:?}",
+        secret!(&presentation)
+    );
+
+    Ok(presentation)
+}
+
+/// Create a presentation, as [`create_presentation_with_resolver`] with an asynchronous resolver
+pub async fn create_presentation_with_async_resolver(
+    pres_req: &PresentationRequest,
+    credentials: PresentCredentials<'_>,
+    self_attested: Option<HashMap<String, String>>,
+    link_secret: &LinkSecret,
+    resolver: &impl AsyncObjectResolver,
+) -> Result<Presentation> {
+    trace!(
+        "create_presentation_with_async_resolver >>> pres_req: {:?}, credentials: {:?}",
+        pres_req,
+        credentials
+    );
+
+    let resolved =
+        ResolvedObjects::resolve_async(ObjectRefs::for_credentials(&credentials), resolver).await?;
+    let presentation = create_presentation(
+        pres_req,
+        credentials,
+        self_attested,
+        link_secret,
+        &resolved.schemas(),
+        &resolved.cred_defs(),
+    )?;
+
+    trace!(
+        "create_presentation_with_async_resolver <<< presentation: {:?}",
+        secret!(&presentation)
+    );
+
+    Ok(presentation)
+}
+
+/// Create a presentation in the W3C Verifiable Presentation form, for exchanges which cannot
+/// carry the legacy presentation JSON
+///
+/// See [`create_presentation`] for the presentation itself and
+/// [`presentation_to_w3c`](crate::w3c::presentation_to_w3c) for its W3C form.
+pub fn create_w3c_presentation(
+    pres_req: &PresentationRequest,
+    credentials: PresentCredentials,
+    self_attested: Option<HashMap<String, String>>,
+    link_secret: &LinkSecret,
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+) -> Result<W3CPresentation> {
+    trace!(
+        "create_w3c_presentation >>> pres_req: {:?}, credentials: {:?}, schemas: {:?}, cred_defs: {:?}",
+        pres_req,
+        credentials,
+        schemas,
+        cred_defs
+    );
+
+    let presentation = create_presentation(
+        pres_req,
+        credentials,
+        self_attested,
+        link_secret,
+        schemas,
+        cred_defs,
+    )?;
+    let w3c_presentation = presentation_to_w3c(&presentation, pres_req, cred_defs)?;
+
+    trace!(
+        "create_w3c_presentation <<< w3c_presentation: {:?}",
+        secret!(&w3c_presentation)
+    );
+
+    Ok(w3c_presentation)
+}
+
+/// Find the credentials which can be used for each referent of a presentation request
+///
+/// A credential is a candidate for a requested attribute or predicate when it contains the
+/// requested attribute(s), satisfies the `restrictions` of the referent and, when a non-revoked
+/// interval applies to a revocable credential, has a revocation state with a timestamp inside of
+/// that interval. Candidates for predicates must also satisfy the predicate itself.
+///
+/// Restrictions are evaluated against the tags of the credential: `schema_id`, `cred_def_id`,
+/// `rev_reg_id`, `attr::<name>::value` and `attr::<name>::marker`, and, when the schema and
+/// credential definition are provided, `schema_name`, `schema_version`, `schema_issuer_id` and
+/// `issuer_id` (plus `schema_issuer_did` and `issuer_did` for legacy identifiers).
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::prover;
+/// use anoncreds::issuer;
+/// use anoncreds::verifier;
+/// use anoncreds::types::CredentialCandidate;
+/// use anoncreds::types::MakeCredentialValues;
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+/// use anoncreds::data_types::cred_def::CredentialDefinitionId;
+/// use anoncreds::data_types::schema::SchemaId;
+///
+/// use std::collections::HashMap;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let credential_offer =
+///     issuer::create_credential_offer("did:web:xyz/resource/schema",
+///                                     "did:web:xyz/resource/cred-def",
+///                                     &key_correctness_proof,
+///                                     ).expect("Unable to create Credential Offer");
+///
+/// let link_secret =
+///     prover::create_link_secret().expect("Unable to create link secret");
+///
+/// let (credential_request, credential_request_metadata) =
+///     prover::create_credential_request(Some("entropy"),
+///                                       None,
+///                                       &cred_def,
+///                                       &link_secret,
+///                                       "my-secret-id",
+///                                       &credential_offer,
+///                                       ).expect("Unable to create credential request");
+///
+/// let mut credential_values = MakeCredentialValues::default();
+/// credential_values.add_raw("name", "john").expect("Unable to add credential value");
+/// credential_values.add_raw("age", "28").expect("Unable to add credential value");
+///
+/// let mut credential =
+///     issuer::create_credential(&cred_def,
+///                               &cred_def_priv,
+///                               &credential_offer,
+///                               &credential_request,
+///                               credential_values.into(),
+///                               None,
+///                               None,
+///                               None
+///                               ).expect("Unable to create credential");
+///
+/// prover::process_credential(&mut credential,
+///                            &credential_request_metadata,
+///                            &link_secret,
+///                            &cred_def,
+///                            None
+///                            ).expect("Unable to process the credential");
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = serde_json::from_value(serde_json::json!({
+///     "nonce": nonce,
+///     "name":"example_presentation_request",
+///     "version":"0.1",
+///     "requested_attributes":{
+///         "attr1_referent":{
+///             "name":"name",
+///             "restrictions": {
+///                 "issuer_id": "did:web:xyz"
+///             }
+///         },
+///         "attr2_referent":{
+///             "name":"phone"
+///         },
+///     },
+///     "requested_predicates":{
+///         "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+///     }
+/// }))
+/// .expect("Unable to create presentation request");
+///
+/// let mut schemas = HashMap::new();
+/// let schema_id = SchemaId::new_unchecked("did:web:xyz/resource/schema");
+/// schemas.insert(&schema_id, &schema);
+///
+/// let mut cred_defs = HashMap::new();
+/// let cred_def_id = CredentialDefinitionId::new_unchecked("did:web:xyz/resource/cred-def");
+/// cred_defs.insert(&cred_def_id, &cred_def);
+///
+/// let credentials = [CredentialCandidate::new(&credential, None)];
+/// let matches =
+///     prover::match_credentials(&pres_request,
+///                               &credentials,
+///                               &schemas,
+///                               &cred_defs);
+///
+/// assert_eq!(matches.attribute_candidates("attr1_referent").len(), 1);
+/// assert_eq!(matches.unmatched_attributes(), vec!["attr2_referent"]);
+///
+/// let self_attested = HashMap::from([("attr2_referent".to_owned(), "8-800-300".to_owned())]);
+/// let presentation =
+///     prover::create_presentation(&pres_request,
+///                                 matches.to_present_credentials(),
+///                                 Some(self_attested),
+///                                 &link_secret,
+///                                 &schemas,
+///                                 &cred_defs
+///                                 ).expect("Unable to create presentation");
+/// ```
+pub fn match_credentials<'p>(
+    pres_req: &PresentationRequest,
+    credentials: &[CredentialCandidate<'p>],
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+) -> CredentialMatches<'p> {
+    trace!(
+        "match_credentials >>> pres_req: {:?}, credentials: {:?}, schemas: {:?}, cred_defs: {:?}",
+        pres_req,
+        credentials,
+        schemas,
+        cred_defs
+    );
+
+    let pres_req_val = pres_req.value();
+
+    let tags: Vec<HashMap<String, String>> = credentials
+        .iter()
+        .map(|candidate| {
+            let cred = candidate.cred;
+            build_credential_tags(
+                cred,
+                schemas.get(&cred.schema_id).copied(),
+                cred_defs.get(&cred.cred_def_id).copied(),
+            )
+        })
+        .collect();
+
+    let find_candidates = |names: &[&String],
+                           restrictions: Option<&Query>,
+                           non_revoked: Option<&NonRevokedInterval>,
+                           satisfies: &dyn Fn(&Credential) -> bool| {
+        credentials
+            .iter()
+            .zip(&tags)
+            .filter(|(candidate, tags)| {
+                names.iter().all(|name| {
+                    tags.contains_key(&format!("attr::{}::marker", attr_common_view(name)))
+                }) && restrictions.map_or(true, |query| match_credential_tags(query, tags))
+                    && is_non_revoked_candidate(candidate, non_revoked)
+                    && satisfies(candidate.cred)
+            })
+            .map(|(candidate, _)| *candidate)
+            .collect::<Vec<_>>()
+    };
+
+    let mut matches = CredentialMatches::default();
+
+    for (referent, info) in &pres_req_val.requested_attributes {
+        let names: Vec<&String> = info
+            .name
+            .iter()
+            .chain(info.names.iter().flatten())
+            .collect();
+        let non_revoked = info
+            .non_revoked
+            .as_ref()
+            .or(pres_req_val.non_revoked.as_ref());
+        matches.attributes.insert(
+            referent.clone(),
+            ReferentMatches {
+                candidates: find_candidates(
+                    &names,
+                    info.restrictions.as_ref(),
+                    non_revoked,
+                    &|_| true,
+                ),
+                non_revoked: non_revoked.is_some(),
+            },
+        );
+    }
+
+    for (referent, info) in &pres_req_val.requested_predicates {
+        let non_revoked = info
+            .non_revoked
+            .as_ref()
+            .or(pres_req_val.non_revoked.as_ref());
+        matches.predicates.insert(
+            referent.clone(),
+            ReferentMatches {
+                candidates: find_candidates(
+                    &[&info.name],
+                    info.restrictions.as_ref(),
+                    non_revoked,
+                    &|cred| satisfies_predicate(cred, info),
+                ),
+                non_revoked: non_revoked.is_some(),
+            },
+        );
+    }
+
+    trace!("match_credentials <<< matches: {:?}", matches);
+
+    matches
+}
+
+fn is_non_revoked_candidate(
+    candidate: &CredentialCandidate,
+    non_revoked: Option<&NonRevokedInterval>,
+) -> bool {
+    match (non_revoked, &candidate.cred.rev_reg_id) {
+        (Some(interval), Some(_)) => candidate
+            .rev_state
+            .map_or(false, |state| interval.is_valid(state.timestamp).is_ok()),
+        _ => true,
+    }
+}
+
+fn satisfies_predicate(credential: &Credential, predicate: &PredicateInfo) -> bool {
+    let value = credential
+        .values
+        .0
+        .iter()
+        .find(|(name, _)| attr_common_view(name) == attr_common_view(&predicate.name))
+        .and_then(|(_, value)| value.encoded.parse::<i64>().ok());
+
+    value.map_or(false, |value| predicate.is_satisfied_by(value))
+}
+
 /// Create a [`CredentialRevocationState`] based on a [`Witness`], [`RevocationStatusList`] and
 /// timestamp.
 pub fn create_revocation_state_with_witness(
@@ -554,7 +995,9 @@ pub fn create_revocation_state_with_witness(
 /// supplied, it will update it. Otherwise it will create a new [`CredentialRevocationState`]
 ///
 /// This can be done by anyone, allowing prover to offload this task The `tails_path` here is used
-/// instead of `tails_location` in `revoc_reg_def` so prover can provide it
+/// instead of `tails_location` in `revoc_reg_def` so prover can provide it. Use
+/// [`create_or_update_revocation_state_with_tails_reader`] to read the tails file from another
+/// source
 ///
 /// # Example
 ///
@@ -619,6 +1062,29 @@ pub fn create_or_update_revocation_state(
     rev_reg_idx: u32,
     rev_state: Option<&CredentialRevocationState>, // for witness update
     old_rev_status_list: Option<&RevocationStatusList>, // for witness update
+) -> Result<CredentialRevocationState> {
+    create_or_update_revocation_state_with_tails_reader(
+        &TailsFileReader::new_tails_reader(tails_path),
+        rev_reg_def,
+        rev_status_list,
+        rev_reg_idx,
+        rev_state,
+        old_rev_status_list,
+    )
+}
+
+/// Create or update the revocation state, reading the tails file through `tails_reader`
+///
+/// The prover can provide the tails file from any source, e.g. a local file, an in-memory buffer
+/// or a URL. Use [`TailsReader::with_verification`] to check it against `revoc_reg_def` first. See
+/// [`create_or_update_revocation_state`] for the revocation state itself.
+pub fn create_or_update_revocation_state_with_tails_reader(
+    tails_reader: &TailsReader,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_status_list: &RevocationStatusList,
+    rev_reg_idx: u32,
+    rev_state: Option<&CredentialRevocationState>, // for witness update
+    old_rev_status_list: Option<&RevocationStatusList>, // for witness update
 ) -> Result<CredentialRevocationState> {
     trace!(
         "create_or_update_revocation_state >>> revoc_reg_def: {:?}, \
@@ -639,9 +1105,9 @@ pub fn create_or_update_revocation_state(
         err_msg!("Timestamp is required to create or update the revocation state")
     })?;
 
+    tails_reader.ensure_verified(rev_reg_def)?;
     let mut issued = HashSet::<u32>::new();
     let mut revoked = HashSet::<u32>::new();
-    let tails_reader = TailsFileReader::new_tails_reader(tails_path);
     let witness = if let (Some(source_rev_state), Some(source_rev_list)) =
         (rev_state, old_rev_status_list)
     {
@@ -668,7 +1134,7 @@ pub fn create_or_update_revocation_state(
             rev_reg_idx,
             rev_reg_def.value.max_cred_num,
             &rev_reg_delta,
-            &tails_reader,
+            tails_reader,
         )?;
         witness
     } else {
@@ -690,7 +1156,7 @@ pub fn create_or_update_revocation_state(
             // issuance by default
             true,
             &rev_reg_delta,
-            &tails_reader,
+            tails_reader,
         )?
     };
 
@@ -701,6 +1167,146 @@ pub fn create_or_update_revocation_state(
     })
 }
 
+/// Update a [`CredentialRevocationState`] from a [`RevocationStatusListDelta`], for registries
+/// publishing revocation deltas instead of full revocation status lists.
+///
+/// The delta must start at the accumulator of `rev_state` and carry the accumulator and timestamp
+/// it moves the registry to.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::prover;
+/// use anoncreds::issuer;
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+/// use anoncreds::types::RegistryType;
+/// use anoncreds::tails::{TailsFileReader, TailsFileWriter};
+/// use std::collections::BTreeSet;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig {
+///                                             support_revocation: true
+///                                          },
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let mut tw = TailsFileWriter::new(None);
+/// let (rev_reg_def, rev_reg_def_priv) =
+///     issuer::create_revocation_registry_def(&cred_def,
+///                                            "did:web:xyz/resource/cred-def",
+///                                            "did:web:xyz",
+///                                            "default-tag",
+///                                            RegistryType::CL_ACCUM,
+///                                            10,
+///                                            &mut tw
+///                                            ).expect("Unable to create revocation registry");
+///
+/// let rev_status_list =
+///     issuer::create_revocation_status_list("did:web:xyz/resource/rev-reg-def",
+///                                           &rev_reg_def,
+///                                           "did:web:xyz",
+///                                           Some(10),
+///                                           true
+///                                           ).expect("Unable to create revocation status list");
+///
+/// let tails_reader = TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location);
+/// let rev_state =
+///     prover::create_or_update_revocation_state_with_tails_reader(&tails_reader,
+///                                               &rev_reg_def,
+///                                               &rev_status_list,
+///                                               1,
+///                                               None,
+///                                               None
+///                                              ).expect("Unable to create the revocation state");
+///
+/// let updated_rev_status_list =
+///     issuer::update_revocation_status_list(Some(20),
+///                                           None,
+///                                           Some(BTreeSet::from([2])),
+///                                           &rev_reg_def,
+///                                           &rev_status_list
+///                                           ).expect("Unable to update revocation status list");
+///
+/// let delta = rev_status_list.diff(&updated_rev_status_list).expect("Unable to compute delta");
+/// let rev_state =
+///     prover::update_revocation_state_with_delta(&tails_reader,
+///                                                &rev_reg_def,
+///                                                &rev_state,
+///                                                1,
+///                                                &delta
+///                                               ).expect("Unable to update the revocation state");
+/// ```
+pub fn update_revocation_state_with_delta(
+    tails_reader: &TailsReader,
+    rev_reg_def: &RevocationRegistryDefinition,
+    rev_state: &CredentialRevocationState,
+    rev_reg_idx: u32,
+    delta: &RevocationStatusListDelta,
+) -> Result<CredentialRevocationState> {
+    trace!(
+        "update_revocation_state_with_delta >>> revoc_reg_def: {:?}, rev_state: {:?}, \
+    rev_reg_idx: {}, delta: {:?}",
+        rev_reg_def,
+        rev_state,
+        rev_reg_idx,
+        delta,
+    );
+
+    let rev_reg: ursa::cl::RevocationRegistry = delta
+        .to_accumulator
+        .ok_or_else(|| err_msg!("Target accumulator is required to update the revocation state"))?
+        .try_into()?;
+    let timestamp = delta
+        .to_timestamp
+        .ok_or_else(|| err_msg!("Timestamp is required to update the revocation state"))?;
+    if let Some(from_accumulator) = delta.from_accumulator {
+        if UrsaRevocationRegistry::try_from(rev_state.rev_reg.clone())? != from_accumulator {
+            return Err(err_msg!(
+                "Revocation status list delta does not start at the revocation state accumulator"
+            ));
+        }
+    }
+
+    tails_reader.ensure_verified(rev_reg_def)?;
+    let issued = delta.issued.iter().copied().collect::<HashSet<_>>();
+    let revoked = delta.revoked.iter().copied().collect::<HashSet<_>>();
+    let rev_reg_delta =
+        RevocationRegistryDelta::from_parts(Some(&rev_state.rev_reg), &rev_reg, &issued, &revoked);
+
+    let mut witness = rev_state.witness.clone();
+    witness.update(
+        rev_reg_idx,
+        rev_reg_def.value.max_cred_num,
+        &rev_reg_delta,
+        tails_reader,
+    )?;
+
+    let rev_state = CredentialRevocationState {
+        witness,
+        rev_reg,
+        timestamp,
+    };
+
+    trace!(
+        "update_revocation_state_with_delta <<< rev_state: {:?}",
+        rev_state
+    );
+
+    Ok(rev_state)
+}
+
 fn create_index_deltas(
     delta: &bitvec::vec::BitVec,
     list: &bitvec::vec::BitVec,
@@ -722,6 +1328,7 @@ fn prepare_credential_for_proving(
     requested_attributes: HashSet<(String, bool)>,
     requested_predicates: HashSet<String>,
     pres_req: &PresentationRequestPayload,
+    credential_attrs: &HashMap<String, AttributeValues>,
 ) -> Result<(Vec<RequestedAttributeInfo>, Vec<RequestedPredicateInfo>)> {
     trace!(
         "_prepare_credentials_for_proving >>> requested_attributes: {:?}, requested_predicates: {:?}, pres_req: {:?}",
@@ -762,9 +1369,24 @@ fn prepare_credential_for_proving(
                 )
             })?;
 
+        // Not-equal predicates are proven on the side of their value the attribute is on
+        let predicate_info = if predicate_info.p_type == PredicateTypes::NE {
+            let value = get_credential_values_for_attribute(credential_attrs, &predicate_info.name)
+                .and_then(|values| values.encoded.parse::<i64>().ok())
+                .ok_or_else(|| {
+                    err_msg!(
+                        "Credential value not found for predicate attribute {:?}",
+                        predicate_info.name
+                    )
+                })?;
+            predicate_info.resolve_for_value(value)?
+        } else {
+            predicate_info.clone()
+        };
+
         preds.push(RequestedPredicateInfo {
             predicate_referent,
-            predicate_info: predicate_info.clone(),
+            predicate_info,
         });
     }
 
@@ -863,6 +1485,14 @@ fn update_requested_proof(
     }
 
     for predicate_info in req_predicates_for_credential {
+        if proof_req.requested_predicates[&predicate_info.predicate_referent].p_type
+            == PredicateTypes::NE
+        {
+            requested_proof.not_equal_predicates.insert(
+                predicate_info.predicate_referent.clone(),
+                predicate_info.predicate_info.p_type,
+            );
+        }
         requested_proof.predicates.insert(
             predicate_info.predicate_referent,
             SubProofReferent { sub_proof_index },
@@ -874,21 +1504,36 @@ fn update_requested_proof(
     Ok(())
 }
 
+/// Blinding of an attribute proven equal in the proof builder
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum EqualBlinding {
+    /// Not drawn yet
+    Unused,
+    /// Shared by the sub proofs of the credentials presented for the referents
+    Shared,
+    /// Drawn again for another credential after some of the credentials presented for the
+    /// referents
+    Replaced,
+}
+
 fn build_sub_proof_request(
     req_attrs_for_credential: &[RequestedAttributeInfo],
     req_predicates_for_credential: &[RequestedPredicateInfo],
+    equal_attributes: &[String],
 ) -> Result<SubProofRequest> {
-    trace!("_build_sub_proof_request <<< req_attrs_for_credential: {:?}, req_predicates_for_credential: {:?}",
-           req_attrs_for_credential, req_predicates_for_credential);
+    trace!("_build_sub_proof_request <<< req_attrs_for_credential: {:?}, req_predicates_for_credential: {:?}, equal_attributes: {:?}",
+           req_attrs_for_credential, req_predicates_for_credential, equal_attributes);
 
     let mut sub_proof_request_builder = CryptoVerifier::new_sub_proof_request_builder()?;
 
     for attr in req_attrs_for_credential {
         if attr.revealed {
             if let Some(ref name) = &attr.attr_info.name {
+                check_not_equal_attribute(name, equal_attributes)?;
                 sub_proof_request_builder.add_revealed_attr(&attr_common_view(name))?;
             } else if let Some(ref names) = &attr.attr_info.names {
                 for name in names {
+                    check_not_equal_attribute(name, equal_attributes)?;
                     sub_proof_request_builder.add_revealed_attr(&attr_common_view(name))?;
                 }
             }
@@ -896,13 +1541,13 @@ fn build_sub_proof_request(
     }
 
     for predicate in req_predicates_for_credential {
-        let p_type = format!("{}", predicate.predicate_info.p_type);
-
-        sub_proof_request_builder.add_predicate(
-            &attr_common_view(&predicate.predicate_info.name),
-            &p_type,
-            predicate.predicate_info.p_value,
-        )?;
+        for (p_type, p_value) in predicate.predicate_info.crypto_predicates()? {
+            sub_proof_request_builder.add_predicate(
+                &attr_common_view(&predicate.predicate_info.name),
+                &p_type.to_string(),
+                p_value,
+            )?;
+        }
     }
 
     let sub_proof_request = sub_proof_request_builder.finalize()?;
@@ -967,6 +1612,7 @@ mod tests {
                 name: "age".to_string(),
                 p_type: PredicateTypes::GE,
                 p_value: 8,
+                p_value_max: None,
                 restrictions: None,
                 non_revoked: None,
             }
@@ -984,6 +1630,8 @@ mod tests {
                     PREDICATE_REFERENT.to_string() => _predicate_info()
                 ),
                 non_revoked: None,
+                equal_attributes: Vec::new(),
+                same_credential_referents: Vec::new(),
             }
         }
 
@@ -1000,7 +1648,8 @@ mod tests {
             let proof_req = _proof_req();
 
             let (req_attr_info, req_pred_info) =
-                prepare_credential_for_proving(req_attrs, req_preds, &proof_req).unwrap();
+                prepare_credential_for_proving(req_attrs, req_preds, &proof_req, &HashMap::new())
+                    .unwrap();
 
             assert_eq!(1, req_attr_info.len());
             assert_eq!(1, req_pred_info.len());
@@ -1024,7 +1673,8 @@ mod tests {
             );
 
             let (req_attr_info, req_pred_info) =
-                prepare_credential_for_proving(req_attrs, req_preds, &proof_req).unwrap();
+                prepare_credential_for_proving(req_attrs, req_preds, &proof_req, &HashMap::new())
+                    .unwrap();
 
             assert_eq!(2, req_attr_info.len());
             assert_eq!(1, req_pred_info.len());
@@ -1037,7 +1687,8 @@ mod tests {
 
             proof_req.requested_attributes.clear();
 
-            let res = prepare_credential_for_proving(req_attrs, req_preds, &proof_req);
+            let res =
+                prepare_credential_for_proving(req_attrs, req_preds, &proof_req, &HashMap::new());
             assert_kind!(Input, res);
         }
 
@@ -1048,7 +1699,8 @@ mod tests {
 
             proof_req.requested_predicates.clear();
 
-            let res = prepare_credential_for_proving(req_attrs, req_preds, &proof_req);
+            let res =
+                prepare_credential_for_proving(req_attrs, req_preds, &proof_req, &HashMap::new());
             assert_kind!(Input, res);
         }
     }
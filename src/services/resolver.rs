@@ -0,0 +1,321 @@
+use std::collections::{HashMap, HashSet};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use crate::data_types::presentation::Presentation;
+use crate::data_types::rev_reg_def::{
+    RevocationRegistryDefinition, RevocationRegistryDefinitionId,
+};
+use crate::data_types::rev_status_list::RevocationStatusList;
+use crate::data_types::schema::{Schema, SchemaId};
+use crate::error::Result;
+use crate::services::types::PresentCredentials;
+
+/// Future returned by an [`AsyncObjectResolver`]
+pub type ResolveFuture<'a, T> = Pin<Box<dyn Future<Output = Result<Arc<T>>> + Send + 'a>>;
+
+/// Source of the objects published on a verifiable data registry, used to fetch them on demand
+/// instead of gathering them in advance.
+///
+/// `resolve_rev_status_list` must return the revocation status list published at exactly
+/// `timestamp`, as referenced by the presentation identifiers.
+pub trait ObjectResolver {
+    fn resolve_schema(&self, id: &SchemaId) -> Result<Arc<Schema>>;
+
+    fn resolve_cred_def(&self, id: &CredentialDefinitionId) -> Result<Arc<CredentialDefinition>>;
+
+    fn resolve_rev_reg_def(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Arc<RevocationRegistryDefinition>>;
+
+    fn resolve_rev_status_list(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Result<Arc<RevocationStatusList>>;
+}
+
+/// Asynchronous variant of [`ObjectResolver`], for registries reached over the network
+pub trait AsyncObjectResolver {
+    fn resolve_schema<'a>(&'a self, id: &'a SchemaId) -> ResolveFuture<'a, Schema>;
+
+    fn resolve_cred_def<'a>(
+        &'a self,
+        id: &'a CredentialDefinitionId,
+    ) -> ResolveFuture<'a, CredentialDefinition>;
+
+    fn resolve_rev_reg_def<'a>(
+        &'a self,
+        id: &'a RevocationRegistryDefinitionId,
+    ) -> ResolveFuture<'a, RevocationRegistryDefinition>;
+
+    fn resolve_rev_status_list<'a>(
+        &'a self,
+        id: &'a RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> ResolveFuture<'a, RevocationStatusList>;
+}
+
+/// Resolver serving objects held in memory, mostly useful for tests
+#[derive(Debug, Default)]
+pub struct InMemoryResolver {
+    schemas: HashMap<SchemaId, Arc<Schema>>,
+    cred_defs: HashMap<CredentialDefinitionId, Arc<CredentialDefinition>>,
+    rev_reg_defs: HashMap<RevocationRegistryDefinitionId, Arc<RevocationRegistryDefinition>>,
+    rev_status_lists: HashMap<(RevocationRegistryDefinitionId, u64), Arc<RevocationStatusList>>,
+}
+
+impl InMemoryResolver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_schema(&mut self, id: SchemaId, schema: impl Into<Arc<Schema>>) -> &mut Self {
+        self.schemas.insert(id, schema.into());
+        self
+    }
+
+    pub fn add_cred_def(
+        &mut self,
+        id: CredentialDefinitionId,
+        cred_def: impl Into<Arc<CredentialDefinition>>,
+    ) -> &mut Self {
+        self.cred_defs.insert(id, cred_def.into());
+        self
+    }
+
+    pub fn add_rev_reg_def(
+        &mut self,
+        id: RevocationRegistryDefinitionId,
+        rev_reg_def: impl Into<Arc<RevocationRegistryDefinition>>,
+    ) -> &mut Self {
+        self.rev_reg_defs.insert(id, rev_reg_def.into());
+        self
+    }
+
+    /// Add a revocation status list, served for its registry definition id and timestamp
+    pub fn add_rev_status_list(
+        &mut self,
+        rev_status_list: impl Into<Arc<RevocationStatusList>>,
+    ) -> Result<&mut Self> {
+        let rev_status_list = rev_status_list.into();
+        let id = rev_status_list
+            .id()
+            .ok_or_else(|| err_msg!("Revocation status list is missing the registry id"))?;
+        let timestamp = rev_status_list
+            .timestamp()
+            .ok_or_else(|| err_msg!("Revocation status list is missing the timestamp"))?;
+        self.rev_status_lists
+            .insert((id, timestamp), rev_status_list);
+        Ok(self)
+    }
+}
+
+impl ObjectResolver for InMemoryResolver {
+    fn resolve_schema(&self, id: &SchemaId) -> Result<Arc<Schema>> {
+        self.schemas
+            .get(id)
+            .cloned()
+            .ok_or_else(|| err_msg!("Schema not found for ID: {}", id))
+    }
+
+    fn resolve_cred_def(&self, id: &CredentialDefinitionId) -> Result<Arc<CredentialDefinition>> {
+        self.cred_defs
+            .get(id)
+            .cloned()
+            .ok_or_else(|| err_msg!("Credential Definition not found for ID: {}", id))
+    }
+
+    fn resolve_rev_reg_def(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Arc<RevocationRegistryDefinition>> {
+        self.rev_reg_defs
+            .get(id)
+            .cloned()
+            .ok_or_else(|| err_msg!("Revocation Registry Definition not found for ID: {}", id))
+    }
+
+    fn resolve_rev_status_list(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> Result<Arc<RevocationStatusList>> {
+        self.rev_status_lists
+            .get(&(id.clone(), timestamp))
+            .cloned()
+            .ok_or_else(|| {
+                err_msg!(
+                    "Revocation Status List not found for ID and timestamp: {}, {}",
+                    id,
+                    timestamp
+                )
+            })
+    }
+}
+
+impl AsyncObjectResolver for InMemoryResolver {
+    fn resolve_schema<'a>(&'a self, id: &'a SchemaId) -> ResolveFuture<'a, Schema> {
+        Box::pin(std::future::ready(ObjectResolver::resolve_schema(self, id)))
+    }
+
+    fn resolve_cred_def<'a>(
+        &'a self,
+        id: &'a CredentialDefinitionId,
+    ) -> ResolveFuture<'a, CredentialDefinition> {
+        Box::pin(std::future::ready(ObjectResolver::resolve_cred_def(
+            self, id,
+        )))
+    }
+
+    fn resolve_rev_reg_def<'a>(
+        &'a self,
+        id: &'a RevocationRegistryDefinitionId,
+    ) -> ResolveFuture<'a, RevocationRegistryDefinition> {
+        Box::pin(std::future::ready(ObjectResolver::resolve_rev_reg_def(
+            self, id,
+        )))
+    }
+
+    fn resolve_rev_status_list<'a>(
+        &'a self,
+        id: &'a RevocationRegistryDefinitionId,
+        timestamp: u64,
+    ) -> ResolveFuture<'a, RevocationStatusList> {
+        Box::pin(std::future::ready(ObjectResolver::resolve_rev_status_list(
+            self, id, timestamp,
+        )))
+    }
+}
+
+/// Identifiers of the objects needed to create or verify a presentation
+#[derive(Debug, Default)]
+pub(crate) struct ObjectRefs {
+    schemas: HashSet<SchemaId>,
+    cred_defs: HashSet<CredentialDefinitionId>,
+    rev_status_lists: HashSet<(RevocationRegistryDefinitionId, u64)>,
+}
+
+impl ObjectRefs {
+    /// The objects referenced by the presentation identifiers, revocation objects are only
+    /// referenced by identifiers carrying a timestamp
+    pub(crate) fn for_presentation(presentation: &Presentation) -> Result<Self> {
+        let mut refs = Self::default();
+        for identifier in &presentation.identifiers {
+            refs.schemas.insert(identifier.schema_id.clone());
+            refs.cred_defs.insert(identifier.cred_def_id.clone());
+            if let (Some(rev_reg_id), Some(timestamp)) =
+                (identifier.rev_reg_id.as_ref(), identifier.timestamp)
+            {
+                // Revocation registry definition id is the same as the rev reg id
+                let rev_reg_def_id = RevocationRegistryDefinitionId::new(rev_reg_id.clone())?;
+                refs.rev_status_lists.insert((rev_reg_def_id, timestamp));
+            }
+        }
+        Ok(refs)
+    }
+
+    /// The schemas and credential definitions of the presented credentials
+    pub(crate) fn for_credentials(credentials: &PresentCredentials) -> Self {
+        let mut refs = Self::default();
+        for present in credentials.0.iter().filter(|present| !present.is_empty()) {
+            refs.schemas.insert(present.cred.schema_id.clone());
+            refs.cred_defs.insert(present.cred.cred_def_id.clone());
+        }
+        refs
+    }
+}
+
+/// Objects fetched from a resolver
+#[derive(Debug, Default)]
+pub(crate) struct ResolvedObjects {
+    schemas: HashMap<SchemaId, Arc<Schema>>,
+    cred_defs: HashMap<CredentialDefinitionId, Arc<CredentialDefinition>>,
+    rev_reg_defs: HashMap<RevocationRegistryDefinitionId, Arc<RevocationRegistryDefinition>>,
+    rev_status_lists: Vec<Arc<RevocationStatusList>>,
+}
+
+impl ResolvedObjects {
+    pub(crate) fn resolve(refs: ObjectRefs, resolver: &impl ObjectResolver) -> Result<Self> {
+        let mut resolved = Self::default();
+        for id in refs.schemas {
+            let schema = resolver.resolve_schema(&id)?;
+            resolved.schemas.insert(id, schema);
+        }
+        for id in refs.cred_defs {
+            let cred_def = resolver.resolve_cred_def(&id)?;
+            resolved.cred_defs.insert(id, cred_def);
+        }
+        for (id, timestamp) in refs.rev_status_lists {
+            if !resolved.rev_reg_defs.contains_key(&id) {
+                let rev_reg_def = resolver.resolve_rev_reg_def(&id)?;
+                resolved.rev_reg_defs.insert(id.clone(), rev_reg_def);
+            }
+            let rev_status_list = resolver.resolve_rev_status_list(&id, timestamp)?;
+            resolved.rev_status_lists.push(rev_status_list);
+        }
+        Ok(resolved)
+    }
+
+    pub(crate) async fn resolve_async(
+        refs: ObjectRefs,
+        resolver: &impl AsyncObjectResolver,
+    ) -> Result<Self> {
+        let mut resolved = Self::default();
+        for id in refs.schemas {
+            let schema = resolver.resolve_schema(&id).await?;
+            resolved.schemas.insert(id, schema);
+        }
+        for id in refs.cred_defs {
+            let cred_def = resolver.resolve_cred_def(&id).await?;
+            resolved.cred_defs.insert(id, cred_def);
+        }
+        for (id, timestamp) in refs.rev_status_lists {
+            if !resolved.rev_reg_defs.contains_key(&id) {
+                let rev_reg_def = resolver.resolve_rev_reg_def(&id).await?;
+                resolved.rev_reg_defs.insert(id.clone(), rev_reg_def);
+            }
+            let rev_status_list = resolver.resolve_rev_status_list(&id, timestamp).await?;
+            resolved.rev_status_lists.push(rev_status_list);
+        }
+        Ok(resolved)
+    }
+
+    pub(crate) fn schemas(&self) -> HashMap<&SchemaId, &Schema> {
+        self.schemas
+            .iter()
+            .map(|(id, s)| (id, s.as_ref()))
+            .collect()
+    }
+
+    pub(crate) fn cred_defs(&self) -> HashMap<&CredentialDefinitionId, &CredentialDefinition> {
+        self.cred_defs
+            .iter()
+            .map(|(id, c)| (id, c.as_ref()))
+            .collect()
+    }
+
+    pub(crate) fn rev_reg_defs(
+        &self,
+    ) -> Option<HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition>> {
+        if self.rev_reg_defs.is_empty() {
+            return None;
+        }
+        Some(
+            self.rev_reg_defs
+                .iter()
+                .map(|(id, d)| (id, d.as_ref()))
+                .collect(),
+        )
+    }
+
+    pub(crate) fn rev_status_lists(&self) -> Option<Vec<&RevocationStatusList>> {
+        if self.rev_status_lists.is_empty() {
+            return None;
+        }
+        Some(self.rev_status_lists.iter().map(AsRef::as_ref).collect())
+    }
+}
@@ -0,0 +1,330 @@
+use std::collections::BTreeSet;
+use std::fmt;
+
+use super::issuer::{
+    create_credential, update_revocation_status_list_timestamp_only,
+    update_revocation_status_list_with_tails_reader,
+};
+use super::tails::{TailsFileReader, TailsReader};
+use super::types::{
+    Credential, CredentialDefinitionPrivate, CredentialOffer, CredentialRequest,
+    CredentialRevocationConfig, CredentialValues, RevocationRegistryAllocator,
+    RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate, RevocationStatusList,
+};
+use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use crate::data_types::rev_reg::RevocationRegistryId;
+use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
+use crate::error::{Result, ValidationError};
+
+/// Revocation registries of a credential definition, managed by its issuer
+///
+/// The set owns the definitions of the registries, their private keys and their revocation
+/// status lists. New credentials are issued with the first registry which has a free revocation
+/// index, in the order the registries were added, and credentials are revoked by registry and
+/// index. Issuances and revocations update the status lists held by the set, which are all
+/// timestamped and returned at once by [`update_status_lists`](Self::update_status_lists), to
+/// be published.
+///
+/// The set is serializable, to be persisted by the issuer along with its private keys.
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RevocationRegistrySet {
+    cred_def_id: CredentialDefinitionId,
+    registries: Vec<ManagedRegistry>,
+}
+
+#[derive(Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ManagedRegistry {
+    rev_reg_def: RevocationRegistryDefinition,
+    rev_reg_def_private: RevocationRegistryDefinitionPrivate,
+    rev_status_list: RevocationStatusList,
+    allocator: RevocationRegistryAllocator,
+    /// Whether the status list changed since it was last returned to be published
+    #[serde(default)]
+    updated: bool,
+}
+
+impl ManagedRegistry {
+    fn id(&self) -> &RevocationRegistryDefinitionId {
+        self.allocator.rev_reg_def_id()
+    }
+}
+
+impl RevocationRegistrySet {
+    pub fn new(
+        cred_def_id: impl TryInto<CredentialDefinitionId, Error = ValidationError>,
+    ) -> Result<Self> {
+        Ok(Self {
+            cred_def_id: cred_def_id.try_into()?,
+            registries: Vec::new(),
+        })
+    }
+
+    #[must_use]
+    pub const fn cred_def_id(&self) -> &CredentialDefinitionId {
+        &self.cred_def_id
+    }
+
+    /// Add a revocation registry no credential was issued with yet, along with its initial
+    /// revocation status list
+    pub fn add_registry(
+        &mut self,
+        rev_reg_def_id: impl TryInto<RevocationRegistryDefinitionId, Error = ValidationError>,
+        rev_reg_def: RevocationRegistryDefinition,
+        rev_reg_def_private: RevocationRegistryDefinitionPrivate,
+        rev_status_list: RevocationStatusList,
+    ) -> Result<()> {
+        let rev_reg_def_id = rev_reg_def_id.try_into()?;
+        trace!(
+            "RevocationRegistrySet::add_registry >>> rev_reg_def_id: {:?}, rev_reg_def: {:?}, rev_status_list: {:?}",
+            rev_reg_def_id,
+            rev_reg_def,
+            rev_status_list
+        );
+
+        if rev_reg_def.cred_def_id != self.cred_def_id {
+            return Err(err_msg!(
+                "Revocation registry {} is for credential definition {}, not {}",
+                rev_reg_def_id,
+                rev_reg_def.cred_def_id,
+                self.cred_def_id
+            ));
+        }
+        if self.registry(&rev_reg_def_id).is_ok() {
+            return Err(err_msg!(
+                "Revocation registry {} was already added",
+                rev_reg_def_id
+            ));
+        }
+        if rev_status_list
+            .id()
+            .map_or(false, |list_id| list_id != rev_reg_def_id)
+        {
+            return Err(err_msg!(
+                "Revocation status list is not the one of revocation registry {}",
+                rev_reg_def_id
+            ));
+        }
+
+        let allocator = RevocationRegistryAllocator::new(rev_reg_def_id.to_string(), &rev_reg_def)?;
+        self.registries.push(ManagedRegistry {
+            rev_reg_def,
+            rev_reg_def_private,
+            rev_status_list,
+            allocator,
+            updated: false,
+        });
+        Ok(())
+    }
+
+    /// The revocation registries of the set, in the order they were added
+    pub fn rev_reg_def_ids(&self) -> impl Iterator<Item = &RevocationRegistryDefinitionId> {
+        self.registries.iter().map(ManagedRegistry::id)
+    }
+
+    pub fn rev_reg_def(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<&RevocationRegistryDefinition> {
+        Ok(&self.registry(rev_reg_def_id)?.rev_reg_def)
+    }
+
+    /// The current revocation status list of a registry, including the changes which were not
+    /// returned by [`update_status_lists`](Self::update_status_lists) yet
+    pub fn rev_status_list(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<&RevocationStatusList> {
+        Ok(&self.registry(rev_reg_def_id)?.rev_status_list)
+    }
+
+    /// The number of credentials which can still be issued with the registries of the set
+    #[must_use]
+    pub fn remaining(&self) -> u32 {
+        self.registries
+            .iter()
+            .map(|registry| registry.allocator.remaining())
+            .sum()
+    }
+
+    /// Issue a credential with the first registry which has a free revocation index, as
+    /// [`issuer::create_credential`](crate::issuer::create_credential)
+    ///
+    /// The tails of the registry the credential is issued with are read with the reader returned
+    /// by `tails_reader`, such as a [`TailsFileReader`](crate::tails::TailsFileReader) for the
+    /// `tails_location` of the registry definition.
+    ///
+    /// The registry and revocation index of the credential are returned along with it. When no
+    /// registry has a free index, a [`RevocationRegistryFull`](crate::ErrorKind) error is raised
+    /// and a new registry must be added.
+    pub fn create_credential<F>(
+        &mut self,
+        cred_def: &CredentialDefinition,
+        cred_def_private: &CredentialDefinitionPrivate,
+        cred_offer: &CredentialOffer,
+        cred_request: &CredentialRequest,
+        cred_values: CredentialValues,
+        tails_reader: F,
+    ) -> Result<(Credential, RevocationRegistryDefinitionId, u32)>
+    where
+        F: FnOnce(
+            &RevocationRegistryDefinitionId,
+            &RevocationRegistryDefinition,
+        ) -> Result<TailsReader>,
+    {
+        if cred_offer.cred_def_id != self.cred_def_id {
+            return Err(err_msg!(
+                "Credential offer is for credential definition {}, not {}",
+                cred_offer.cred_def_id,
+                self.cred_def_id
+            ));
+        }
+        let registry = self
+            .registries
+            .iter_mut()
+            .find(|registry| registry.allocator.remaining() > 0)
+            .ok_or_else(|| {
+                err_msg!(
+                    RevocationRegistryFull,
+                    "No revocation registry left for credential definition {}",
+                    self.cred_def_id
+                )
+            })?;
+        let registry_idx = registry.allocator.allocate()?;
+        let rev_reg_def_id = registry.id().clone();
+
+        let issue = |registry: &ManagedRegistry| -> Result<(Credential, RevocationStatusList)> {
+            let tails_reader = tails_reader(&rev_reg_def_id, &registry.rev_reg_def)?;
+            // The index is only added to the accumulator on issuance when the registry does not
+            // issue all its credentials by default
+            let rev_status_list = update_revocation_status_list_with_tails_reader(
+                None,
+                Some(BTreeSet::from([registry_idx])),
+                None,
+                &registry.rev_reg_def,
+                &registry.rev_status_list,
+                &tails_reader,
+            )?;
+            let credential = create_credential(
+                cred_def,
+                cred_def_private,
+                cred_offer,
+                cred_request,
+                cred_values,
+                Some(RevocationRegistryId::new_unchecked(
+                    rev_reg_def_id.to_string(),
+                )),
+                Some(&registry.rev_status_list),
+                Some(CredentialRevocationConfig {
+                    reg_def: &registry.rev_reg_def,
+                    reg_def_private: &registry.rev_reg_def_private,
+                    registry_idx,
+                    tails_reader,
+                }),
+            )?;
+            Ok((credential, rev_status_list))
+        };
+        let (credential, rev_status_list) = match issue(registry) {
+            Ok(issued) => issued,
+            Err(err) => {
+                registry.allocator.release(registry_idx);
+                return Err(err);
+            }
+        };
+        if rev_status_list.state() != registry.rev_status_list.state() {
+            registry.rev_status_list = rev_status_list;
+            registry.updated = true;
+        }
+
+        Ok((credential, rev_reg_def_id, registry_idx))
+    }
+
+    /// Revoke the credential issued with the revocation index `registry_idx` of a registry
+    ///
+    /// The tails of the registry are read with `tails_reader`, or from the `tails_location` of
+    /// the registry definition when none is given, as in
+    /// [`issuer::update_revocation_status_list`](crate::issuer::update_revocation_status_list).
+    pub fn revoke(
+        &mut self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+        registry_idx: u32,
+        tails_reader: Option<&TailsReader>,
+    ) -> Result<()> {
+        let registry = self
+            .registries
+            .iter_mut()
+            .find(|registry| registry.id() == rev_reg_def_id)
+            .ok_or_else(|| err_msg!("Unknown revocation registry {}", rev_reg_def_id))?;
+        if !registry.allocator.is_used(registry_idx) {
+            return Err(err_msg!(
+                InvalidUserRevocId,
+                "No credential was issued with revocation index {} of revocation registry {}",
+                registry_idx,
+                rev_reg_def_id
+            ));
+        }
+        if registry.rev_status_list.get(registry_idx as usize) == Some(true) {
+            return Ok(());
+        }
+
+        let default_reader;
+        let tails_reader = match tails_reader {
+            Some(reader) => reader,
+            None => {
+                default_reader =
+                    TailsFileReader::new_tails_reader(&registry.rev_reg_def.value.tails_location);
+                &default_reader
+            }
+        };
+        registry.rev_status_list = update_revocation_status_list_with_tails_reader(
+            None,
+            None,
+            Some(BTreeSet::from([registry_idx])),
+            &registry.rev_reg_def,
+            &registry.rev_status_list,
+            tails_reader,
+        )?;
+        registry.updated = true;
+        Ok(())
+    }
+
+    /// Timestamp the revocation status lists which changed since they were last returned, and
+    /// return them to be published, in the order the registries were added
+    pub fn update_status_lists(&mut self, timestamp: u64) -> Vec<RevocationStatusList> {
+        self.registries
+            .iter_mut()
+            .filter(|registry| registry.updated)
+            .map(|registry| {
+                registry.rev_status_list = update_revocation_status_list_timestamp_only(
+                    timestamp,
+                    &registry.rev_status_list,
+                );
+                registry.updated = false;
+                registry.rev_status_list.clone()
+            })
+            .collect()
+    }
+
+    fn registry(
+        &self,
+        rev_reg_def_id: &RevocationRegistryDefinitionId,
+    ) -> Result<&ManagedRegistry> {
+        self.registries
+            .iter()
+            .find(|registry| registry.id() == rev_reg_def_id)
+            .ok_or_else(|| err_msg!("Unknown revocation registry {}", rev_reg_def_id))
+    }
+}
+
+impl fmt::Debug for RevocationRegistrySet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RevocationRegistrySet")
+            .field("cred_def_id", &self.cred_def_id)
+            .field(
+                "rev_reg_def_ids",
+                &self.rev_reg_def_ids().collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
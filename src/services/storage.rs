@@ -0,0 +1,678 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, ErrorKind as IoErrorKind};
+use std::path::{Path, PathBuf};
+
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+#[cfg(feature = "zeroize")]
+use zeroize::Zeroize;
+
+use crate::data_types::cred_def::{CredentialDefinitionId, CredentialDefinitionPrivate};
+use crate::data_types::cred_request::CredentialRequestMetadata;
+use crate::data_types::credential::Credential;
+use crate::data_types::link_secret::LinkSecret;
+use crate::data_types::rev_reg_def::{
+    RevocationRegistryDefinitionId, RevocationRegistryDefinitionPrivate,
+};
+use crate::data_types::rev_status_list::RevocationStatusList;
+use crate::error::Result;
+
+const CRED_DEF_PRIVATE: &str = "credDefPrivate";
+const REV_REG_DEF_PRIVATE: &str = "revRegDefPrivate";
+const REV_STATUS_LIST: &str = "revStatusList";
+const LINK_SECRET: &str = "linkSecret";
+const CRED_REQUEST_METADATA: &str = "credRequestMetadata";
+const CREDENTIAL: &str = "credential";
+
+/// Storage of the private keys and revocation state an issuer keeps between issuances
+pub trait IssuerStore {
+    fn store_cred_def_private(
+        &mut self,
+        id: &CredentialDefinitionId,
+        cred_def_private: &CredentialDefinitionPrivate,
+    ) -> Result<()>;
+
+    fn cred_def_private(
+        &self,
+        id: &CredentialDefinitionId,
+    ) -> Result<Option<CredentialDefinitionPrivate>>;
+
+    fn store_rev_reg_def_private(
+        &mut self,
+        id: &RevocationRegistryDefinitionId,
+        rev_reg_def_private: &RevocationRegistryDefinitionPrivate,
+    ) -> Result<()>;
+
+    fn rev_reg_def_private(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationRegistryDefinitionPrivate>>;
+
+    /// Store the current revocation status list of its registry, replacing the previous one
+    fn store_rev_status_list(&mut self, rev_status_list: &RevocationStatusList) -> Result<()>;
+
+    /// The current revocation status list of a registry
+    fn rev_status_list(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationStatusList>>;
+}
+
+/// Storage of the link secrets, pending credential requests and credentials of a holder
+///
+/// Link secrets and credentials are stored under an identifier chosen by the holder, and the
+/// metadata of a credential request until the credential is received.
+pub trait HolderStore {
+    fn store_link_secret(&mut self, id: &str, link_secret: &LinkSecret) -> Result<()>;
+
+    fn link_secret(&self, id: &str) -> Result<Option<LinkSecret>>;
+
+    fn store_cred_request_metadata(
+        &mut self,
+        id: &str,
+        cred_request_metadata: &CredentialRequestMetadata,
+    ) -> Result<()>;
+
+    fn cred_request_metadata(&self, id: &str) -> Result<Option<CredentialRequestMetadata>>;
+
+    /// Remove the metadata of a credential request, returning whether it was stored
+    fn remove_cred_request_metadata(&mut self, id: &str) -> Result<bool>;
+
+    fn store_credential(&mut self, id: &str, credential: &Credential) -> Result<()>;
+
+    fn credential(&self, id: &str) -> Result<Option<Credential>>;
+
+    /// The identifiers of the stored credentials, in lexicographic order
+    fn credential_ids(&self) -> Result<Vec<String>>;
+
+    /// Remove a credential, returning whether it was stored
+    fn remove_credential(&mut self, id: &str) -> Result<bool>;
+}
+
+/// Issuer and holder store keeping its records in memory, mostly useful for tests
+///
+/// Records are held in their JSON form, as written by [`FileStore`]. With the `zeroize` feature
+/// enabled, link secrets are zeroized when they are replaced and when the store is dropped.
+#[derive(Default)]
+pub struct InMemoryStore {
+    records: BTreeMap<String, BTreeMap<String, Value>>,
+}
+
+impl InMemoryStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, category: &str, id: &str, value: Value) -> Option<Value> {
+        self.records
+            .entry(category.to_owned())
+            .or_default()
+            .insert(id.to_owned(), value)
+    }
+
+    fn insert_json<T: Serialize>(&mut self, category: &str, id: &str, record: &T) -> Result<()> {
+        let value = serde_json::to_value(record)?;
+        self.insert(category, id, value);
+        Ok(())
+    }
+
+    fn get(&self, category: &str, id: &str) -> Option<&Value> {
+        self.records
+            .get(category)
+            .and_then(|records| records.get(id))
+    }
+
+    fn get_json<T: DeserializeOwned>(&self, category: &str, id: &str) -> Result<Option<T>> {
+        self.get(category, id)
+            .map(|value| {
+                serde_json::from_value(value.clone()).map_err(|err| {
+                    err_msg!(
+                        InvalidState,
+                        "Stored {} {} could not be read: {}",
+                        category,
+                        id,
+                        err
+                    )
+                })
+            })
+            .transpose()
+    }
+
+    fn remove(&mut self, category: &str, id: &str) -> bool {
+        self.records
+            .get_mut(category)
+            .map_or(false, |records| records.remove(id).is_some())
+    }
+
+    fn ids(&self, category: &str) -> Vec<String> {
+        self.records
+            .get(category)
+            .map(|records| records.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// The identifiers of the records of each category, leaving the records out
+    fn record_ids(&self) -> BTreeMap<&str, Vec<&str>> {
+        self.records
+            .iter()
+            .map(|(category, records)| {
+                (
+                    category.as_str(),
+                    records.keys().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+impl fmt::Debug for InMemoryStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InMemoryStore")
+            .field("records", &self.record_ids())
+            .finish()
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl Drop for InMemoryStore {
+    fn drop(&mut self) {
+        if let Some(link_secrets) = self.records.get_mut(LINK_SECRET) {
+            link_secrets.values_mut().for_each(zeroize_link_secret);
+        }
+    }
+}
+
+#[cfg(feature = "zeroize")]
+fn zeroize_link_secret(value: &mut Value) {
+    if let Value::String(link_secret) = value {
+        link_secret.zeroize();
+    }
+}
+
+#[cfg(not(feature = "zeroize"))]
+fn zeroize_link_secret(_value: &mut Value) {}
+
+impl IssuerStore for InMemoryStore {
+    fn store_cred_def_private(
+        &mut self,
+        id: &CredentialDefinitionId,
+        cred_def_private: &CredentialDefinitionPrivate,
+    ) -> Result<()> {
+        self.insert_json(CRED_DEF_PRIVATE, &id.0, cred_def_private)
+    }
+
+    fn cred_def_private(
+        &self,
+        id: &CredentialDefinitionId,
+    ) -> Result<Option<CredentialDefinitionPrivate>> {
+        self.get_json(CRED_DEF_PRIVATE, &id.0)
+    }
+
+    fn store_rev_reg_def_private(
+        &mut self,
+        id: &RevocationRegistryDefinitionId,
+        rev_reg_def_private: &RevocationRegistryDefinitionPrivate,
+    ) -> Result<()> {
+        self.insert_json(REV_REG_DEF_PRIVATE, &id.0, rev_reg_def_private)
+    }
+
+    fn rev_reg_def_private(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationRegistryDefinitionPrivate>> {
+        self.get_json(REV_REG_DEF_PRIVATE, &id.0)
+    }
+
+    fn store_rev_status_list(&mut self, rev_status_list: &RevocationStatusList) -> Result<()> {
+        let id = rev_status_list
+            .id()
+            .ok_or_else(|| err_msg!("Revocation status list is missing the registry id"))?;
+        self.insert_json(REV_STATUS_LIST, &id.0, rev_status_list)
+    }
+
+    fn rev_status_list(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationStatusList>> {
+        self.get_json(REV_STATUS_LIST, &id.0)
+    }
+}
+
+impl HolderStore for InMemoryStore {
+    fn store_link_secret(&mut self, id: &str, link_secret: &LinkSecret) -> Result<()> {
+        let link_secret: String = link_secret.try_clone()?.try_into()?;
+        if let Some(mut previous) = self.insert(LINK_SECRET, id, Value::String(link_secret)) {
+            zeroize_link_secret(&mut previous);
+        }
+        Ok(())
+    }
+
+    fn link_secret(&self, id: &str) -> Result<Option<LinkSecret>> {
+        match self.get(LINK_SECRET, id) {
+            Some(Value::String(link_secret)) => {
+                Ok(Some(LinkSecret::try_from(link_secret.as_str())?))
+            }
+            Some(_) => Err(err_msg!(
+                InvalidState,
+                "Stored {} {} is not a string",
+                LINK_SECRET,
+                id
+            )),
+            None => Ok(None),
+        }
+    }
+
+    fn store_cred_request_metadata(
+        &mut self,
+        id: &str,
+        cred_request_metadata: &CredentialRequestMetadata,
+    ) -> Result<()> {
+        self.insert_json(CRED_REQUEST_METADATA, id, cred_request_metadata)
+    }
+
+    fn cred_request_metadata(&self, id: &str) -> Result<Option<CredentialRequestMetadata>> {
+        self.get_json(CRED_REQUEST_METADATA, id)
+    }
+
+    fn remove_cred_request_metadata(&mut self, id: &str) -> Result<bool> {
+        Ok(self.remove(CRED_REQUEST_METADATA, id))
+    }
+
+    fn store_credential(&mut self, id: &str, credential: &Credential) -> Result<()> {
+        self.insert_json(CREDENTIAL, id, credential)
+    }
+
+    fn credential(&self, id: &str) -> Result<Option<Credential>> {
+        self.get_json(CREDENTIAL, id)
+    }
+
+    fn credential_ids(&self) -> Result<Vec<String>> {
+        Ok(self.ids(CREDENTIAL))
+    }
+
+    fn remove_credential(&mut self, id: &str) -> Result<bool> {
+        Ok(self.remove(CREDENTIAL, id))
+    }
+}
+
+/// Issuer and holder store keeping its records in a JSON file
+///
+/// The records are loaded when the store is opened, and the file is rewritten on every change.
+/// The new content is written to a temporary file next to it first, so that the file is never
+/// left partially written. The file holds private keys and link secrets in the clear: it must be
+/// protected accordingly.
+pub struct FileStore {
+    path: PathBuf,
+    store: InMemoryStore,
+}
+
+impl FileStore {
+    /// Open the store kept in the file at `path`, which is created on the first change if it does
+    /// not exist
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let records = match File::open(&path) {
+            Ok(file) => serde_json::from_reader(BufReader::new(file)).map_err(|err| {
+                err_msg!(
+                    InvalidState,
+                    "Store file {} could not be read: {}",
+                    path.display(),
+                    err
+                )
+            })?,
+            Err(err) if err.kind() == IoErrorKind::NotFound => BTreeMap::new(),
+            Err(err) => return Err(err.into()),
+        };
+        Ok(Self {
+            path,
+            store: InMemoryStore { records },
+        })
+    }
+
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Apply a change to the records and write them to the file
+    ///
+    /// The records are reloaded from the file when it cannot be written, so that the store
+    /// always matches the file.
+    fn update<T>(&mut self, change: impl FnOnce(&mut InMemoryStore) -> Result<T>) -> Result<T> {
+        let result = change(&mut self.store)?;
+        if let Err(err) = self.save() {
+            self.store = Self::open(&self.path)?.store;
+            return Err(err);
+        }
+        Ok(result)
+    }
+
+    fn save(&self) -> Result<()> {
+        let dir = match self.path.parent() {
+            Some(dir) if !dir.as_os_str().is_empty() => dir,
+            _ => Path::new("."),
+        };
+        let mut file = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(BufWriter::new(&mut file), &self.store.records)?;
+        file.as_file().sync_all()?;
+        file.persist(&self.path).map_err(|err| err.error)?;
+        Ok(())
+    }
+}
+
+impl fmt::Debug for FileStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileStore")
+            .field("path", &self.path)
+            .field("records", &self.store.record_ids())
+            .finish()
+    }
+}
+
+impl IssuerStore for FileStore {
+    fn store_cred_def_private(
+        &mut self,
+        id: &CredentialDefinitionId,
+        cred_def_private: &CredentialDefinitionPrivate,
+    ) -> Result<()> {
+        self.update(|store| store.store_cred_def_private(id, cred_def_private))
+    }
+
+    fn cred_def_private(
+        &self,
+        id: &CredentialDefinitionId,
+    ) -> Result<Option<CredentialDefinitionPrivate>> {
+        self.store.cred_def_private(id)
+    }
+
+    fn store_rev_reg_def_private(
+        &mut self,
+        id: &RevocationRegistryDefinitionId,
+        rev_reg_def_private: &RevocationRegistryDefinitionPrivate,
+    ) -> Result<()> {
+        self.update(|store| store.store_rev_reg_def_private(id, rev_reg_def_private))
+    }
+
+    fn rev_reg_def_private(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationRegistryDefinitionPrivate>> {
+        self.store.rev_reg_def_private(id)
+    }
+
+    fn store_rev_status_list(&mut self, rev_status_list: &RevocationStatusList) -> Result<()> {
+        self.update(|store| store.store_rev_status_list(rev_status_list))
+    }
+
+    fn rev_status_list(
+        &self,
+        id: &RevocationRegistryDefinitionId,
+    ) -> Result<Option<RevocationStatusList>> {
+        self.store.rev_status_list(id)
+    }
+}
+
+impl HolderStore for FileStore {
+    fn store_link_secret(&mut self, id: &str, link_secret: &LinkSecret) -> Result<()> {
+        self.update(|store| store.store_link_secret(id, link_secret))
+    }
+
+    fn link_secret(&self, id: &str) -> Result<Option<LinkSecret>> {
+        self.store.link_secret(id)
+    }
+
+    fn store_cred_request_metadata(
+        &mut self,
+        id: &str,
+        cred_request_metadata: &CredentialRequestMetadata,
+    ) -> Result<()> {
+        self.update(|store| store.store_cred_request_metadata(id, cred_request_metadata))
+    }
+
+    fn cred_request_metadata(&self, id: &str) -> Result<Option<CredentialRequestMetadata>> {
+        self.store.cred_request_metadata(id)
+    }
+
+    fn remove_cred_request_metadata(&mut self, id: &str) -> Result<bool> {
+        if self.store.get(CRED_REQUEST_METADATA, id).is_none() {
+            return Ok(false);
+        }
+        self.update(|store| store.remove_cred_request_metadata(id))
+    }
+
+    fn store_credential(&mut self, id: &str, credential: &Credential) -> Result<()> {
+        self.update(|store| store.store_credential(id, credential))
+    }
+
+    fn credential(&self, id: &str) -> Result<Option<Credential>> {
+        self.store.credential(id)
+    }
+
+    fn credential_ids(&self) -> Result<Vec<String>> {
+        self.store.credential_ids()
+    }
+
+    fn remove_credential(&mut self, id: &str) -> Result<bool> {
+        if self.store.get(CREDENTIAL, id).is_none() {
+            return Ok(false);
+        }
+        self.update(|store| store.remove_credential(id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::issuer::{
+        create_credential, create_credential_definition, create_credential_offer, create_schema,
+    };
+    use crate::prover::create_credential_request;
+    use crate::types::{CredentialDefinitionConfig, MakeCredentialValues, SignatureType};
+
+    fn cred_def_private() -> CredentialDefinitionPrivate {
+        let schema = create_schema("name", "1.0", "sample:uri", vec!["name".to_owned()].into())
+            .expect("Error creating schema");
+        let (_, cred_def_private, _) = create_credential_definition(
+            "schema:id",
+            &schema,
+            "sample:id",
+            "default",
+            SignatureType::CL,
+            CredentialDefinitionConfig::default(),
+        )
+        .expect("Error creating credential definition");
+        cred_def_private
+    }
+
+    fn issue_credential() -> (CredentialRequestMetadata, Credential) {
+        let schema = create_schema("name", "1.0", "sample:uri", vec!["name".to_owned()].into())
+            .expect("Error creating schema");
+        let (cred_def, cred_def_private, key_correctness_proof) = create_credential_definition(
+            "schema:id",
+            &schema,
+            "sample:id",
+            "default",
+            SignatureType::CL,
+            CredentialDefinitionConfig::default(),
+        )
+        .expect("Error creating credential definition");
+        let cred_offer = create_credential_offer("schema:id", "cred:def", &key_correctness_proof)
+            .expect("Error creating credential offer");
+        let link_secret = LinkSecret::new().unwrap();
+        let (cred_request, cred_request_metadata) = create_credential_request(
+            Some("entropy"),
+            None,
+            &cred_def,
+            &link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+        let mut cred_values = MakeCredentialValues::default();
+        cred_values.add_raw("name", "Alex").unwrap();
+        let credential = create_credential(
+            &cred_def,
+            &cred_def_private,
+            &cred_offer,
+            &cred_request,
+            cred_values.into(),
+            None,
+            None,
+            None,
+        )
+        .expect("Error creating credential");
+        (cred_request_metadata, credential)
+    }
+
+    fn rev_status_list(rev_reg_def_id: Option<&str>) -> RevocationStatusList {
+        let mut list = json!({
+            "issuerId": "sample:id",
+            "revocationList": [0, 1, 0],
+            "currentAccumulator": "1 1379509F4D411630D308A5ABB4F422FCE6737B330B1C5FD286AA5C26F2061E60 1 235535CC45D4816C7686C5A402A230B35A62DDE82B4A652E384FD31912C4E4BB 1 0C94B61595FCAEFC892BB98A27D524C97ED0B7ED1CC49AD6F178A59D4199C9A4 1 172482285606DEE8500FC8A13E6A35EC071F8B84F0EB4CD3DD091C0B4CD30E5E 2 095E45DDF417D05FB10933FFC63D474548B7FFFF7888802F07FFFFFF7D07A8A8 1 0000000000000000000000000000000000000000000000000000000000000000",
+            "timestamp": 1234
+        });
+        if let Some(rev_reg_def_id) = rev_reg_def_id {
+            list["revRegDefId"] = json!(rev_reg_def_id);
+        }
+        serde_json::from_value(list).expect("Error creating revocation status list")
+    }
+
+    fn check_rev_status_lists(store: &mut impl IssuerStore) {
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked("rev:reg");
+        assert!(store.rev_status_list(&rev_reg_def_id).unwrap().is_none());
+
+        store
+            .store_rev_status_list(&rev_status_list(Some("rev:reg")))
+            .unwrap();
+        let stored = store.rev_status_list(&rev_reg_def_id).unwrap().unwrap();
+        assert_eq!(json!(stored), json!(rev_status_list(Some("rev:reg"))));
+
+        // The registry of a status list is needed to store it
+        assert!(store.store_rev_status_list(&rev_status_list(None)).is_err());
+    }
+
+    fn check_holder_records(store: &mut impl HolderStore) {
+        let (cred_request_metadata, credential) = issue_credential();
+
+        store
+            .store_cred_request_metadata("request", &cred_request_metadata)
+            .unwrap();
+        assert_eq!(
+            json!(store.cred_request_metadata("request").unwrap().unwrap()),
+            json!(cred_request_metadata)
+        );
+        assert!(store.remove_cred_request_metadata("request").unwrap());
+        assert!(store.cred_request_metadata("request").unwrap().is_none());
+        assert!(!store.remove_cred_request_metadata("request").unwrap());
+
+        store.store_credential("cred2", &credential).unwrap();
+        store.store_credential("cred1", &credential).unwrap();
+        assert_eq!(store.credential_ids().unwrap(), ["cred1", "cred2"]);
+        assert_eq!(
+            json!(store.credential("cred1").unwrap().unwrap()),
+            json!(credential)
+        );
+        assert!(store.remove_credential("cred1").unwrap());
+        assert!(store.credential("cred1").unwrap().is_none());
+        assert!(!store.remove_credential("cred1").unwrap());
+        assert_eq!(store.credential_ids().unwrap(), ["cred2"]);
+    }
+
+    #[test]
+    fn test_in_memory_store() {
+        let mut store = InMemoryStore::new();
+        let cred_def_id = CredentialDefinitionId::new_unchecked("cred:def");
+        assert!(store.cred_def_private(&cred_def_id).unwrap().is_none());
+
+        let cred_def_private = cred_def_private();
+        store
+            .store_cred_def_private(&cred_def_id, &cred_def_private)
+            .unwrap();
+        assert_eq!(
+            json!(store.cred_def_private(&cred_def_id).unwrap().unwrap()),
+            json!(cred_def_private)
+        );
+
+        let link_secret = LinkSecret::new().unwrap();
+        store.store_link_secret("default", &link_secret).unwrap();
+        assert_eq!(
+            store.link_secret("default").unwrap().unwrap().0,
+            link_secret.0
+        );
+
+        assert!(store.credential_ids().unwrap().is_empty());
+        assert!(!store.remove_credential("cred").unwrap());
+    }
+
+    #[test]
+    fn test_file_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+        let cred_def_id = CredentialDefinitionId::new_unchecked("cred:def");
+        let cred_def_private = cred_def_private();
+        let link_secret = LinkSecret::new().unwrap();
+
+        let mut store = FileStore::open(&path).unwrap();
+        assert!(!path.exists());
+        store
+            .store_cred_def_private(&cred_def_id, &cred_def_private)
+            .unwrap();
+        store.store_link_secret("default", &link_secret).unwrap();
+
+        let store = FileStore::open(&path).unwrap();
+        assert_eq!(
+            json!(store.cred_def_private(&cred_def_id).unwrap().unwrap()),
+            json!(cred_def_private)
+        );
+        assert_eq!(
+            store.link_secret("default").unwrap().unwrap().0,
+            link_secret.0
+        );
+
+        std::fs::write(&path, "not json").unwrap();
+        assert!(FileStore::open(&path).is_err());
+    }
+
+    #[test]
+    fn test_store_rev_status_lists() {
+        check_rev_status_lists(&mut InMemoryStore::new());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+        check_rev_status_lists(&mut FileStore::open(&path).unwrap());
+        assert!(FileStore::open(&path)
+            .unwrap()
+            .rev_status_list(&RevocationRegistryDefinitionId::new_unchecked("rev:reg"))
+            .unwrap()
+            .is_some());
+    }
+
+    #[test]
+    fn test_store_holder_records() {
+        check_holder_records(&mut InMemoryStore::new());
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("store.json");
+        check_holder_records(&mut FileStore::open(&path).unwrap());
+        let store = FileStore::open(&path).unwrap();
+        assert_eq!(store.credential_ids().unwrap(), ["cred2"]);
+        assert!(store.cred_request_metadata("request").unwrap().is_none());
+    }
+
+    #[test]
+    fn test_store_debug_leaves_records_out() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut store = FileStore::open(dir.path().join("store.json")).unwrap();
+        let link_secret = LinkSecret::new().unwrap();
+        store.store_link_secret("default", &link_secret).unwrap();
+        let link_secret: String = link_secret.try_into().unwrap();
+
+        let debug = format!("{:?}", store);
+        assert!(debug.contains("linkSecret"));
+        assert!(debug.contains("default"));
+        assert!(!debug.contains(&link_secret));
+        assert!(!format!("{:?}", store.store).contains(&link_secret));
+    }
+}
@@ -1,12 +1,15 @@
 use std::cell::RefCell;
 use std::fs::File;
+use std::future::Future;
 use std::io::{Read, Seek, SeekFrom, Write};
 use std::path::PathBuf;
+use std::pin::Pin;
 
 use crate::utils::base58;
 use sha2::{Digest, Sha256};
 use tempfile;
 
+use crate::data_types::rev_reg_def::RevocationRegistryDefinition;
 use crate::error::Result;
 use crate::ursa::{
     cl::{RevocationTailsAccessor, RevocationTailsGenerator, Tail},
@@ -15,20 +18,156 @@ use crate::ursa::{
 
 const TAILS_BLOB_TAG_SZ: u8 = 2;
 const TAIL_SIZE: usize = Tail::BYTES_REPR_SIZE;
+const TAILS_VERSION: [u8; 2] = [0, 2];
 
+/// Source of tails data used when building or updating revocation state
+///
+/// Wraps any [`TailsReaderImpl`] backend, so tails files stored outside the
+/// local filesystem (in memory, behind a URL) can be plugged into the
+/// revocation APIs. The crate ships no object store client: implement
+/// [`AsyncTailsSource`] or [`TailsReaderImpl`] over one
 #[derive(Debug)]
 pub struct TailsReader {
     inner: Box<RefCell<dyn TailsReaderImpl>>,
+    verify: bool,
+    verified_hash: RefCell<Option<String>>,
 }
 
 impl TailsReader {
-    pub(crate) fn new<TR: TailsReaderImpl + 'static>(inner: TR) -> Self {
+    /// Wrap a custom tails backend
+    pub fn new<TR: TailsReaderImpl + 'static>(inner: TR) -> Self {
         Self {
             inner: Box::new(RefCell::new(inner)),
+            verify: false,
+            verified_hash: RefCell::new(None),
         }
     }
+
+    /// Run [`verify_tails_file`] against the revocation registry definition before the tails
+    /// are first used by the issuer and prover revocation functions
+    #[must_use]
+    pub fn with_verification(mut self) -> Self {
+        self.verify = true;
+        self
+    }
+
+    /// Verify the tails for `rev_reg_def` if verification was requested and the tails were not
+    /// already verified against the same tails hash
+    pub(crate) fn ensure_verified(&self, rev_reg_def: &RevocationRegistryDefinition) -> Result<()> {
+        if !self.verify
+            || self.verified_hash.borrow().as_deref() == Some(&rev_reg_def.value.tails_hash)
+        {
+            return Ok(());
+        }
+        verify_tails_file(rev_reg_def, self)?;
+        self.verified_hash
+            .replace(Some(rev_reg_def.value.tails_hash.clone()));
+        Ok(())
+    }
+}
+
+/// Check the tails file against a revocation registry definition
+///
+/// The SHA-256 hash must match `tails_hash`, the file must start with the version tag and it must
+/// hold exactly the `2 * max_cred_num + 1` tails generated for the registry. Mismatches are
+/// reported as [`ErrorKind::InvalidTailsFile`](crate::ErrorKind::InvalidTailsFile)
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::tails::{self, TailsBufferReader, TailsBufferWriter};
+/// use anoncreds::types::{CredentialDefinitionConfig, RegistryType, SignatureType};
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, _cred_def_priv, _key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig {
+///                                             support_revocation: true
+///                                          },
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let mut tw = TailsBufferWriter::new(None);
+/// let (rev_reg_def, _rev_reg_def_priv) =
+///     issuer::create_revocation_registry_def(&cred_def,
+///                                            "did:web:xyz/resource/cred-def",
+///                                            "did:web:xyz",
+///                                            "default-tag",
+///                                            RegistryType::CL_ACCUM,
+///                                            10,
+///                                            &mut tw
+///                                            ).expect("Unable to create revocation registry");
+///
+/// let tails_reader = TailsBufferReader::new_tails_reader(tw.into_data());
+/// tails::verify_tails_file(&rev_reg_def, &tails_reader).expect("Invalid tails file");
+/// ```
+pub fn verify_tails_file(
+    rev_reg_def: &RevocationRegistryDefinition,
+    reader: &TailsReader,
+) -> Result<()> {
+    trace!(
+        "verify_tails_file >>> rev_reg_def: {:?}, reader: {:?}",
+        rev_reg_def,
+        reader
+    );
+
+    let mut inner = reader.inner.borrow_mut();
+
+    let hash = base58::encode(inner.hash()?);
+    if hash != rev_reg_def.value.tails_hash {
+        return Err(err_msg!(
+            InvalidTailsFile,
+            "Tails hash mismatch: expected {}, found {}",
+            rev_reg_def.value.tails_hash,
+            hash
+        ));
+    }
+
+    let version = inner
+        .read(TAILS_BLOB_TAG_SZ as usize, 0)
+        .map_err(|_| err_msg!(InvalidTailsFile, "Tails file is missing the version tag"))?;
+    if version != TAILS_VERSION {
+        return Err(err_msg!(
+            InvalidTailsFile,
+            "Unsupported tails file version: {:?}",
+            version
+        ));
+    }
+
+    let tails_count = 2 * rev_reg_def.value.max_cred_num as usize + 1;
+    let size = tails_file_size(rev_reg_def.value.max_cred_num);
+    if inner.read(TAIL_SIZE, size - TAIL_SIZE).is_err() || inner.read(1, size).is_ok() {
+        return Err(err_msg!(
+            InvalidTailsFile,
+            "Tails file does not hold the expected {} tails",
+            tails_count
+        ));
+    }
+
+    trace!("verify_tails_file <<< res: ()");
+    Ok(())
 }
 
+/// Size in bytes of the tails file of a revocation registry holding `max_cred_num` credentials
+fn tails_file_size(max_cred_num: u32) -> usize {
+    TAILS_BLOB_TAG_SZ as usize + (2 * max_cred_num as usize + 1) * TAIL_SIZE
+}
+
+/// Backend for a [`TailsReader`]
+///
+/// Reads are synchronous because the accumulator computations consume tails
+/// synchronously. Asynchronous sources implement [`AsyncTailsSource`] instead,
+/// which fetches the whole tails file up front
 pub trait TailsReaderImpl: std::fmt::Debug + Send {
     fn hash(&mut self) -> Result<Vec<u8>>;
     fn read(&mut self, size: usize, offset: usize) -> Result<Vec<u8>>;
@@ -64,6 +203,43 @@ impl RevocationTailsAccessor for TailsReader {
     }
 }
 
+/// Tails of a revocation registry read into memory at once, to be shared between threads
+pub(crate) struct LoadedTails(Vec<Tail>);
+
+impl TailsReader {
+    /// Read the `2 * max_cred_num + 1` tails of a revocation registry into memory
+    pub(crate) fn load(&self, rev_reg_def: &RevocationRegistryDefinition) -> Result<LoadedTails> {
+        self.ensure_verified(rev_reg_def)?;
+        let tails_count = 2 * rev_reg_def.value.max_cred_num as usize + 1;
+        let bytes = self
+            .inner
+            .borrow_mut()
+            .read(tails_count * TAIL_SIZE, TAILS_BLOB_TAG_SZ as usize)?;
+        let tails = bytes
+            .chunks(TAIL_SIZE)
+            .map(Tail::from_bytes)
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        Ok(LoadedTails(tails))
+    }
+}
+
+impl RevocationTailsAccessor for LoadedTails {
+    fn access_tail(
+        &self,
+        tail_id: u32,
+        accessor: &mut dyn FnMut(&Tail),
+    ) -> std::result::Result<(), UrsaCryptoError> {
+        let tail = self.0.get(tail_id as usize).ok_or_else(|| {
+            UrsaCryptoError::from_msg(
+                UrsaCryptoErrorKind::InvalidState,
+                format!("Tail {} is out of the loaded tails", tail_id),
+            )
+        })?;
+        accessor(tail);
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct TailsFileReader {
     path: String,
@@ -128,38 +304,221 @@ impl TailsReaderImpl for TailsFileReader {
     }
 }
 
+/// In-memory tails reader, for tails files that have already been fetched
+/// from a remote source or generated in memory
+#[derive(Debug)]
+pub struct TailsBufferReader {
+    data: Vec<u8>,
+    hash: Option<Vec<u8>>,
+}
+
+impl TailsBufferReader {
+    #[must_use]
+    pub fn new_tails_reader(data: Vec<u8>) -> TailsReader {
+        TailsReader::new(Self { data, hash: None })
+    }
+}
+
+impl TailsReaderImpl for TailsBufferReader {
+    fn hash(&mut self) -> Result<Vec<u8>> {
+        if self.hash.is_none() {
+            self.hash = Some(Sha256::digest(&self.data).to_vec());
+        }
+        Ok(self.hash.as_ref().unwrap().clone())
+    }
+
+    fn read(&mut self, size: usize, offset: usize) -> Result<Vec<u8>> {
+        read_slice(&self.data, size, offset)
+    }
+}
+
+/// Future returned by an [`AsyncTailsSource`]
+pub type TailsFuture<'a> = Pin<Box<dyn Future<Output = Result<Vec<u8>>> + Send + 'a>>;
+
+/// Asynchronous source of tails files, such as an HTTP client or an object store
+///
+/// This is the extension point for object stores: the crate only provides the trait, the
+/// client is supplied by the caller
+pub trait AsyncTailsSource {
+    /// Fetch the whole tails file stored at `location`
+    fn fetch<'a>(&'a self, location: &'a str) -> TailsFuture<'a>;
+}
+
+impl TailsBufferReader {
+    /// Fetch the tails file of `rev_reg_def` from its `tails_location` and verify it
+    ///
+    /// The returned reader holds the tails in memory and can be passed to the synchronous
+    /// revocation functions
+    pub async fn fetch_tails_reader(
+        source: &impl AsyncTailsSource,
+        rev_reg_def: &RevocationRegistryDefinition,
+    ) -> Result<TailsReader> {
+        let data = source.fetch(&rev_reg_def.value.tails_location).await?;
+        let reader = Self::new_tails_reader(data).with_verification();
+        reader.ensure_verified(rev_reg_def)?;
+        Ok(reader)
+    }
+}
+
+/// Tails reader fetching the tails file from an HTTP(S) URL
+///
+/// The file is downloaded on first access and kept in memory afterwards.
+/// The download blocks the calling thread; use [`AsyncTailsSource`] with an
+/// asynchronous HTTP client from async code. Downloads larger than the tails
+/// file of a registry holding `max_cred_num` credentials are rejected
+#[cfg(feature = "http")]
+#[derive(Debug)]
+pub struct TailsUrlReader {
+    url: String,
+    max_size: usize,
+    data: Option<Vec<u8>>,
+    hash: Option<Vec<u8>>,
+}
+
+#[cfg(feature = "http")]
+impl TailsUrlReader {
+    #[must_use]
+    pub fn new_tails_reader(url: &str, max_cred_num: u32) -> TailsReader {
+        TailsReader::new(Self {
+            url: url.to_owned(),
+            max_size: tails_file_size(max_cred_num),
+            data: None,
+            hash: None,
+        })
+    }
+
+    pub fn fetch(&mut self) -> Result<()> {
+        if self.data.is_none() {
+            let response = ureq::get(&self.url).call().map_err(|err| {
+                err_msg!(IOError, "Error fetching tails file {}: {}", self.url, err)
+            })?;
+            let mut data = Vec::new();
+            response
+                .into_reader()
+                .take(self.max_size as u64 + 1)
+                .read_to_end(&mut data)?;
+            if data.len() > self.max_size {
+                return Err(err_msg!(
+                    InvalidTailsFile,
+                    "Tails file {} exceeds the expected size of {} bytes",
+                    self.url,
+                    self.max_size
+                ));
+            }
+            debug!(
+                "TailsUrlReader: fetched tails file [size {}]: {}",
+                data.len(),
+                self.url
+            );
+            self.data.replace(data);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "http")]
+impl TailsReaderImpl for TailsUrlReader {
+    fn hash(&mut self) -> Result<Vec<u8>> {
+        if self.hash.is_none() {
+            self.fetch()?;
+            self.hash = Some(Sha256::digest(self.data.as_ref().unwrap()).to_vec());
+        }
+        Ok(self.hash.as_ref().unwrap().clone())
+    }
+
+    fn read(&mut self, size: usize, offset: usize) -> Result<Vec<u8>> {
+        self.fetch()?;
+        read_slice(self.data.as_ref().unwrap(), size, offset)
+    }
+}
+
+fn read_slice(data: &[u8], size: usize, offset: usize) -> Result<Vec<u8>> {
+    offset
+        .checked_add(size)
+        .and_then(|end| data.get(offset..end))
+        .map(<[u8]>::to_vec)
+        .ok_or_else(|| {
+            err_msg!(
+                IOError,
+                "Tails read out of bounds: offset {}, size {}, length {}",
+                offset,
+                size,
+                data.len()
+            )
+        })
+}
+
 pub trait TailsWriter: std::fmt::Debug {
     fn write(&mut self, generator: &mut RevocationTailsGenerator) -> Result<(String, String)>;
 }
 
+/// Callback reporting tails generation progress as `(generated, total)` tails
+pub type TailsProgressCallback = Box<dyn FnMut(u32, u32) + Send>;
+
+#[derive(Default)]
+struct TailsProgress(Option<TailsProgressCallback>);
+
+impl std::fmt::Debug for TailsProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("TailsProgress")
+            .field(&self.0.as_ref().map(|_| "<callback>"))
+            .finish()
+    }
+}
+
+/// Generate all tails into `sink`, returning the written size and the base58 tails hash
+fn write_tails<W: Write>(
+    generator: &mut RevocationTailsGenerator,
+    sink: &mut W,
+    progress: &mut TailsProgress,
+) -> Result<(u64, String)> {
+    let mut hasher = Sha256::default();
+    let version = &TAILS_VERSION;
+    sink.write_all(version)?;
+    hasher.update(version);
+    let mut size = version.len() as u64;
+    let total = generator.count();
+    let mut generated = 0;
+    while let Some(tail) = generator.try_next()? {
+        let tail_bytes = tail.to_bytes()?;
+        sink.write_all(tail_bytes.as_slice())?;
+        hasher.update(&tail_bytes);
+        size += tail_bytes.len() as u64;
+        generated += 1;
+        if let Some(callback) = progress.0.as_mut() {
+            callback(generated, total);
+        }
+    }
+    sink.flush()?;
+    Ok((size, base58::encode(hasher.finalize())))
+}
+
 #[derive(Debug)]
 pub struct TailsFileWriter {
     root_path: PathBuf,
+    progress: TailsProgress,
 }
 
 impl TailsFileWriter {
     pub fn new(root_path: Option<String>) -> Self {
         Self {
             root_path: root_path.map_or_else(std::env::temp_dir, PathBuf::from),
+            progress: TailsProgress::default(),
         }
     }
+
+    /// Report generation progress to `callback`
+    #[must_use]
+    pub fn with_progress(mut self, callback: impl FnMut(u32, u32) + Send + 'static) -> Self {
+        self.progress = TailsProgress(Some(Box::new(callback)));
+        self
+    }
 }
 
 impl TailsWriter for TailsFileWriter {
     fn write(&mut self, generator: &mut RevocationTailsGenerator) -> Result<(String, String)> {
         let mut tempf = tempfile::NamedTempFile::new_in(self.root_path.clone())?;
-        let file = tempf.as_file_mut();
-        let mut hasher = Sha256::default();
-        let version = &[0u8, 2u8];
-        file.write_all(version)?;
-        hasher.update(version);
-        while let Some(tail) = generator.try_next()? {
-            let tail_bytes = tail.to_bytes()?;
-            file.write_all(tail_bytes.as_slice())?;
-            hasher.update(tail_bytes);
-        }
-        let tails_size = file.stream_position()?;
-        let hash = base58::encode(hasher.finalize());
+        let (tails_size, hash) = write_tails(generator, tempf.as_file_mut(), &mut self.progress)?;
         let path = tempf.path().with_file_name(hash.clone());
         let _outf = match tempf.persist_noclobber(&path) {
             Ok(f) => f,
@@ -175,3 +534,394 @@ impl TailsWriter for TailsFileWriter {
         Ok((path, hash))
     }
 }
+
+/// In-memory tails writer
+///
+/// The tails location is the one given on creation, or the base58 tails hash when none is given,
+/// matching the file name used by [`TailsFileWriter`]
+#[derive(Debug, Default)]
+pub struct TailsBufferWriter {
+    location: Option<String>,
+    data: Vec<u8>,
+    progress: TailsProgress,
+}
+
+impl TailsBufferWriter {
+    pub fn new(location: Option<String>) -> Self {
+        Self {
+            location,
+            ..Self::default()
+        }
+    }
+
+    /// Report generation progress to `callback`
+    #[must_use]
+    pub fn with_progress(mut self, callback: impl FnMut(u32, u32) + Send + 'static) -> Self {
+        self.progress = TailsProgress(Some(Box::new(callback)));
+        self
+    }
+
+    /// The generated tails file
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_data(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+impl TailsWriter for TailsBufferWriter {
+    fn write(&mut self, generator: &mut RevocationTailsGenerator) -> Result<(String, String)> {
+        let mut data = Vec::new();
+        let (tails_size, hash) = write_tails(generator, &mut data, &mut self.progress)?;
+        self.data = data;
+        let location = self.location.clone().unwrap_or_else(|| hash.clone());
+        debug!(
+            "TailsBufferWriter: wrote tails [size {}]: {}",
+            tails_size, location
+        );
+        Ok((location, hash))
+    }
+}
+
+/// Tails writer streaming the generated tails file to any [`Write`] sink, e.g. an upload body
+///
+/// The sink is not buffered, wrap it in a [`std::io::BufWriter`] when writes are expensive
+pub struct TailsStreamWriter<W: Write> {
+    sink: W,
+    location: String,
+    progress: TailsProgress,
+}
+
+impl<W: Write> TailsStreamWriter<W> {
+    /// Create a writer for `sink`, `location` is the tails location to publish in the
+    /// revocation registry definition
+    pub fn new(sink: W, location: impl Into<String>) -> Self {
+        Self {
+            sink,
+            location: location.into(),
+            progress: TailsProgress::default(),
+        }
+    }
+
+    /// Report generation progress to `callback`
+    #[must_use]
+    pub fn with_progress(mut self, callback: impl FnMut(u32, u32) + Send + 'static) -> Self {
+        self.progress = TailsProgress(Some(Box::new(callback)));
+        self
+    }
+
+    pub fn into_inner(self) -> W {
+        self.sink
+    }
+}
+
+impl<W: Write> std::fmt::Debug for TailsStreamWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TailsStreamWriter")
+            .field("location", &self.location)
+            .field("progress", &self.progress)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write> TailsWriter for TailsStreamWriter<W> {
+    fn write(&mut self, generator: &mut RevocationTailsGenerator) -> Result<(String, String)> {
+        let (tails_size, hash) = write_tails(generator, &mut self.sink, &mut self.progress)?;
+        debug!(
+            "TailsStreamWriter: wrote tails [size {}]: {}",
+            tails_size, self.location
+        );
+        Ok((self.location.clone(), hash))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorKind;
+
+    fn tails_data() -> Vec<u8> {
+        (0u8..=255).collect()
+    }
+
+    #[test]
+    fn test_buffer_reader() {
+        let data = tails_data();
+        let mut reader = TailsBufferReader {
+            data: data.clone(),
+            hash: None,
+        };
+        assert_eq!(reader.read(4, 2).unwrap(), vec![2, 3, 4, 5]);
+        assert_eq!(reader.hash().unwrap(), Sha256::digest(&data).to_vec());
+        assert!(reader.read(4, 254).is_err());
+        assert!(reader.read(1, usize::MAX).is_err());
+    }
+
+    fn create_rev_reg_def(tails_writer: &mut impl TailsWriter) -> RevocationRegistryDefinition {
+        use crate::issuer;
+        use crate::types::{CredentialDefinitionConfig, RegistryType, SignatureType};
+
+        let schema = issuer::create_schema(
+            "schema",
+            "1.0",
+            "did:web:xyz",
+            vec!["name".to_owned()].into(),
+        )
+        .unwrap();
+        let (cred_def, _, _) = issuer::create_credential_definition(
+            "did:web:xyz/resource/schema",
+            &schema,
+            "did:web:xyz",
+            "tag",
+            SignatureType::CL,
+            CredentialDefinitionConfig {
+                support_revocation: true,
+            },
+        )
+        .unwrap();
+        let (rev_reg_def, _) = issuer::create_revocation_registry_def(
+            &cred_def,
+            "did:web:xyz/resource/cred-def",
+            "did:web:xyz",
+            "tag",
+            RegistryType::CL_ACCUM,
+            5,
+            tails_writer,
+        )
+        .unwrap();
+        rev_reg_def
+    }
+
+    #[test]
+    fn test_buffer_and_stream_writers() {
+        use std::sync::{Arc, Mutex};
+
+        let progress = Arc::new(Mutex::new(Vec::new()));
+        let recorded = progress.clone();
+        let mut buffer_writer =
+            TailsBufferWriter::new(None).with_progress(move |generated, total| {
+                recorded.lock().unwrap().push((generated, total))
+            });
+        let rev_reg_def = create_rev_reg_def(&mut buffer_writer);
+        // the location defaults to the tails hash
+        assert_eq!(
+            rev_reg_def.value.tails_location,
+            rev_reg_def.value.tails_hash
+        );
+        let progress = progress.lock().unwrap();
+        assert_eq!(progress.len(), 11);
+        assert_eq!(progress.last(), Some(&(11, 11)));
+
+        let mut stream_writer = TailsStreamWriter::new(Vec::new(), "https://tails.example/abc");
+        let stream_rev_reg_def = create_rev_reg_def(&mut stream_writer);
+        assert_eq!(
+            stream_rev_reg_def.value.tails_location,
+            "https://tails.example/abc"
+        );
+        let streamed = stream_writer.into_inner();
+        assert_eq!(streamed.len(), buffer_writer.data().len());
+        assert_eq!(streamed.len(), 2 + 11 * TAIL_SIZE);
+
+        let mut reader = TailsBufferReader {
+            data: streamed,
+            hash: None,
+        };
+        assert_eq!(
+            base58::encode(reader.hash().unwrap()),
+            stream_rev_reg_def.value.tails_hash
+        );
+    }
+
+    #[test]
+    fn test_verify_tails_file() {
+        let mut tails_writer = TailsBufferWriter::new(None);
+        let rev_reg_def = create_rev_reg_def(&mut tails_writer);
+        let data = tails_writer.into_data();
+
+        let reader = TailsBufferReader::new_tails_reader(data.clone());
+        verify_tails_file(&rev_reg_def, &reader).unwrap();
+
+        let check_invalid = |data: Vec<u8>, rehash: bool| {
+            let mut rev_reg_def = rev_reg_def.clone();
+            if rehash {
+                rev_reg_def.value.tails_hash = base58::encode(Sha256::digest(&data));
+            }
+            let reader = TailsBufferReader::new_tails_reader(data).with_verification();
+            assert_eq!(
+                verify_tails_file(&rev_reg_def, &reader).unwrap_err().kind(),
+                ErrorKind::InvalidTailsFile
+            );
+            assert_eq!(
+                reader.ensure_verified(&rev_reg_def).unwrap_err().kind(),
+                ErrorKind::InvalidTailsFile
+            );
+        };
+
+        let mut tampered = data.clone();
+        tampered[10] ^= 1;
+        check_invalid(tampered, false);
+
+        let mut wrong_version = data.clone();
+        wrong_version[1] = 1;
+        check_invalid(wrong_version, true);
+
+        let mut truncated = data.clone();
+        truncated.truncate(data.len() - 1);
+        check_invalid(truncated, true);
+
+        let mut extended = data;
+        extended.extend_from_slice(&[0; TAIL_SIZE]);
+        check_invalid(extended, true);
+    }
+
+    #[test]
+    fn test_load_tails() {
+        let mut tails_writer = TailsBufferWriter::new(None);
+        let rev_reg_def = create_rev_reg_def(&mut tails_writer);
+        let reader = TailsBufferReader::new_tails_reader(tails_writer.into_data());
+        let loaded = reader.load(&rev_reg_def).unwrap();
+
+        let tail_bytes = |accessor: &dyn RevocationTailsAccessor, tail_id: u32| {
+            let mut bytes = None;
+            accessor
+                .access_tail(tail_id, &mut |tail| bytes = Some(tail.to_bytes().unwrap()))
+                .map(|_| bytes.unwrap())
+        };
+        for tail_id in 0..11 {
+            assert_eq!(
+                tail_bytes(&loaded, tail_id).unwrap(),
+                tail_bytes(&reader, tail_id).unwrap()
+            );
+        }
+        assert!(tail_bytes(&loaded, 11).is_err());
+    }
+
+    #[test]
+    fn test_fetch_tails_reader() {
+        use std::collections::HashMap;
+
+        struct MapSource(HashMap<String, Vec<u8>>);
+
+        impl AsyncTailsSource for MapSource {
+            fn fetch<'a>(&'a self, location: &'a str) -> TailsFuture<'a> {
+                Box::pin(async move {
+                    self.0
+                        .get(location)
+                        .cloned()
+                        .ok_or_else(|| err_msg!(IOError, "Unknown tails location {}", location))
+                })
+            }
+        }
+
+        let mut tails_writer = TailsBufferWriter::new(None);
+        let rev_reg_def = create_rev_reg_def(&mut tails_writer);
+        let data = tails_writer.into_data();
+
+        let mut source = MapSource(HashMap::new());
+        assert_eq!(
+            futures_executor::block_on(TailsBufferReader::fetch_tails_reader(
+                &source,
+                &rev_reg_def
+            ))
+            .unwrap_err()
+            .kind(),
+            ErrorKind::IOError
+        );
+
+        let mut tampered = data.clone();
+        tampered[10] ^= 1;
+        source
+            .0
+            .insert(rev_reg_def.value.tails_location.clone(), tampered);
+        assert_eq!(
+            futures_executor::block_on(TailsBufferReader::fetch_tails_reader(
+                &source,
+                &rev_reg_def
+            ))
+            .unwrap_err()
+            .kind(),
+            ErrorKind::InvalidTailsFile
+        );
+
+        source
+            .0
+            .insert(rev_reg_def.value.tails_location.clone(), data);
+        let reader = futures_executor::block_on(TailsBufferReader::fetch_tails_reader(
+            &source,
+            &rev_reg_def,
+        ))
+        .unwrap();
+        verify_tails_file(&rev_reg_def, &reader).unwrap();
+    }
+
+    #[cfg(feature = "http")]
+    fn serve_once(body: Vec<u8>) -> (String, std::thread::JoinHandle<()>) {
+        use std::io::BufRead;
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/tails", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut request = std::io::BufReader::new(stream);
+            let mut line = String::new();
+            while request.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let mut stream = request.into_inner();
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            )
+            .unwrap();
+            // the client may stop reading an oversized body
+            let _ = stream.write_all(&body);
+        });
+        (url, server)
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_url_reader() {
+        let data = tails_data();
+        let (url, server) = serve_once(data.clone());
+
+        let mut reader = TailsUrlReader {
+            url,
+            max_size: data.len(),
+            data: None,
+            hash: None,
+        };
+        assert_eq!(reader.read(4, 2).unwrap(), vec![2, 3, 4, 5]);
+        // the file is only fetched once, the stub serves a single request
+        assert_eq!(reader.hash().unwrap(), Sha256::digest(&data).to_vec());
+        assert_eq!(reader.read(2, 254).unwrap(), vec![254, 255]);
+        server.join().unwrap();
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn test_url_reader_rejects_oversized_file() {
+        let data = tails_data();
+        let (url, server) = serve_once(data.clone());
+
+        let mut reader = TailsUrlReader {
+            url,
+            max_size: data.len() - 1,
+            data: None,
+            hash: None,
+        };
+        assert_eq!(
+            reader.read(4, 2).unwrap_err().kind(),
+            ErrorKind::InvalidTailsFile
+        );
+        server.join().unwrap();
+        assert_eq!(
+            tails_file_size(1),
+            TAILS_BLOB_TAG_SZ as usize + 3 * TAIL_SIZE
+        );
+    }
+}
@@ -4,24 +4,28 @@ pub use crate::data_types::{
     cred_offer::CredentialOffer,
     cred_request::{CredentialRequest, CredentialRequestMetadata},
     credential::{AttributeValues, Credential, CredentialValues},
+    date::Date,
     link_secret::LinkSecret,
     pres_request::PresentationRequest,
     presentation::Presentation,
     rev_reg::RevocationRegistry,
+    rev_reg_allocator::RevocationRegistryAllocator,
     rev_reg_def::{
         RegistryType, RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate,
     },
-    rev_status_list::RevocationStatusList,
+    rev_status_list::{RevocationStatusList, RevocationStatusListDelta},
     schema::AttributeNames,
+    w3c::{W3CCredential, W3CPresentation},
 };
-use crate::services::helpers::encode_credential_attribute;
+use crate::services::encoding::{AttributeEncoder, LegacyEncoder};
 use crate::ursa::cl::{RevocationRegistry as CryptoRevocationRegistry, Witness};
+pub use crate::utils::query::{AbstractQuery, Query};
 use crate::{
     error::{Error, ValidationError},
     invalid,
     utils::validation::Validatable,
 };
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct CredentialDefinitionConfig {
@@ -60,14 +64,30 @@ impl MakeCredentialValues {
         &mut self,
         name: impl Into<String>,
         raw: impl Into<String>,
+    ) -> Result<(), Error> {
+        self.add_raw_with_encoder(name, raw, &LegacyEncoder)
+    }
+
+    /// Add an attribute encoded with the given encoder instead of the legacy encoding
+    pub fn add_raw_with_encoder(
+        &mut self,
+        name: impl Into<String>,
+        raw: impl Into<String>,
+        encoder: &dyn AttributeEncoder,
     ) -> Result<(), Error> {
         let raw = raw.into();
-        let encoded = encode_credential_attribute(&raw)?;
+        let encoded = encoder.encode(&raw)?;
         self.0
              .0
             .insert(name.into(), AttributeValues { raw, encoded });
         Ok(())
     }
+
+    /// Add a date attribute, with the raw value `YYYY-MM-DD` and the `YYYYMMDD` encoding
+    /// expected by date predicates
+    pub fn add_date(&mut self, name: impl Into<String>, date: Date) {
+        self.add_encoded(name, date.to_string(), date.to_encoded().to_string());
+    }
 }
 
 impl From<MakeCredentialValues> for CredentialValues {
@@ -155,6 +175,14 @@ impl PresentCredential<'_> {
     pub fn is_empty(&self) -> bool {
         self.requested_attributes.is_empty() && self.requested_predicates.is_empty()
     }
+
+    pub fn presents(&self, referent: &str) -> bool {
+        self.requested_predicates.contains(referent)
+            || self
+                .requested_attributes
+                .iter()
+                .any(|(attr_referent, _)| attr_referent == referent)
+    }
 }
 
 #[derive(Debug)]
@@ -174,6 +202,127 @@ impl<'a, 'p> AddCredential<'a, 'p> {
     }
 }
 
+/// A credential held by the prover, optionally with a revocation state, which can be matched
+/// against a presentation request
+#[derive(Debug, Clone, Copy)]
+pub struct CredentialCandidate<'p> {
+    pub cred: &'p Credential,
+    pub rev_state: Option<&'p CredentialRevocationState>,
+}
+
+impl<'p> CredentialCandidate<'p> {
+    #[must_use]
+    pub const fn new(
+        cred: &'p Credential,
+        rev_state: Option<&'p CredentialRevocationState>,
+    ) -> Self {
+        Self { cred, rev_state }
+    }
+
+    fn is_same(&self, other: &Self) -> bool {
+        std::ptr::eq(self.cred, other.cred)
+    }
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct ReferentMatches<'p> {
+    pub candidates: Vec<CredentialCandidate<'p>>,
+    pub non_revoked: bool,
+}
+
+/// The credentials which can be used for each referent of a presentation request, as returned
+/// by [`crate::prover::match_credentials`]
+#[derive(Debug, Default)]
+pub struct CredentialMatches<'p> {
+    pub(crate) attributes: HashMap<String, ReferentMatches<'p>>,
+    pub(crate) predicates: HashMap<String, ReferentMatches<'p>>,
+}
+
+impl<'p> CredentialMatches<'p> {
+    /// The candidate credentials for a requested attribute
+    #[must_use]
+    pub fn attribute_candidates(&self, referent: &str) -> &[CredentialCandidate<'p>] {
+        self.attributes
+            .get(referent)
+            .map_or(&[], |m| m.candidates.as_slice())
+    }
+
+    /// The candidate credentials for a requested predicate
+    #[must_use]
+    pub fn predicate_candidates(&self, referent: &str) -> &[CredentialCandidate<'p>] {
+        self.predicates
+            .get(referent)
+            .map_or(&[], |m| m.candidates.as_slice())
+    }
+
+    /// The referents of requested attributes without any candidate credential, these have to be
+    /// self attested
+    #[must_use]
+    pub fn unmatched_attributes(&self) -> Vec<&str> {
+        unmatched(&self.attributes)
+    }
+
+    /// The referents of requested predicates without any candidate credential
+    #[must_use]
+    pub fn unmatched_predicates(&self) -> Vec<&str> {
+        unmatched(&self.predicates)
+    }
+
+    /// Build the credentials for a presentation using the first candidate of every referent,
+    /// all attributes are revealed. Referents without candidates are left out.
+    #[must_use]
+    pub fn to_present_credentials(&self) -> PresentCredentials<'p> {
+        let mut selected: Vec<(CredentialCandidate<'p>, bool)> = Vec::new();
+        let mut select = |matches: &ReferentMatches<'p>| {
+            matches.candidates.first().map(|candidate| {
+                let idx = selected
+                    .iter()
+                    .position(|(c, _)| c.is_same(candidate))
+                    .unwrap_or_else(|| {
+                        selected.push((*candidate, false));
+                        selected.len() - 1
+                    });
+                selected[idx].1 |= matches.non_revoked;
+                idx
+            })
+        };
+
+        let attributes: Vec<(&String, usize)> = self
+            .attributes
+            .iter()
+            .filter_map(|(referent, matches)| select(matches).map(|idx| (referent, idx)))
+            .collect();
+        let predicates: Vec<(&String, usize)> = self
+            .predicates
+            .iter()
+            .filter_map(|(referent, matches)| select(matches).map(|idx| (referent, idx)))
+            .collect();
+
+        let mut present = PresentCredentials::default();
+        for (idx, (candidate, non_revoked)) in selected.into_iter().enumerate() {
+            // Only provide the revocation state when a non-revocation proof is requested
+            let rev_state = candidate.rev_state.filter(|_| non_revoked);
+            let mut cred =
+                present.add_credential(candidate.cred, rev_state.map(|s| s.timestamp), rev_state);
+            for (referent, _) in attributes.iter().filter(|(_, i)| *i == idx) {
+                cred.add_requested_attribute(referent.as_str(), true);
+            }
+            for (referent, _) in predicates.iter().filter(|(_, i)| *i == idx) {
+                cred.add_requested_predicate(referent.as_str());
+            }
+        }
+        present
+    }
+}
+
+fn unmatched<'a>(matches: &'a HashMap<String, ReferentMatches<'_>>) -> Vec<&'a str> {
+    matches
+        .iter()
+        .filter(|(_, m)| m.candidates.is_empty())
+        .map(|(referent, _)| referent.as_str())
+        .collect()
+}
+
 #[allow(dead_code)]
 #[derive(Debug)]
 pub(crate) struct RequestedAttribute<'a> {
@@ -234,3 +383,29 @@ impl<'a> std::fmt::Debug for CredentialRevocationConfig<'a> {
         )
     }
 }
+
+/// Revocation configuration of a batch of credentials issued with the same revocation registry
+///
+/// The revocation indices of the credentials are allocated by `allocator`, which must be the
+/// allocator of the registry.
+pub struct CredentialBatchRevocationConfig<'a> {
+    pub reg_def: &'a RevocationRegistryDefinition,
+    pub reg_def_private: &'a RevocationRegistryDefinitionPrivate,
+    pub rev_status_list: &'a RevocationStatusList,
+    pub allocator: &'a mut RevocationRegistryAllocator,
+    pub tails_reader: TailsReader,
+}
+
+impl<'a> std::fmt::Debug for CredentialBatchRevocationConfig<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "CredentialBatchRevocationConfig {{ reg_def: {:?}, private: {:?}, rev_status_list: {:?}, allocator: {:?}, reader: {:?} }}",
+            self.reg_def,
+            secret!(self.reg_def_private),
+            self.rev_status_list,
+            self.allocator,
+            self.tails_reader,
+        )
+    }
+}
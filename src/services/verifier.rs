@@ -1,3 +1,4 @@
+use super::encoding::AttributeEncoder;
 use super::helpers::attr_common_view;
 use super::helpers::new_nonce;
 use super::types::Presentation;
@@ -6,29 +7,44 @@ use super::types::RevocationRegistryDefinition;
 use super::types::RevocationStatusList;
 use crate::data_types::cred_def::CredentialDefinition;
 use crate::data_types::cred_def::CredentialDefinitionId;
+use crate::data_types::date::Date;
 use crate::data_types::issuer_id::IssuerId;
 use crate::data_types::nonce::Nonce;
 use crate::data_types::pres_request::AttributeInfo;
+use crate::data_types::pres_request::EqualAttributes;
 use crate::data_types::pres_request::NonRevokedInterval;
+use crate::data_types::pres_request::PredicateInfo;
+use crate::data_types::pres_request::PredicateTypes;
 use crate::data_types::pres_request::PresentationRequestPayload;
-use crate::data_types::presentation::{Identifier, RequestedProof, RevealedAttributeInfo};
+use crate::data_types::presentation::{
+    Identifier, RequestedProof, RevealedAttributeGroupInfo, RevealedAttributeInfo,
+};
 use crate::data_types::rev_reg_def::RevocationRegistryDefinitionId;
 use crate::data_types::schema::Schema;
 use crate::data_types::schema::SchemaId;
-use crate::error::Result;
+use crate::data_types::w3c::W3CPresentation;
+use crate::error::{Error, Result};
 use crate::services::helpers::build_credential_schema;
 use crate::services::helpers::build_non_credential_schema;
 use crate::services::helpers::build_sub_proof_request;
+use crate::services::helpers::compare_tag_values;
 use crate::services::helpers::get_predicates_for_credential;
 use crate::services::helpers::get_revealed_attributes_for_credential;
-use crate::ursa::cl::verifier::Verifier as CryptoVerifier;
-use crate::ursa::cl::CredentialPublicKey;
+use crate::services::helpers::like_match;
+use crate::services::resolver::{AsyncObjectResolver, ObjectRefs, ObjectResolver, ResolvedObjects};
+use crate::services::w3c::presentation_from_w3c;
+use crate::ursa::cl::verifier::{ProofVerifier, Verifier as CryptoVerifier};
 use crate::ursa::cl::RevocationRegistry as CryptoRevocationRegistry;
+use crate::ursa::cl::{CredentialPublicKey, CredentialSchema, NonCredentialSchema};
 use crate::utils::query::Query;
 use crate::utils::validation::LEGACY_DID_IDENTIFIER;
 use once_cell::sync::Lazy;
 use regex::Regex;
+use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+pub use super::pres_request_builder::{PresentationRequestBuilder, Restrictions};
 
 #[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Filter {
@@ -40,10 +56,196 @@ pub struct Filter {
     cred_def_id: CredentialDefinitionId,
 }
 
+/// Encoders of revealed attributes, by attribute name in its common view
+type AttributeEncoders = HashMap<String, Box<dyn AttributeEncoder>>;
+
 static INTERNAL_TAG_MATCHER: Lazy<Regex> =
     Lazy::new(|| Regex::new("^attr::([^:]+)::(value|marker)$").unwrap());
 
+/// Machine-readable reason for a failed verification check
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FailureCode {
+    /// A referent from the presentation request is not answered by the presentation
+    MissingReferent,
+    /// The presentation answers a referent which is not in the presentation request
+    UnexpectedReferent,
+    /// A sub proof index in the presentation does not point to an identifier
+    InvalidIdentifier,
+    /// A revealed value does not match the value in the cryptographic proof
+    RevealedValueMismatch,
+    /// The restrictions in the presentation request can not be evaluated
+    InvalidRestriction,
+    /// The credential does not satisfy the restrictions of the presentation request
+    RestrictionNotSatisfied,
+    /// Referents which must be answered by the same credential are answered by different ones
+    CredentialMismatch,
+    /// No schema was provided for the identifier of a sub proof
+    SchemaNotFound,
+    /// No credential definition was provided for the identifier of a sub proof
+    CredentialDefinitionNotFound,
+    /// No revocation registry definition was provided for the identifier of a sub proof
+    RevocationRegistryDefinitionNotFound,
+    /// No revocation status list was provided for the identifier and timestamp of a sub proof
+    RevocationStatusListNotFound,
+    /// A non-revocation proof was requested but the sub proof has no timestamp
+    MissingTimestamp,
+    /// The timestamp of a sub proof is outside of the requested non-revoked interval
+    TimestampOutOfInterval,
+    /// The cryptographic proof could not be verified
+    InvalidProof,
+}
+
+impl FailureCode {
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::MissingReferent => "missing_referent",
+            Self::UnexpectedReferent => "unexpected_referent",
+            Self::InvalidIdentifier => "invalid_identifier",
+            Self::RevealedValueMismatch => "revealed_value_mismatch",
+            Self::InvalidRestriction => "invalid_restriction",
+            Self::RestrictionNotSatisfied => "restriction_not_satisfied",
+            Self::CredentialMismatch => "credential_mismatch",
+            Self::SchemaNotFound => "schema_not_found",
+            Self::CredentialDefinitionNotFound => "credential_definition_not_found",
+            Self::RevocationRegistryDefinitionNotFound => {
+                "revocation_registry_definition_not_found"
+            }
+            Self::RevocationStatusListNotFound => "revocation_status_list_not_found",
+            Self::MissingTimestamp => "missing_timestamp",
+            Self::TimestampOutOfInterval => "timestamp_out_of_interval",
+            Self::InvalidProof => "invalid_proof",
+        }
+    }
+}
+
+impl std::fmt::Display for FailureCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A failed verification check together with a human readable explanation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationFailure {
+    pub code: FailureCode,
+    pub message: String,
+}
+
+impl VerificationFailure {
+    fn new(code: FailureCode, err: &Error) -> Self {
+        Self {
+            code,
+            message: err.to_string(),
+        }
+    }
+}
+
+/// Outcome of a single verification check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CheckStatus {
+    Passed,
+    Failed(VerificationFailure),
+    /// The check does not apply or could not be performed because an earlier check failed
+    Skipped,
+}
+
+impl CheckStatus {
+    #[must_use]
+    pub const fn is_passed(&self) -> bool {
+        matches!(self, Self::Passed)
+    }
+
+    #[must_use]
+    pub const fn is_failed(&self) -> bool {
+        matches!(self, Self::Failed(_))
+    }
+
+    #[must_use]
+    pub const fn failure(&self) -> Option<&VerificationFailure> {
+        match self {
+            Self::Failed(failure) => Some(failure),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReferentKind {
+    Attribute,
+    AttributeGroup,
+    Predicate,
+    SelfAttested,
+}
+
+/// Verification status of a single referent of the presentation request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReferentReport {
+    pub kind: ReferentKind,
+    /// Index of the sub proof answering the referent, `None` when self attested or missing
+    pub sub_proof_index: Option<u32>,
+    pub status: CheckStatus,
+}
+
+/// Verification status of a single sub proof (credential) of the presentation
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SubProofReport {
+    pub identifier: Identifier,
+    /// The CL proofs of all sub proofs are bound to a single challenge, so they either all pass
+    /// or all fail
+    pub cl_proof: CheckStatus,
+    pub non_revocation: CheckStatus,
+    pub timestamp_in_interval: CheckStatus,
+    pub restrictions: CheckStatus,
+}
+
+/// Detailed result of [`verify_presentation_detailed`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VerificationReport {
+    /// `true` iff every check of the report passed or was skipped as not applicable
+    pub verified: bool,
+    /// Status of the aggregated cryptographic proof
+    pub proof: CheckStatus,
+    pub requested_attributes: HashMap<String, ReferentReport>,
+    pub requested_predicates: HashMap<String, ReferentReport>,
+    pub sub_proofs: Vec<SubProofReport>,
+    /// Failures which can not be attributed to a requested referent or a sub proof
+    pub failures: Vec<VerificationFailure>,
+}
+
+impl VerificationReport {
+    /// Iterate over all failures contained in the report
+    pub fn all_failures(&self) -> impl Iterator<Item = &VerificationFailure> {
+        let referents = self
+            .requested_attributes
+            .values()
+            .chain(self.requested_predicates.values())
+            .filter_map(|r| r.status.failure());
+        let sub_proofs = self.sub_proofs.iter().flat_map(|s| {
+            [
+                &s.cl_proof,
+                &s.non_revocation,
+                &s.timestamp_in_interval,
+                &s.restrictions,
+            ]
+            .into_iter()
+            .filter_map(CheckStatus::failure)
+        });
+        self.failures
+            .iter()
+            .chain(self.proof.failure())
+            .chain(referents)
+            .chain(sub_proofs)
+    }
+}
+
 /// Verify an incoming proof presentation
+///
+/// The inputs are parsed on every call: use [`verify_presentation_with_context`] to parse them
+/// once for many presentations.
 pub fn verify_presentation(
     presentation: &Presentation,
     pres_req: &PresentationRequest,
@@ -58,225 +260,1576 @@ pub fn verify_presentation(
     trace!("verify >>> presentation: {:?}, pres_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_status_lists: {:?}",
     presentation, pres_req, schemas, cred_defs, rev_reg_defs, rev_status_lists);
 
+    let valid = VerifierContext::unchecked(
+        schemas.clone(),
+        cred_defs.clone(),
+        rev_reg_defs.cloned(),
+        rev_status_lists,
+    )?
+    .verify_presentation(presentation, pres_req, nonrevoke_interval_override)?;
+
+    trace!("verify <<< valid: {:?}", valid);
+
+    Ok(valid)
+}
+
+/// Verify an incoming proof presentation against the inputs of a [`VerifierContext`]
+///
+/// The context is built once for many presentations, whereas [`verify_presentation`] keeps its
+/// loose maps, for existing callers, and parses them on every call.
+pub fn verify_presentation_with_context(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    context: &VerifierContext,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool> {
+    context.verify_presentation(presentation, pres_req, nonrevoke_interval_override)
+}
+
+/// Verify an incoming proof presentation and report the outcome of every check
+///
+/// Unlike [`verify_presentation`], verification does not stop at the first failed check. The
+/// returned [`VerificationReport`] contains a status per requested referent and per sub proof,
+/// failed checks carry a [`FailureCode`]. An error is only returned when the inputs provided by
+/// the verifier, such as the revocation status lists, are malformed.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::verifier;
+/// use anoncreds::types::PresentationRequest;
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = serde_json::from_value::<PresentationRequest>(serde_json::json!({
+///     "nonce": nonce,
+///     "name":"example_presentation_request",
+///     "version":"0.1",
+///     "requested_attributes":{
+///         "attr1_referent":{
+///             "name":"name",
+///         },
+///     },
+/// }))
+/// .expect("Unable to create presentation request");
+///
+/// // A presentation answering the referent through a self attested attribute
+/// let presentation = serde_json::from_value(serde_json::json!({
+///     "proof": {"proofs": [], "aggregated_proof": {"c_hash": "1", "c_list": []}},
+///     "requested_proof": {
+///         "revealed_attrs": {},
+///         "self_attested_attrs": {"attr1_referent": "Alice"},
+///     },
+///     "identifiers": [],
+/// }))
+/// .expect("Unable to parse presentation");
+///
+/// let report = verifier::verify_presentation_detailed(
+///     &presentation,
+///     &pres_request,
+///     &Default::default(),
+///     &Default::default(),
+///     None,
+///     None,
+///     None,
+/// )
+/// .expect("Unable to verify presentation");
+///
+/// assert!(report.requested_attributes["attr1_referent"].status.is_passed());
+/// ```
+pub fn verify_presentation_detailed(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<&RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<VerificationReport> {
+    trace!("verify_presentation_detailed >>> presentation: {:?}, pres_req: {:?}, schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?} rev_status_lists: {:?}",
+    presentation, pres_req, schemas, cred_defs, rev_reg_defs, rev_status_lists);
+
+    let report = VerifierContext::unchecked(
+        schemas.clone(),
+        cred_defs.clone(),
+        rev_reg_defs.cloned(),
+        rev_status_lists,
+    )?
+    .verify_presentation_detailed(presentation, pres_req, nonrevoke_interval_override)?;
+
+    trace!("verify_presentation_detailed <<< report: {:?}", report);
+
+    Ok(report)
+}
+
+/// Verify an incoming proof presentation, resolving the schemas, credential definitions and
+/// revocation objects referenced by the presentation identifiers through `resolver`
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::prover;
+/// use anoncreds::verifier;
+/// use anoncreds::resolver::InMemoryResolver;
+/// use anoncreds::types::MakeCredentialValues;
+/// use anoncreds::types::PresentCredentials;
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+/// use anoncreds::data_types::schema::SchemaId;
+/// use anoncreds::data_types::cred_def::CredentialDefinitionId;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let credential_offer =
+///     issuer::create_credential_offer("did:web:xyz/resource/schema",
+///                                     "did:web:xyz/resource/cred-def",
+///                                     &key_correctness_proof,
+///                                     ).expect("Unable to create Credential Offer");
+///
+/// let link_secret =
+///     prover::create_link_secret().expect("Unable to create link secret");
+///
+/// let (credential_request, credential_request_metadata) =
+///     prover::create_credential_request(Some("entropy"),
+///                                       None,
+///                                       &cred_def,
+///                                       &link_secret,
+///                                       "my-secret-id",
+///                                       &credential_offer,
+///                                       ).expect("Unable to create credential request");
+///
+/// let mut credential_values = MakeCredentialValues::default();
+/// credential_values.add_raw("name", "john").expect("Unable to add credential value");
+/// credential_values.add_raw("age", "28").expect("Unable to add credential value");
+///
+/// let mut credential =
+///     issuer::create_credential(&cred_def,
+///                               &cred_def_priv,
+///                               &credential_offer,
+///                               &credential_request,
+///                               credential_values.into(),
+///                               None,
+///                               None,
+///                               None
+///                               ).expect("Unable to create credential");
+///
+/// prover::process_credential(&mut credential,
+///                            &credential_request_metadata,
+///                            &link_secret,
+///                            &cred_def,
+///                            None
+///                            ).expect("Unable to process the credential");
+///
+/// // The verifier resolves the objects referenced by the presentation from its registry
+/// let mut resolver = InMemoryResolver::new();
+/// resolver
+///     .add_schema(SchemaId::new_unchecked("did:web:xyz/resource/schema"), schema)
+///     .add_cred_def(CredentialDefinitionId::new_unchecked("did:web:xyz/resource/cred-def"), cred_def);
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = serde_json::from_value(serde_json::json!({
+///     "nonce": nonce,
+///     "name":"example_presentation_request",
+///     "version":"0.1",
+///     "requested_attributes":{
+///         "attr1_referent":{
+///             "name":"name",
+///         },
+///     },
+///     "requested_predicates":{
+///         "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+///     }
+/// }))
+/// .expect("Unable to create presentation request");
+///
+/// let mut present = PresentCredentials::default();
+/// let mut cred1 = present.add_credential(&credential, None, None);
+/// cred1.add_requested_attribute("attr1_referent", true);
+/// cred1.add_requested_predicate("predicate1_referent");
+///
+/// let presentation =
+///     prover::create_presentation_with_resolver(&pres_request,
+///                                               present,
+///                                               None,
+///                                               &link_secret,
+///                                               &resolver
+///                                               ).expect("Unable to create presentation");
+///
+/// let valid = verifier::verify_presentation_with_resolver(&presentation,
+///                                                        &pres_request,
+///                                                        &resolver,
+///                                                        None)
+///     .expect("Unable to verify presentation");
+///
+/// assert!(valid);
+/// ```
+pub fn verify_presentation_with_resolver(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    resolver: &impl ObjectResolver,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool> {
+    trace!(
+        "verify_presentation_with_resolver >>> presentation: {:?}, pres_req: {:?}",
+        presentation,
+        pres_req
+    );
+
+    let resolved = ResolvedObjects::resolve(ObjectRefs::for_presentation(presentation)?, resolver)?;
+    let rev_reg_defs = resolved.rev_reg_defs();
+    let valid = verify_presentation(
+        presentation,
+        pres_req,
+        &resolved.schemas(),
+        &resolved.cred_defs(),
+        rev_reg_defs.as_ref(),
+        resolved.rev_status_lists(),
+        nonrevoke_interval_override,
+    )?;
+
+    trace!("verify_presentation_with_resolver <<< valid: {:?}", valid);
+
+    Ok(valid)
+}
+
+/// Verify an incoming proof presentation in the W3C Verifiable Presentation form
+///
+/// A presentation whose challenge is not the nonce of `pres_req` is not valid. Credential entries
+/// whose issuer or revealed attributes do not match the proof are rejected as errors, see
+/// [`presentation_from_w3c`](crate::w3c::presentation_from_w3c). See [`verify_presentation`] for
+/// the verification itself.
+pub fn verify_w3c_presentation(
+    presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition>>,
+    rev_status_lists: Option<Vec<&RevocationStatusList>>,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool> {
+    trace!(
+        "verify_w3c_presentation >>> presentation: {:?}, pres_req: {:?}",
+        presentation,
+        pres_req
+    );
+
+    if presentation.proof.challenge != pres_req.value().nonce.to_string() {
+        trace!("verify_w3c_presentation <<< valid: false");
+        return Ok(false);
+    }
+
+    let legacy_presentation = presentation_from_w3c(presentation, pres_req, cred_defs)?;
+    let valid = verify_presentation(
+        &legacy_presentation,
+        pres_req,
+        schemas,
+        cred_defs,
+        rev_reg_defs,
+        rev_status_lists,
+        nonrevoke_interval_override,
+    )?;
+
+    trace!("verify_w3c_presentation <<< valid: {:?}", valid);
+
+    Ok(valid)
+}
+
+/// Verify an incoming proof presentation, as [`verify_presentation_with_resolver`] with an
+/// asynchronous resolver
+pub async fn verify_presentation_with_async_resolver(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    resolver: &impl AsyncObjectResolver,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+) -> Result<bool> {
+    trace!(
+        "verify_presentation_with_async_resolver >>> presentation: {:?}, pres_req: {:?}",
+        presentation,
+        pres_req
+    );
+
+    let resolved =
+        ResolvedObjects::resolve_async(ObjectRefs::for_presentation(presentation)?, resolver)
+            .await?;
+    let rev_reg_defs = resolved.rev_reg_defs();
+    let valid = verify_presentation(
+        presentation,
+        pres_req,
+        &resolved.schemas(),
+        &resolved.cred_defs(),
+        rev_reg_defs.as_ref(),
+        resolved.rev_status_lists(),
+        nonrevoke_interval_override,
+    )?;
+
+    trace!(
+        "verify_presentation_with_async_resolver <<< valid: {:?}",
+        valid
+    );
+
+    Ok(valid)
+}
+
+/// Verifies many presentations against the same schemas, credential definitions and revocation
+/// status lists
+///
+/// The presentations are verified through one [`VerifierContext`], so credential schemas, public
+/// keys and the revocation registries are parsed once and shared by all verifications. With the
+/// `rayon` feature enabled, presentations are verified in parallel.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::verifier::{self, BatchVerifier};
+/// use anoncreds::types::PresentationRequest;
+/// use std::collections::HashMap;
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = serde_json::from_value::<PresentationRequest>(serde_json::json!({
+///     "nonce": nonce,
+///     "name":"example_presentation_request",
+///     "version":"0.1",
+///     "requested_attributes":{
+///         "attr1_referent":{
+///             "name":"name",
+///         },
+///     },
+/// }))
+/// .expect("Unable to create presentation request");
+///
+/// // A presentation answering the referent through a self attested attribute
+/// let presentation = serde_json::from_value(serde_json::json!({
+///     "proof": {"proofs": [], "aggregated_proof": {"c_hash": "1", "c_list": []}},
+///     "requested_proof": {
+///         "revealed_attrs": {},
+///         "self_attested_attrs": {"attr1_referent": "Alice"},
+///     },
+///     "identifiers": [],
+/// }))
+/// .expect("Unable to parse presentation");
+///
+/// let batch_verifier = BatchVerifier::new(HashMap::new(), HashMap::new(), None, None)
+///     .expect("Unable to create batch verifier");
+/// let reports = batch_verifier.verify_detailed(&[
+///     (&presentation, &pres_request),
+///     (&presentation, &pres_request),
+/// ]);
+///
+/// assert!(reports
+///     .iter()
+///     .all(|report| report.requested_attributes["attr1_referent"].status.is_passed()));
+/// ```
+pub struct BatchVerifier<'a> {
+    context: VerifierContext<'a>,
+    nonrevoke_interval_override:
+        Option<HashMap<&'a RevocationRegistryDefinitionId, HashMap<u64, u64>>>,
+}
+
+impl<'a> BatchVerifier<'a> {
+    /// Build the [`VerifierContext`] shared by the verifications, see [`VerifierContext::new`]
+    pub fn new(
+        schemas: HashMap<&'a SchemaId, &'a Schema>,
+        cred_defs: HashMap<&'a CredentialDefinitionId, &'a CredentialDefinition>,
+        rev_reg_defs: Option<
+            HashMap<&'a RevocationRegistryDefinitionId, &'a RevocationRegistryDefinition>,
+        >,
+        rev_status_lists: Option<Vec<&'a RevocationStatusList>>,
+    ) -> Result<Self> {
+        VerifierContext::new(schemas, cred_defs, rev_reg_defs, rev_status_lists).map(Self::from)
+    }
+
+    /// Accept earlier non-revoked intervals, as with the `nonrevoke_interval_override` of
+    /// [`verify_presentation`]
+    #[must_use]
+    pub fn with_nonrevoke_interval_override(
+        mut self,
+        nonrevoke_interval_override: HashMap<&'a RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    ) -> Self {
+        self.nonrevoke_interval_override = Some(nonrevoke_interval_override);
+        self
+    }
+
+    /// Check that the revealed raw values of an attribute match their encoding by `encoder`, see
+    /// [`VerifierContext::with_attribute_encoder`]
+    #[must_use]
+    pub fn with_attribute_encoder(
+        mut self,
+        name: &str,
+        encoder: impl AttributeEncoder + 'static,
+    ) -> Self {
+        self.context = self.context.with_attribute_encoder(name, encoder);
+        self
+    }
+
+    /// Verify each presentation against its request, with the same result as
+    /// [`verify_presentation`] per item
+    pub fn verify(&self, items: &[(&Presentation, &PresentationRequest)]) -> Vec<Result<bool>> {
+        trace!("BatchVerifier::verify >>> items: {}", items.len());
+
+        let results = self.map_items(items, |presentation, pres_req| {
+            self.context.verify_presentation(
+                presentation,
+                pres_req,
+                self.nonrevoke_interval_override.as_ref(),
+            )
+        });
+
+        trace!("BatchVerifier::verify <<< results: {:?}", results);
+
+        results
+    }
+
+    /// Verify each presentation against its request, with the same report as
+    /// [`verify_presentation_detailed`] per item
+    pub fn verify_detailed(
+        &self,
+        items: &[(&Presentation, &PresentationRequest)],
+    ) -> Vec<VerificationReport> {
+        trace!("BatchVerifier::verify_detailed >>> items: {}", items.len());
+
+        let reports = self.map_items(items, |presentation, pres_req| {
+            self.context
+                .check(
+                    presentation,
+                    pres_req,
+                    self.nonrevoke_interval_override.as_ref(),
+                )
+                .0
+        });
+
+        trace!("BatchVerifier::verify_detailed <<< reports: {:?}", reports);
+
+        reports
+    }
+
+    #[cfg(feature = "rayon")]
+    fn map_items<T: Send>(
+        &self,
+        items: &[(&Presentation, &PresentationRequest)],
+        f: impl Fn(&Presentation, &PresentationRequest) -> T + Sync,
+    ) -> Vec<T> {
+        use rayon::prelude::*;
+
+        items
+            .par_iter()
+            .map(|(presentation, pres_req)| f(presentation, pres_req))
+            .collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    fn map_items<T>(
+        &self,
+        items: &[(&Presentation, &PresentationRequest)],
+        f: impl Fn(&Presentation, &PresentationRequest) -> T,
+    ) -> Vec<T> {
+        items
+            .iter()
+            .map(|(presentation, pres_req)| f(presentation, pres_req))
+            .collect()
+    }
+}
+
+impl<'a> From<VerifierContext<'a>> for BatchVerifier<'a> {
+    fn from(context: VerifierContext<'a>) -> Self {
+        Self {
+            context,
+            nonrevoke_interval_override: None,
+        }
+    }
+}
+
+/// Verification inputs shared by many presentations, built once from the schemas, credential
+/// definitions, revocation registry definitions and revocation status lists known to the verifier
+///
+/// Credential schemas and public keys are parsed and the revocation registries are indexed per
+/// registry definition and timestamp on construction. Missing or malformed inputs are reported
+/// there instead of on every verification.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::verifier::{self, VerifierContext};
+/// use anoncreds::types::PresentationRequest;
+/// use std::collections::HashMap;
+///
+/// let nonce = verifier::generate_nonce().expect("Unable to generate nonce");
+/// let pres_request = serde_json::from_value::<PresentationRequest>(serde_json::json!({
+///     "nonce": nonce,
+///     "name":"example_presentation_request",
+///     "version":"0.1",
+///     "requested_attributes":{
+///         "attr1_referent":{
+///             "name":"name",
+///         },
+///     },
+/// }))
+/// .expect("Unable to create presentation request");
+///
+/// // A presentation answering the referent through a self attested attribute
+/// let presentation = serde_json::from_value(serde_json::json!({
+///     "proof": {"proofs": [], "aggregated_proof": {"c_hash": "1", "c_list": []}},
+///     "requested_proof": {
+///         "revealed_attrs": {},
+///         "self_attested_attrs": {"attr1_referent": "Alice"},
+///     },
+///     "identifiers": [],
+/// }))
+/// .expect("Unable to parse presentation");
+///
+/// let context = VerifierContext::new(HashMap::new(), HashMap::new(), None, None)
+///     .expect("Unable to create verifier context");
+/// let report = context
+///     .verify_presentation_detailed(&presentation, &pres_request, None)
+///     .expect("Unable to verify presentation");
+///
+/// assert!(report.requested_attributes["attr1_referent"].status.is_passed());
+/// ```
+pub struct VerifierContext<'a> {
+    schemas: HashMap<&'a SchemaId, &'a Schema>,
+    cred_defs: HashMap<&'a CredentialDefinitionId, &'a CredentialDefinition>,
+    rev_reg_defs:
+        Option<HashMap<&'a RevocationRegistryDefinitionId, &'a RevocationRegistryDefinition>>,
+    rev_reg_map: Option<RevRegMap>,
+    key_cache: KeyCache,
+    encoders: AttributeEncoders,
+}
+
+impl<'a> VerifierContext<'a> {
+    pub fn new(
+        schemas: HashMap<&'a SchemaId, &'a Schema>,
+        cred_defs: HashMap<&'a CredentialDefinitionId, &'a CredentialDefinition>,
+        rev_reg_defs: Option<
+            HashMap<&'a RevocationRegistryDefinitionId, &'a RevocationRegistryDefinition>,
+        >,
+        rev_status_lists: Option<Vec<&'a RevocationStatusList>>,
+    ) -> Result<Self> {
+        trace!(
+            "VerifierContext::new >>> schemas: {:?}, cred_defs: {:?}, rev_reg_defs: {:?}, rev_status_lists: {:?}",
+            schemas,
+            cred_defs,
+            rev_reg_defs,
+            rev_status_lists
+        );
+
+        let context = Self::unchecked(schemas, cred_defs, rev_reg_defs, rev_status_lists)?;
+        let Self {
+            schemas,
+            cred_defs,
+            rev_reg_defs,
+            rev_reg_map,
+            key_cache,
+            ..
+        } = &context;
+        for (schema_id, schema) in schemas {
+            key_cache.credential_schema(schema_id, schema)?;
+        }
+        for (cred_def_id, cred_def) in cred_defs {
+            if !schemas.contains_key(&cred_def.schema_id) {
+                return Err(err_msg!(
+                    "Schema not provided for ID: {:?}, used by Credential Definition {:?}",
+                    cred_def.schema_id,
+                    cred_def_id
+                ));
+            }
+            key_cache.public_key(cred_def_id, cred_def)?;
+        }
+        for (rev_reg_def_id, rev_reg_def) in rev_reg_defs.iter().flatten() {
+            if !cred_defs.contains_key(&rev_reg_def.cred_def_id) {
+                return Err(err_msg!(
+                    "Credential Definition not provided for ID: {:?}, used by Revocation Registry Definition {:?}",
+                    rev_reg_def.cred_def_id,
+                    rev_reg_def_id
+                ));
+            }
+        }
+        for rev_reg_def_id in rev_reg_map.iter().flat_map(HashMap::keys) {
+            if !rev_reg_defs
+                .as_ref()
+                .map_or(false, |defs| defs.contains_key(rev_reg_def_id))
+            {
+                return Err(err_msg!(
+                    "Revocation Registry Definition not provided for ID: {:?}, used by a Revocation Status List",
+                    rev_reg_def_id
+                ));
+            }
+        }
+
+        trace!("VerifierContext::new <<<");
+
+        Ok(context)
+    }
+
+    /// Context for a single verification by the free functions, which only parse the keys and
+    /// look up the inputs used by the presentation
+    fn unchecked(
+        schemas: HashMap<&'a SchemaId, &'a Schema>,
+        cred_defs: HashMap<&'a CredentialDefinitionId, &'a CredentialDefinition>,
+        rev_reg_defs: Option<
+            HashMap<&'a RevocationRegistryDefinitionId, &'a RevocationRegistryDefinition>,
+        >,
+        rev_status_lists: Option<Vec<&'a RevocationStatusList>>,
+    ) -> Result<Self> {
+        Ok(Self {
+            schemas,
+            cred_defs,
+            rev_reg_defs,
+            rev_reg_map: rev_status_lists.map(build_rev_reg_map).transpose()?,
+            key_cache: KeyCache::default(),
+            encoders: AttributeEncoders::new(),
+        })
+    }
+
+    /// Check that the revealed raw values of an attribute match their encoding by `encoder`
+    ///
+    /// Without an encoder, only the encoded values of revealed attributes are verified.
+    #[must_use]
+    pub fn with_attribute_encoder(
+        mut self,
+        name: &str,
+        encoder: impl AttributeEncoder + 'static,
+    ) -> Self {
+        self.encoders
+            .insert(attr_common_view(name), Box::new(encoder));
+        self
+    }
+
+    /// Verify an incoming proof presentation, as
+    /// [`verify_presentation`](crate::verifier::verify_presentation)
+    pub fn verify_presentation(
+        &self,
+        presentation: &Presentation,
+        pres_req: &PresentationRequest,
+        nonrevoke_interval_override: Option<
+            &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+        >,
+    ) -> Result<bool> {
+        trace!(
+            "VerifierContext::verify_presentation >>> presentation: {:?}, pres_req: {:?}",
+            presentation,
+            pres_req
+        );
+
+        let (report, first_error) = self.check(presentation, pres_req, nonrevoke_interval_override);
+        if let Some(err) = first_error {
+            return Err(err);
+        }
+
+        trace!(
+            "VerifierContext::verify_presentation <<< valid: {:?}",
+            report.verified
+        );
+
+        Ok(report.verified)
+    }
+
+    /// Verify an incoming proof presentation and report the outcome of every check, as
+    /// [`verify_presentation_detailed`](crate::verifier::verify_presentation_detailed)
+    pub fn verify_presentation_detailed(
+        &self,
+        presentation: &Presentation,
+        pres_req: &PresentationRequest,
+        nonrevoke_interval_override: Option<
+            &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+        >,
+    ) -> Result<VerificationReport> {
+        trace!(
+            "VerifierContext::verify_presentation_detailed >>> presentation: {:?}, pres_req: {:?}",
+            presentation,
+            pres_req
+        );
+
+        let (report, _) = self.check(presentation, pres_req, nonrevoke_interval_override);
+
+        trace!(
+            "VerifierContext::verify_presentation_detailed <<< report: {:?}",
+            report
+        );
+
+        Ok(report)
+    }
+
+    fn check(
+        &self,
+        presentation: &Presentation,
+        pres_req: &PresentationRequest,
+        nonrevoke_interval_override: Option<
+            &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+        >,
+    ) -> (VerificationReport, Option<Error>) {
+        check_presentation(
+            presentation,
+            pres_req,
+            &self.schemas,
+            &self.cred_defs,
+            self.rev_reg_defs.as_ref(),
+            self.rev_reg_map.as_ref(),
+            nonrevoke_interval_override,
+            &self.key_cache,
+            &self.encoders,
+        )
+    }
+}
+
+/// Collects the outcome of the verification stages into a `VerificationReport`, keeping the
+/// first error so that `verify_presentation` can keep failing with the same error as before
+struct ReportBuilder {
+    report: VerificationReport,
+    first_error: Option<Error>,
+}
+
+impl ReportBuilder {
+    fn new() -> Self {
+        Self {
+            report: VerificationReport {
+                verified: false,
+                proof: CheckStatus::Skipped,
+                requested_attributes: HashMap::new(),
+                requested_predicates: HashMap::new(),
+                sub_proofs: Vec::new(),
+                failures: Vec::new(),
+            },
+            first_error: None,
+        }
+    }
+
+    fn failure(&mut self, code: FailureCode, err: Error) -> VerificationFailure {
+        let failure = VerificationFailure::new(code, &err);
+        self.first_error.get_or_insert(err);
+        failure
+    }
+
+    fn fail(&mut self, code: FailureCode, err: Error) {
+        let failure = self.failure(code, err);
+        self.report.failures.push(failure);
+    }
+
+    fn fail_referent(&mut self, referent: &str, predicate: bool, code: FailureCode, err: Error) {
+        let failure = self.failure(code, err);
+        let referents = if predicate {
+            &mut self.report.requested_predicates
+        } else {
+            &mut self.report.requested_attributes
+        };
+        match referents.get_mut(referent) {
+            Some(report) if !report.status.is_failed() => {
+                report.status = CheckStatus::Failed(failure)
+            }
+            Some(_) => {}
+            None => self.report.failures.push(failure),
+        }
+    }
+
+    fn finish(mut self) -> (VerificationReport, Option<Error>) {
+        let report = &mut self.report;
+        report.verified = report.proof.is_passed() && report.all_failures().next().is_none();
+        (self.report, self.first_error)
+    }
+}
+
+/// A failed check of a requested attribute or predicate
+struct ReferentFailure {
+    referent: String,
+    predicate: bool,
+    error: Error,
+}
+
+impl ReferentFailure {
+    fn attribute(referent: &str, error: Error) -> Self {
+        Self {
+            referent: referent.to_string(),
+            predicate: false,
+            error,
+        }
+    }
+
+    fn predicate(referent: &str, error: Error) -> Self {
+        Self {
+            referent: referent.to_string(),
+            predicate: true,
+            error,
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn check_presentation(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition>>,
+    rev_reg_map: Option<&RevRegMap>,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+    key_cache: &KeyCache,
+    encoders: &AttributeEncoders,
+) -> (VerificationReport, Option<Error>) {
+    let mut builder = ReportBuilder::new();
+    let pres_req = pres_req.value();
+    let requested_proof = &presentation.requested_proof;
+
     // These values are from the prover and cannot be trusted
-    let received_revealed_attrs: HashMap<String, Identifier> =
-        received_revealed_attrs(presentation)?;
-    let received_unrevealed_attrs: HashMap<String, Identifier> =
-        received_unrevealed_attrs(presentation)?;
-    let received_predicates: HashMap<String, Identifier> = received_predicates(presentation)?;
+    let received = received_revealed_attrs(presentation).and_then(|revealed| {
+        Ok((
+            revealed,
+            received_unrevealed_attrs(presentation)?,
+            received_predicates(presentation)?,
+        ))
+    });
+    let (received_revealed_attrs, received_unrevealed_attrs, received_predicates) = match received {
+        Ok(received) => received,
+        Err(err) => {
+            builder.fail(FailureCode::InvalidIdentifier, err);
+            return builder.finish();
+        }
+    };
     let received_self_attested_attrs: HashSet<String> = received_self_attested_attrs(presentation);
 
-    let pres_req = pres_req.value();
+    for (referent, info) in &pres_req.requested_attributes {
+        let kind = if received_self_attested_attrs.contains(referent) {
+            ReferentKind::SelfAttested
+        } else if info.names.is_some() {
+            ReferentKind::AttributeGroup
+        } else {
+            ReferentKind::Attribute
+        };
+        builder.report.requested_attributes.insert(
+            referent.clone(),
+            ReferentReport {
+                kind,
+                sub_proof_index: attribute_sub_proof_index(requested_proof, referent),
+                status: CheckStatus::Skipped,
+            },
+        );
+    }
+    for referent in pres_req.requested_predicates.keys() {
+        builder.report.requested_predicates.insert(
+            referent.clone(),
+            ReferentReport {
+                kind: ReferentKind::Predicate,
+                sub_proof_index: requested_proof
+                    .predicates
+                    .get(referent)
+                    .map(|p| p.sub_proof_index),
+                status: CheckStatus::Skipped,
+            },
+        );
+    }
 
     // Ensures that all attributes in the request is also in the presentation
-    compare_attr_from_proof_and_request(
+    if let Err(err) = compare_attr_from_proof_and_request(
         pres_req,
         &received_revealed_attrs,
         &received_unrevealed_attrs,
         &received_self_attested_attrs,
         &received_predicates,
-    )?;
+    ) {
+        builder.first_error = Some(err);
+        report_referent_mismatch(
+            &mut builder,
+            pres_req,
+            &received_revealed_attrs,
+            &received_unrevealed_attrs,
+            &received_self_attested_attrs,
+            &received_predicates,
+        );
+        return builder.finish();
+    }
+
+    // Ensures the referents bound to one credential are answered by the same sub proof
+    for failure in verify_same_credential_referents(pres_req, requested_proof) {
+        builder.fail_referent(
+            &failure.referent,
+            failure.predicate,
+            FailureCode::CredentialMismatch,
+            failure.error,
+        );
+    }
 
     // Ensures the encoded values are same as request
-    verify_revealed_attribute_values(pres_req, presentation)?;
+    for failure in verify_revealed_attribute_values(pres_req, presentation, encoders) {
+        builder.fail_referent(
+            &failure.referent,
+            failure.predicate,
+            FailureCode::RevealedValueMismatch,
+            failure.error,
+        );
+    }
 
     // Ensures the restrictinos set out in the request is met
-    verify_requested_restrictions(
+    match verify_requested_restrictions(
         pres_req,
         schemas,
         cred_defs,
-        &presentation.requested_proof,
+        requested_proof,
         &received_revealed_attrs,
         &received_unrevealed_attrs,
         &received_predicates,
         &received_self_attested_attrs,
-    )?;
-
-    let mut proof_verifier = CryptoVerifier::new_proof_verifier()?;
-    let non_credential_schema = build_non_credential_schema()?;
+    ) {
+        Ok(failures) => {
+            for failure in failures {
+                builder.fail_referent(
+                    &failure.referent,
+                    failure.predicate,
+                    FailureCode::RestrictionNotSatisfied,
+                    failure.error,
+                );
+            }
+        }
+        Err(err) => builder.fail(FailureCode::InvalidRestriction, err),
+    }
 
-    for sub_proof_index in 0..presentation.identifiers.len() {
-        let identifier = presentation.identifiers[sub_proof_index].clone();
+    for referent in builder
+        .report
+        .requested_attributes
+        .values_mut()
+        .chain(builder.report.requested_predicates.values_mut())
+    {
+        if !referent.status.is_failed() {
+            referent.status = CheckStatus::Passed;
+        }
+    }
 
-        let schema = schemas
-            .get(&identifier.schema_id)
-            .ok_or_else(|| err_msg!("Schema not provided for ID: {:?}", identifier.schema_id))?;
+    let proof_setup = CryptoVerifier::new_proof_verifier()
+        .map_err(Error::from)
+        .and_then(|verifier| Ok((verifier, build_non_credential_schema()?)));
+    let (mut proof_verifier, non_credential_schema) = match proof_setup {
+        Ok(setup) => setup,
+        Err(err) => {
+            builder.fail(FailureCode::InvalidProof, err);
+            return builder.finish();
+        }
+    };
+    let mut revocation_checked = Vec::with_capacity(presentation.identifiers.len());
+    let mut sub_proofs_added = true;
+
+    for (sub_proof_index, identifier) in presentation.identifiers.iter().enumerate() {
+        let mut sub_proof = SubProofReport {
+            identifier: identifier.clone(),
+            cl_proof: CheckStatus::Skipped,
+            non_revocation: CheckStatus::Skipped,
+            timestamp_in_interval: CheckStatus::Skipped,
+            restrictions: sub_proof_restrictions(sub_proof_index, pres_req, &builder.report),
+        };
 
-        let cred_def_id = CredentialDefinitionId::new(identifier.cred_def_id.clone())?;
-        let cred_def = cred_defs.get(&cred_def_id).ok_or_else(|| {
-            err_msg!(
-                "Credential Definition not provided for ID: {:?}",
-                identifier.cred_def_id
-            )
-        })?;
+        match add_sub_proof_request(
+            &mut proof_verifier,
+            &mut sub_proof,
+            sub_proof_index,
+            presentation,
+            pres_req,
+            schemas,
+            cred_defs,
+            rev_reg_defs,
+            rev_reg_map,
+            nonrevoke_interval_override,
+            &non_credential_schema,
+            key_cache,
+        ) {
+            Ok(checked) => revocation_checked.push(checked),
+            Err(err) => {
+                builder.first_error.get_or_insert(err);
+                revocation_checked.push(false);
+                sub_proofs_added = false;
+            }
+        }
 
-        let rev_reg_map = if let Some(ref lists) = rev_status_lists {
-            let mut map: HashMap<
-                RevocationRegistryDefinitionId,
-                HashMap<u64, CryptoRevocationRegistry>,
-            > = HashMap::new();
+        builder.report.sub_proofs.push(sub_proof);
+    }
 
-            for list in lists.iter() {
-                let id = list
-                    .id()
-                    .ok_or_else(|| err_msg!(Unexpected, "RevStatusList missing Id"))?;
+    // The cryptographic proof can only be verified once all sub proof requests are known
+    if sub_proofs_added {
+        let verified = proof_verifier
+            .verify(&presentation.proof, pres_req.nonce.as_native())
+            .map_err(Error::from)
+            .and_then(|valid| {
+                if valid {
+                    check_equal_attributes(presentation, pres_req)?;
+                }
+                Ok(valid)
+            });
+        let status = match verified {
+            Ok(true) => CheckStatus::Passed,
+            Ok(false) => CheckStatus::Failed(VerificationFailure::new(
+                FailureCode::InvalidProof,
+                &err_msg!(ProofRejected, "Presentation proof is not valid"),
+            )),
+            Err(err) => {
+                let failure = builder.failure(FailureCode::InvalidProof, err);
+                CheckStatus::Failed(failure)
+            }
+        };
+        for (sub_proof, checked) in builder.report.sub_proofs.iter_mut().zip(revocation_checked) {
+            sub_proof.cl_proof = status.clone();
+            if checked {
+                sub_proof.non_revocation = status.clone();
+            }
+        }
+        builder.report.proof = status;
+    }
 
-                let timestamp = list
-                    .timestamp()
-                    .ok_or_else(|| err_msg!(Unexpected, "RevStatusList missing timestamp"))?;
+    builder.finish()
+}
 
-                let rev_reg: Option<ursa::cl::RevocationRegistry> = (*list).try_into()?;
-                let rev_reg = rev_reg.ok_or_else(|| {
-                    err_msg!(Unexpected, "Revocation status list missing accumulator")
+/// Check that the credentials presented for the referents of each equal attribute hide the same
+/// value for it
+///
+/// The sub proofs share the challenge of the proof, so their responses for the attribute are
+/// equal if and only if the hidden values are. The proof verifier cannot compare them itself, as
+/// it requires its common attributes in every sub proof.
+fn check_equal_attributes(
+    presentation: &Presentation,
+    pres_req: &PresentationRequestPayload,
+) -> Result<()> {
+    for EqualAttributes { name, referents } in &pres_req.equal_attributes {
+        let attr_name = attr_common_view(name);
+        let mut hidden_value = None;
+        for referent in referents {
+            let sub_proof = presentation
+                .requested_proof
+                .sub_proof_index(referent)
+                .and_then(|sub_proof_index| presentation.proof.proofs.get(sub_proof_index as usize))
+                .ok_or_else(|| {
+                    err_msg!(
+                        ProofRejected,
+                        "No credential is presented for referent \"{}\" of the equal attribute \"{}\"",
+                        referent,
+                        name
+                    )
+                })?;
+            let sub_proof = serde_json::to_value(sub_proof)?;
+            let value = sub_proof["primary_proof"]["eq_proof"]["m"]
+                .get(&attr_name)
+                .cloned()
+                .ok_or_else(|| {
+                    err_msg!(
+                        ProofRejected,
+                        "Equal attribute \"{}\" is not hidden for referent \"{}\"",
+                        name,
+                        referent
+                    )
                 })?;
+            match &hidden_value {
+                None => hidden_value = Some(value),
+                Some(hidden_value) if *hidden_value == value => {}
+                Some(_) => {
+                    return Err(err_msg!(
+                        ProofRejected,
+                        "Equal attribute \"{}\" holds different values across credentials",
+                        name
+                    ))
+                }
+            }
+        }
+    }
+    Ok(())
+}
 
-                map.entry(id)
-                    .or_insert_with(HashMap::new)
-                    .insert(timestamp, rev_reg);
+fn verify_same_credential_referents(
+    pres_req: &PresentationRequestPayload,
+    requested_proof: &RequestedProof,
+) -> Vec<ReferentFailure> {
+    let mut failures = Vec::new();
+    for referents in &pres_req.same_credential_referents {
+        let first = &referents[0];
+        let sub_proof_index = requested_proof.sub_proof_index(first);
+        for referent in referents {
+            let referent_index = requested_proof.sub_proof_index(referent);
+            if referent_index.is_some() && referent_index == sub_proof_index {
+                continue;
             }
-            Some(map)
-        } else {
-            None
-        };
+            let error = err_msg!(
+                ProofRejected,
+                "Referents {} and {} must be answered by the same credential",
+                first,
+                referent
+            );
+            failures.push(if pres_req.requested_predicates.contains_key(referent) {
+                ReferentFailure::predicate(referent, error)
+            } else {
+                ReferentFailure::attribute(referent, error)
+            });
+        }
+    }
+    failures
+}
 
-        let (attrs_for_credential, attrs_nonrevoked_interval) =
-            get_revealed_attributes_for_credential(
-                sub_proof_index,
-                &presentation.requested_proof,
-                pres_req,
+fn report_referent_mismatch(
+    builder: &mut ReportBuilder,
+    pres_req: &PresentationRequestPayload,
+    received_revealed_attrs: &HashMap<String, Identifier>,
+    received_unrevealed_attrs: &HashMap<String, Identifier>,
+    received_self_attested_attrs: &HashSet<String>,
+    received_predicates: &HashMap<String, Identifier>,
+) {
+    let received_attrs: HashSet<&String> = received_revealed_attrs
+        .keys()
+        .chain(received_unrevealed_attrs.keys())
+        .chain(received_self_attested_attrs)
+        .collect();
+
+    for referent in pres_req.requested_attributes.keys() {
+        if !received_attrs.contains(referent) {
+            builder.fail_referent(
+                referent,
+                false,
+                FailureCode::MissingReferent,
+                err_msg!(
+                    ProofRejected,
+                    "Requested attribute \"{}\" not found in presentation",
+                    referent
+                ),
             );
-        let (predicates_for_credential, pred_nonrevoked_interval) =
-            get_predicates_for_credential(sub_proof_index, &presentation.requested_proof, pres_req);
-
-        // Collaspe to the most stringent local interval for the attributes / predicates,
-        // we can do this because there is only 1 revocation status list for this credential
-        // if it satsifies the most stringent interval, it will satisfy all intervals
-        let mut cred_nonrevoked_interval: Option<NonRevokedInterval> =
-            match (attrs_nonrevoked_interval, pred_nonrevoked_interval) {
-                (Some(attr), None) => Some(attr),
-                (None, Some(pred)) => Some(pred),
-                (Some(mut attr), Some(pred)) => {
-                    attr.compare_and_set(&pred);
-                    Some(attr)
-                }
-                _ => None,
-            };
+        }
+    }
+    for referent in received_attrs {
+        if !pres_req.requested_attributes.contains_key(referent) {
+            builder.fail(
+                FailureCode::UnexpectedReferent,
+                err_msg!(
+                    ProofRejected,
+                    "Attribute \"{}\" not found in presentation request",
+                    referent
+                ),
+            );
+        }
+    }
 
-        // Global interval is override by the local one,
-        // we only need to update if local is None and Global is Some,
-        // do not need to update if global is more stringent
-        if let (Some(global), None) = (
-            pres_req.non_revoked.clone(),
-            cred_nonrevoked_interval.as_mut(),
-        ) {
-            cred_nonrevoked_interval = Some(global);
-        };
+    for referent in pres_req.requested_predicates.keys() {
+        if !received_predicates.contains_key(referent) {
+            builder.fail_referent(
+                referent,
+                true,
+                FailureCode::MissingReferent,
+                err_msg!(
+                    ProofRejected,
+                    "Requested predicate \"{}\" not found in presentation",
+                    referent
+                ),
+            );
+        }
+    }
+    for referent in received_predicates.keys() {
+        if !pres_req.requested_predicates.contains_key(referent) {
+            builder.fail(
+                FailureCode::UnexpectedReferent,
+                err_msg!(
+                    ProofRejected,
+                    "Predicate \"{}\" not found in presentation request",
+                    referent
+                ),
+            );
+        }
+    }
+}
 
-        // Revocation checks is required iff both conditions are met:
-        // - Credential is revokable (input from verifier, trustable)
-        // - PresentationReq has asked for NRP* (input from verifier, trustable)
-        //
-        // * This is done by setting a NonRevokedInterval either for attr / predicate / global
-        let (rev_reg_def, rev_reg) = if let (Some(_), true) = (
-            cred_def.value.revocation.as_ref(),
-            cred_nonrevoked_interval.is_some(),
-        ) {
-            let timestamp = identifier
-                .timestamp
-                .ok_or_else(|| err_msg!("Identifier timestamp not found for revocation check"))?;
+fn attribute_sub_proof_index(requested_proof: &RequestedProof, referent: &str) -> Option<u32> {
+    requested_proof
+        .revealed_attrs
+        .get(referent)
+        .map(|a| a.sub_proof_index)
+        .or_else(|| {
+            requested_proof
+                .revealed_attr_groups
+                .get(referent)
+                .map(|a| a.sub_proof_index)
+        })
+        .or_else(|| {
+            requested_proof
+                .unrevealed_attrs
+                .get(referent)
+                .map(|a| a.sub_proof_index)
+        })
+}
 
-            if rev_reg_defs.is_none() {
-                return Err(err_msg!(
-                    "Timestamp provided but no Revocation Registry Definitions found"
-                ));
+fn sub_proof_restrictions(
+    sub_proof_index: usize,
+    pres_req: &PresentationRequestPayload,
+    report: &VerificationReport,
+) -> CheckStatus {
+    let sub_proof_index = Some(sub_proof_index as u32);
+    let attributes = pres_req
+        .requested_attributes
+        .iter()
+        .map(|(referent, info)| {
+            (
+                &report.requested_attributes[referent],
+                info.restrictions.is_some(),
+            )
+        });
+    let predicates = pres_req
+        .requested_predicates
+        .iter()
+        .map(|(referent, info)| {
+            (
+                &report.requested_predicates[referent],
+                info.restrictions.is_some(),
+            )
+        });
+
+    let mut status = CheckStatus::Skipped;
+    for (referent, restricted) in attributes.chain(predicates) {
+        if referent.sub_proof_index != sub_proof_index || !restricted {
+            continue;
+        }
+        match referent.status.failure() {
+            Some(failure) if failure.code == FailureCode::RestrictionNotSatisfied => {
+                return CheckStatus::Failed(failure.clone());
             }
-            if rev_reg_map.is_none() {
-                return Err(err_msg!(
-                    "Timestamp provided but no Revocation Registries found"
-                ));
+            _ => status = CheckStatus::Passed,
+        }
+    }
+    status
+}
+
+/// Accumulators per revocation registry definition and timestamp
+type RevRegMap = HashMap<RevocationRegistryDefinitionId, HashMap<u64, CryptoRevocationRegistry>>;
+
+fn build_rev_reg_map(lists: Vec<&RevocationStatusList>) -> Result<RevRegMap> {
+    let mut map: RevRegMap = HashMap::new();
+
+    for list in lists {
+        let id = list
+            .id()
+            .ok_or_else(|| err_msg!(Unexpected, "RevStatusList missing Id"))?;
+
+        let timestamp = list
+            .timestamp()
+            .ok_or_else(|| err_msg!(Unexpected, "RevStatusList missing timestamp"))?;
+
+        let rev_reg: Option<ursa::cl::RevocationRegistry> = list.try_into()?;
+        let rev_reg = rev_reg
+            .ok_or_else(|| err_msg!(Unexpected, "Revocation status list missing accumulator"))?;
+
+        map.entry(id).or_default().insert(timestamp, rev_reg);
+    }
+
+    Ok(map)
+}
+
+/// Credential schemas and public keys parsed on first use, so that verifying many presentations
+/// against the same schemas and credential definitions only parses them once
+#[derive(Default)]
+struct KeyCache {
+    credential_schemas: Mutex<HashMap<SchemaId, Arc<CredentialSchema>>>,
+    public_keys: Mutex<HashMap<CredentialDefinitionId, Arc<CredentialPublicKey>>>,
+}
+
+impl KeyCache {
+    fn credential_schema(&self, id: &SchemaId, schema: &Schema) -> Result<Arc<CredentialSchema>> {
+        if let Some(credential_schema) = lock(&self.credential_schemas).get(id) {
+            return Ok(credential_schema.clone());
+        }
+        let credential_schema = Arc::new(build_credential_schema(&schema.attr_names.0)?);
+        lock(&self.credential_schemas).insert(id.clone(), credential_schema.clone());
+        Ok(credential_schema)
+    }
+
+    fn public_key(
+        &self,
+        id: &CredentialDefinitionId,
+        cred_def: &CredentialDefinition,
+    ) -> Result<Arc<CredentialPublicKey>> {
+        if let Some(public_key) = lock(&self.public_keys).get(id) {
+            return Ok(public_key.clone());
+        }
+        let public_key = Arc::new(CredentialPublicKey::build_from_parts(
+            &cred_def.value.primary,
+            cred_def.value.revocation.as_ref(),
+        )?);
+        lock(&self.public_keys).insert(id.clone(), public_key.clone());
+        Ok(public_key)
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    // The cached values are only inserted once built, a poisoned lock holds no partial state
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn reject(status: &mut CheckStatus, code: FailureCode, err: impl Into<Error>) -> Error {
+    let err = err.into();
+    *status = CheckStatus::Failed(VerificationFailure::new(code, &err));
+    err
+}
+
+/// Adds the sub proof request for a single credential to the proof verifier, recording failed
+/// checks in `report`. Returns whether a non-revocation proof is verified for the credential.
+#[allow(clippy::too_many_arguments)]
+fn add_sub_proof_request(
+    proof_verifier: &mut ProofVerifier,
+    report: &mut SubProofReport,
+    sub_proof_index: usize,
+    presentation: &Presentation,
+    pres_req: &PresentationRequestPayload,
+    schemas: &HashMap<&SchemaId, &Schema>,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+    rev_reg_defs: Option<&HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition>>,
+    rev_reg_map: Option<&RevRegMap>,
+    nonrevoke_interval_override: Option<
+        &HashMap<&RevocationRegistryDefinitionId, HashMap<u64, u64>>,
+    >,
+    non_credential_schema: &NonCredentialSchema,
+    key_cache: &KeyCache,
+) -> Result<bool> {
+    let identifier = presentation.identifiers[sub_proof_index].clone();
+
+    let schema = schemas.get(&identifier.schema_id).ok_or_else(|| {
+        reject(
+            &mut report.cl_proof,
+            FailureCode::SchemaNotFound,
+            err_msg!("Schema not provided for ID: {:?}", identifier.schema_id),
+        )
+    })?;
+
+    let cred_def_id = CredentialDefinitionId::new(identifier.cred_def_id.clone())
+        .map_err(|err| reject(&mut report.cl_proof, FailureCode::InvalidIdentifier, err))?;
+    let cred_def = cred_defs.get(&cred_def_id).ok_or_else(|| {
+        reject(
+            &mut report.cl_proof,
+            FailureCode::CredentialDefinitionNotFound,
+            err_msg!(
+                "Credential Definition not provided for ID: {:?}",
+                identifier.cred_def_id
+            ),
+        )
+    })?;
+
+    let (attrs_for_credential, attrs_nonrevoked_interval) = get_revealed_attributes_for_credential(
+        sub_proof_index,
+        &presentation.requested_proof,
+        pres_req,
+    );
+    let (predicates_for_credential, pred_nonrevoked_interval) =
+        get_predicates_for_credential(sub_proof_index, &presentation.requested_proof, pres_req);
+
+    // Collaspe to the most stringent local interval for the attributes / predicates,
+    // we can do this because there is only 1 revocation status list for this credential
+    // if it satsifies the most stringent interval, it will satisfy all intervals
+    let mut cred_nonrevoked_interval: Option<NonRevokedInterval> =
+        match (attrs_nonrevoked_interval, pred_nonrevoked_interval) {
+            (Some(attr), None) => Some(attr),
+            (None, Some(pred)) => Some(pred),
+            (Some(mut attr), Some(pred)) => {
+                attr.compare_and_set(&pred);
+                Some(attr)
             }
+            _ => None,
+        };
 
-            let rev_reg_id = identifier
-                .rev_reg_id
-                .clone()
-                .ok_or_else(|| err_msg!("Revocation Registry Id not found for revocation check"))?;
-
-            // Revocation registry definition id is the same as the rev reg id
-            let rev_reg_def_id = RevocationRegistryDefinitionId::new(rev_reg_id.clone())?;
-
-            // Override Interval if an earlier `from` value is accepted by the verifier
-            nonrevoke_interval_override.map(|maps| {
-                maps.get(&rev_reg_def_id).map(|map| {
-                    cred_nonrevoked_interval
-                        .as_mut()
-                        .map(|int| int.update_with_override(map))
-                })
-            });
+    // Global interval is override by the local one,
+    // we only need to update if local is None and Global is Some,
+    // do not need to update if global is more stringent
+    if let (Some(global), None) = (
+        pres_req.non_revoked.clone(),
+        cred_nonrevoked_interval.as_mut(),
+    ) {
+        cred_nonrevoked_interval = Some(global);
+    };
+
+    // Revocation checks is required iff both conditions are met:
+    // - Credential is revokable (input from verifier, trustable)
+    // - PresentationReq has asked for NRP* (input from verifier, trustable)
+    //
+    // * This is done by setting a NonRevokedInterval either for attr / predicate / global
+    let (rev_reg_def, rev_reg) = if let (Some(_), true) = (
+        cred_def.value.revocation.as_ref(),
+        cred_nonrevoked_interval.is_some(),
+    ) {
+        let timestamp = identifier.timestamp.ok_or_else(|| {
+            reject(
+                &mut report.timestamp_in_interval,
+                FailureCode::MissingTimestamp,
+                err_msg!("Identifier timestamp not found for revocation check"),
+            )
+        })?;
+
+        if rev_reg_defs.is_none() {
+            return Err(reject(
+                &mut report.non_revocation,
+                FailureCode::RevocationRegistryDefinitionNotFound,
+                err_msg!("Timestamp provided but no Revocation Registry Definitions found"),
+            ));
+        }
+        if rev_reg_map.is_none() {
+            return Err(reject(
+                &mut report.non_revocation,
+                FailureCode::RevocationStatusListNotFound,
+                err_msg!("Timestamp provided but no Revocation Registries found"),
+            ));
+        }
 
-            // Validate timestamp
-            cred_nonrevoked_interval
-                .map(|int| int.is_valid(timestamp))
-                .transpose()?;
-
-            let rev_reg_def = Some(
-                rev_reg_defs
-                    .as_ref()
-                    .ok_or_else(|| err_msg!("Could not load the Revocation Registry Definition"))?
-                    .get(&rev_reg_def_id)
-                    .ok_or_else(|| {
+        let rev_reg_id = identifier.rev_reg_id.clone().ok_or_else(|| {
+            reject(
+                &mut report.non_revocation,
+                FailureCode::InvalidIdentifier,
+                err_msg!("Revocation Registry Id not found for revocation check"),
+            )
+        })?;
+
+        // Revocation registry definition id is the same as the rev reg id
+        let rev_reg_def_id =
+            RevocationRegistryDefinitionId::new(rev_reg_id.clone()).map_err(|err| {
+                reject(
+                    &mut report.non_revocation,
+                    FailureCode::InvalidIdentifier,
+                    err,
+                )
+            })?;
+
+        // Override Interval if an earlier `from` value is accepted by the verifier
+        nonrevoke_interval_override.map(|maps| {
+            maps.get(&rev_reg_def_id).map(|map| {
+                cred_nonrevoked_interval
+                    .as_mut()
+                    .map(|int| int.update_with_override(map))
+            })
+        });
+
+        // Validate timestamp
+        cred_nonrevoked_interval
+            .map(|int| int.is_valid(timestamp))
+            .transpose()
+            .map_err(|err| {
+                reject(
+                    &mut report.timestamp_in_interval,
+                    FailureCode::TimestampOutOfInterval,
+                    err,
+                )
+            })?;
+        report.timestamp_in_interval = CheckStatus::Passed;
+
+        let rev_reg_def = Some(
+            rev_reg_defs
+                .as_ref()
+                .ok_or_else(|| err_msg!("Could not load the Revocation Registry Definition"))?
+                .get(&rev_reg_def_id)
+                .ok_or_else(|| {
+                    reject(
+                        &mut report.non_revocation,
+                        FailureCode::RevocationRegistryDefinitionNotFound,
                         err_msg!(
                             "Revocation Registry Definition not provided for ID: {:?}",
                             rev_reg_def_id
-                        )
-                    })?,
-            );
+                        ),
+                    )
+                })?,
+        );
 
-            let rev_reg = Some(
-                rev_reg_map
-                    .as_ref()
-                    .ok_or_else(|| err_msg!("Could not load the Revocation Registry mapping"))?
-                    .get(&rev_reg_def_id)
-                    .and_then(|regs| regs.get(&timestamp))
-                    .ok_or_else(|| {
+        let rev_reg = Some(
+            rev_reg_map
+                .as_ref()
+                .ok_or_else(|| err_msg!("Could not load the Revocation Registry mapping"))?
+                .get(&rev_reg_def_id)
+                .and_then(|regs| regs.get(&timestamp))
+                .ok_or_else(|| {
+                    reject(
+                        &mut report.non_revocation,
+                        FailureCode::RevocationStatusListNotFound,
                         err_msg!(
                             "Revocation Registry not provided for ID and timestamp: {:?}, {:?}",
                             rev_reg_id,
                             timestamp
-                        )
-                    })?,
-            );
-
-            (rev_reg_def, rev_reg)
-        } else {
-            (None, None)
-        };
+                        ),
+                    )
+                })?,
+        );
 
-        let credential_schema = build_credential_schema(&schema.attr_names.0)?;
-        let sub_pres_request =
-            build_sub_proof_request(&attrs_for_credential, &predicates_for_credential)?;
+        (rev_reg_def, rev_reg)
+    } else {
+        (None, None)
+    };
+
+    let credential_schema = key_cache.credential_schema(&identifier.schema_id, schema)?;
+    let equal_attributes = pres_req.equal_attribute_names(|referent| {
+        presentation.requested_proof.sub_proof_index(referent) == Some(sub_proof_index as u32)
+    });
+    let sub_pres_request = build_sub_proof_request(
+        &attrs_for_credential,
+        &predicates_for_credential,
+        &equal_attributes,
+    )?;
 
-        let credential_pub_key = CredentialPublicKey::build_from_parts(
-            &cred_def.value.primary,
-            cred_def.value.revocation.as_ref(),
-        )?;
+    let credential_pub_key = key_cache
+        .public_key(&cred_def_id, cred_def)
+        .map_err(|err| reject(&mut report.cl_proof, FailureCode::InvalidProof, err))?;
 
-        let rev_key_pub = rev_reg_def.map(|d| &d.value.public_keys.accum_key);
+    let rev_key_pub = rev_reg_def.map(|d| &d.value.public_keys.accum_key);
 
-        proof_verifier.add_sub_proof_request(
+    proof_verifier
+        .add_sub_proof_request(
             &sub_pres_request,
             &credential_schema,
-            &non_credential_schema,
+            non_credential_schema,
             &credential_pub_key,
             rev_key_pub,
             rev_reg,
-        )?;
-    }
+        )
+        .map_err(|err| reject(&mut report.cl_proof, FailureCode::InvalidProof, err))?;
 
-    let valid = proof_verifier.verify(&presentation.proof, pres_req.nonce.as_native())?;
+    Ok(rev_reg.is_some())
+}
+
+/// Decode the date revealed for the requested attribute `referent` of a verified presentation
+///
+/// The date is decoded from the encoded value, which is bound to the proof, see
+/// [`Date`](crate::data_types::date::Date) for the encoding.
+pub fn revealed_date(presentation: &Presentation, referent: &str) -> Result<Date> {
+    let attr = presentation
+        .requested_proof
+        .revealed_attrs
+        .get(referent)
+        .ok_or_else(|| err_msg!("Attribute not revealed for referent: {}", referent))?;
+    decode_date(&attr.encoded)
+}
 
-    trace!("verify <<< valid: {:?}", valid);
+/// Decode the date revealed for the attribute `name` of the requested attribute group `referent`
+/// of a verified presentation, as [`revealed_date`]
+pub fn revealed_group_date(
+    presentation: &Presentation,
+    referent: &str,
+    name: &str,
+) -> Result<Date> {
+    let value = presentation
+        .requested_proof
+        .revealed_attr_groups
+        .get(referent)
+        .and_then(|group| group.values.get(name))
+        .ok_or_else(|| err_msg!("Attribute {} not revealed for referent: {}", name, referent))?;
+    decode_date(&value.encoded)
+}
 
-    Ok(valid)
+fn decode_date(encoded: &str) -> Result<Date> {
+    let encoded = encoded
+        .parse::<i32>()
+        .map_err(|_| err_msg!("Revealed value is not an encoded date: {}", encoded))?;
+    Ok(Date::from_encoded(encoded)?)
 }
 
 /// Generates a cryptographically strong pseudo-random nonce with a length of 80 bits
@@ -284,6 +1837,50 @@ pub fn generate_nonce() -> Result<Nonce> {
     new_nonce()
 }
 
+/// The rotation chain of a credential definition: `cred_def_id` followed by the credential
+/// definitions it was rotated from, from the most recent one
+///
+/// `cred_def_id` must come from a trusted source and be the most recent credential definition of
+/// the chain, as its `rotated_from` links decide which credential definitions are accepted.
+/// Every link must have the issuer and schema of `cred_def_id`, otherwise an error is returned.
+/// The chain stops before the first credential definition which is not in `cred_defs`, since its
+/// issuer cannot be checked.
+pub fn credential_definition_chain(
+    cred_def_id: &CredentialDefinitionId,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+) -> Result<Vec<CredentialDefinitionId>> {
+    let cred_def = cred_defs.get(cred_def_id).ok_or_else(|| {
+        err_msg!(
+            "Credential Definition not provided for ID: {:?}",
+            cred_def_id
+        )
+    })?;
+    let mut chain = vec![cred_def_id.clone()];
+    let mut current = *cred_def;
+    while let Some((previous_id, previous)) = current
+        .rotated_from
+        .as_ref()
+        .and_then(|previous_id| Some((previous_id, *cred_defs.get(previous_id)?)))
+    {
+        if chain.contains(previous_id) {
+            return Err(err_msg!(
+                "Cyclic rotation chain for credential definition: {}",
+                cred_def_id
+            ));
+        }
+        if previous.issuer_id != cred_def.issuer_id || previous.schema_id != cred_def.schema_id {
+            return Err(err_msg!(
+                "Credential definition {} in the rotation chain of {} has another issuer or schema",
+                previous_id,
+                cred_def_id
+            ));
+        }
+        chain.push(previous_id.clone());
+        current = previous;
+    }
+    Ok(chain)
+}
+
 fn compare_attr_from_proof_and_request(
     pres_req: &PresentationRequestPayload,
     received_revealed_attrs: &HashMap<String, Identifier>,
@@ -384,73 +1981,107 @@ fn get_proof_identifier(proof: &Presentation, index: u32) -> Result<Identifier>
 fn verify_revealed_attribute_values(
     pres_req: &PresentationRequestPayload,
     proof: &Presentation,
-) -> Result<()> {
+    encoders: &AttributeEncoders,
+) -> Vec<ReferentFailure> {
+    let mut failures = Vec::new();
+
     for (attr_referent, attr_info) in &proof.requested_proof.revealed_attrs {
-        let attr_name = pres_req
-            .requested_attributes
-            .get(attr_referent)
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent
-                )
-            })?
-            .name
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?;
-        verify_revealed_attribute_value(attr_name.as_str(), proof, attr_info)?;
+        if let Err(err) =
+            verify_revealed_attribute(pres_req, proof, attr_referent, attr_info, encoders)
+        {
+            failures.push(ReferentFailure::attribute(attr_referent, err));
+        }
     }
 
     for (attr_referent, attr_infos) in &proof.requested_proof.revealed_attr_groups {
-        let attr_names = pres_req
-            .requested_attributes
-            .get(attr_referent)
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?
-            .names
-            .as_ref()
-            .ok_or_else(|| {
-                err_msg!(
-                    ProofRejected,
-                    "Attribute with referent \"{}\" not found in ProofRequests",
-                    attr_referent,
-                )
-            })?;
-        if attr_infos.values.len() != attr_names.len() {
-            error!("Proof Revealed Attr Group does not match Proof Request Attribute Group, proof request attrs: {:?}, referent: {:?}, attr_infos: {:?}", pres_req.requested_attributes, attr_referent, attr_infos);
-            return Err(err_msg!(
-                "Proof Revealed Attr Group does not match Proof Request Attribute Group",
-            ));
-        }
-        for attr_name in attr_names {
-            let attr_info = &attr_infos.values.get(attr_name).ok_or_else(|| {
-                err_msg!("Proof Revealed Attr Group does not match Proof Request Attribute Group",)
-            })?;
-            verify_revealed_attribute_value(
-                attr_name,
-                proof,
-                &RevealedAttributeInfo {
-                    sub_proof_index: attr_infos.sub_proof_index,
-                    raw: attr_info.raw.clone(),
-                    encoded: attr_info.encoded.clone(),
-                },
-            )?;
+        if let Err(err) =
+            verify_revealed_attribute_group(pres_req, proof, attr_referent, attr_infos, encoders)
+        {
+            failures.push(ReferentFailure::attribute(attr_referent, err));
         }
     }
+
+    failures
+}
+
+fn verify_revealed_attribute(
+    pres_req: &PresentationRequestPayload,
+    proof: &Presentation,
+    attr_referent: &str,
+    attr_info: &RevealedAttributeInfo,
+    encoders: &AttributeEncoders,
+) -> Result<()> {
+    let attr_name = pres_req
+        .requested_attributes
+        .get(attr_referent)
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent
+            )
+        })?
+        .name
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?;
+    verify_revealed_attribute_value(attr_name.as_str(), proof, attr_info, encoders)
+}
+
+fn verify_revealed_attribute_group(
+    pres_req: &PresentationRequestPayload,
+    proof: &Presentation,
+    attr_referent: &str,
+    attr_infos: &RevealedAttributeGroupInfo,
+    encoders: &AttributeEncoders,
+) -> Result<()> {
+    let attr_names = pres_req
+        .requested_attributes
+        .get(attr_referent)
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?
+        .names
+        .as_ref()
+        .ok_or_else(|| {
+            err_msg!(
+                ProofRejected,
+                "Attribute with referent \"{}\" not found in ProofRequests",
+                attr_referent,
+            )
+        })?;
+    if attr_infos.values.len() != attr_names.len() {
+        error!("Proof Revealed Attr Group does not match Proof Request Attribute Group, proof request attrs: {:?}, referent: {:?}, attr_infos: {:?}", pres_req.requested_attributes, attr_referent, attr_infos);
+        return Err(err_msg!(
+            "Proof Revealed Attr Group does not match Proof Request Attribute Group",
+        ));
+    }
+    for attr_name in attr_names {
+        let attr_info = &attr_infos.values.get(attr_name).ok_or_else(|| {
+            err_msg!("Proof Revealed Attr Group does not match Proof Request Attribute Group",)
+        })?;
+        verify_revealed_attribute_value(
+            attr_name,
+            proof,
+            &RevealedAttributeInfo {
+                sub_proof_index: attr_infos.sub_proof_index,
+                raw: attr_info.raw.clone(),
+                encoded: attr_info.encoded.clone(),
+            },
+            encoders,
+        )?;
+    }
     Ok(())
 }
 
@@ -458,6 +2089,7 @@ fn verify_revealed_attribute_value(
     attr_name: &str,
     proof: &Presentation,
     attr_info: &RevealedAttributeInfo,
+    encoders: &AttributeEncoders,
 ) -> Result<()> {
     let reveal_attr_encoded = attr_info.encoded.to_string();
     let reveal_attr_encoded = Regex::new("^0*")
@@ -493,6 +2125,26 @@ fn verify_revealed_attribute_value(
                 "Encoded Values for \"{}\" are different in RequestedProof \"{}\" and CryptoProof \"{}\"", attr_name, reveal_attr_encoded, crypto_proof_encoded));
     }
 
+    // The raw value can only be checked against an encoding known to the verifier
+    if let Some(encoder) = encoders.get(&attr_common_view(attr_name)) {
+        let raw_encoded = encoder.encode(&attr_info.raw).map_err(|err| {
+            err_msg!(
+                ProofRejected,
+                "Raw value for \"{}\" cannot be encoded: {}",
+                attr_name,
+                err
+            )
+        })?;
+        if raw_encoded.trim_start_matches('0') != reveal_attr_encoded {
+            return Err(err_msg!(
+                ProofRejected,
+                "Raw value for \"{}\" does not match its encoded value \"{}\"",
+                attr_name,
+                reveal_attr_encoded
+            ));
+        }
+    }
+
     Ok(())
 }
 
@@ -506,7 +2158,7 @@ fn verify_requested_restrictions(
     received_unrevealed_attrs: &HashMap<String, Identifier>,
     received_predicates: &HashMap<String, Identifier>,
     self_attested_attrs: &HashSet<String>,
-) -> Result<()> {
+) -> Result<Vec<ReferentFailure>> {
     let proof_attr_identifiers: HashMap<String, Identifier> = received_revealed_attrs
         .iter()
         .chain(received_unrevealed_attrs)
@@ -558,96 +2210,124 @@ fn verify_requested_restrictions(
         return Err(err_msg!("Presentation request contains both restrictions for `schema_issuer_id` (new) and `schema_issuer_did` (legacy)"));
     }
 
-    for (referent, info) in &requested_attrs {
-        if let Some(ref query) = info.restrictions {
-            let filter = gather_filter_info(referent, &proof_attr_identifiers, schemas, cred_defs)?;
+    let verify_attribute = |referent: &str, info: &AttributeInfo, query: &Query| -> Result<()> {
+        let filter = gather_filter_info(referent, &proof_attr_identifiers, schemas, cred_defs)?;
 
-            let attr_value_map: HashMap<String, Option<&str>> = if let Some(name) =
-                info.name.as_ref()
-            {
-                let mut map = HashMap::new();
-                map.insert(
-                    name.clone(),
-                    requested_proof
-                        .revealed_attrs
-                        .get(referent)
-                        .map(|attr| attr.raw.as_str()),
-                );
-                map
-            } else if let Some(names) = info.names.as_ref() {
-                let mut map = HashMap::new();
-                let attrs = requested_proof
-                    .revealed_attr_groups
+        let attr_value_map: HashMap<String, Option<&str>> = if let Some(name) = info.name.as_ref() {
+            let mut map = HashMap::new();
+            map.insert(
+                name.clone(),
+                requested_proof
+                    .revealed_attrs
                     .get(referent)
-                    .ok_or_else(|| err_msg!("Proof does not have referent from proof request"))?;
-                for name in names {
-                    let val = attrs.values.get(name).map(|attr| attr.raw.as_str());
-                    map.insert(name.clone(), val);
-                }
-                map
-            } else {
-                error!(
-                    r#"Proof Request attribute restriction should contain "name" or "names" param. Current proof request: {:?}"#,
-                    pres_req
-                );
-                return Err(err_msg!(
-                    r#"Proof Request attribute restriction should contain "name" or "names" param"#,
-                ));
-            };
-
-            process_operator(&attr_value_map, query, &filter).map_err(err_map!(
-                "Requested restriction validation failed for \"{:?}\" attributes",
-                &attr_value_map
-            ))?;
-        }
-    }
+                    .map(|attr| attr.raw.as_str()),
+            );
+            map
+        } else if let Some(names) = info.names.as_ref() {
+            let mut map = HashMap::new();
+            let attrs = requested_proof
+                .revealed_attr_groups
+                .get(referent)
+                .ok_or_else(|| err_msg!("Proof does not have referent from proof request"))?;
+            for name in names {
+                let val = attrs.values.get(name).map(|attr| attr.raw.as_str());
+                map.insert(name.clone(), val);
+            }
+            map
+        } else {
+            error!(
+                r#"Proof Request attribute restriction should contain "name" or "names" param. Current proof request: {:?}"#,
+                pres_req
+            );
+            return Err(err_msg!(
+                r#"Proof Request attribute restriction should contain "name" or "names" param"#,
+            ));
+        };
 
-    for (referent, info) in &pres_req.requested_predicates {
-        if let Some(ref query) = info.restrictions {
-            let filter = gather_filter_info(referent, received_predicates, schemas, cred_defs)?;
+        process_operator(&attr_value_map, query, &filter).map_err(err_map!(
+            "Requested restriction validation failed for \"{:?}\" attributes",
+            &attr_value_map
+        ))
+    };
 
-            // start with the predicate requested attribute, which is un-revealed
-            let mut attr_value_map = HashMap::new();
-            attr_value_map.insert(info.name.to_string(), None);
+    let verify_predicate = |referent: &str, info: &PredicateInfo, query: &Query| -> Result<()> {
+        let filter = gather_filter_info(referent, received_predicates, schemas, cred_defs)?;
 
-            // include any revealed attributes for the same credential (based on sub_proof_index)
-            let pred_sub_proof_index = requested_proof
-                .predicates
-                .get(referent)
-                .unwrap()
-                .sub_proof_index;
-            for attr_referent in requested_proof.revealed_attrs.keys() {
-                let attr_info = requested_proof.revealed_attrs.get(attr_referent).unwrap();
-                let attr_sub_proof_index = attr_info.sub_proof_index;
-                if pred_sub_proof_index == attr_sub_proof_index {
-                    let attr_name = requested_attrs.get(attr_referent).unwrap().name.clone();
-                    if let Some(name) = attr_name {
-                        attr_value_map.insert(name, Some(attr_info.raw.as_str()));
-                    }
+        // start with the predicate requested attribute, which is un-revealed
+        let mut attr_value_map = HashMap::new();
+        attr_value_map.insert(info.name.to_string(), None);
+
+        // include any revealed attributes for the same credential (based on sub_proof_index)
+        let pred_sub_proof_index = requested_proof
+            .predicates
+            .get(referent)
+            .unwrap()
+            .sub_proof_index;
+        for attr_referent in requested_proof.revealed_attrs.keys() {
+            let attr_info = requested_proof.revealed_attrs.get(attr_referent).unwrap();
+            let attr_sub_proof_index = attr_info.sub_proof_index;
+            if pred_sub_proof_index == attr_sub_proof_index {
+                let attr_name = requested_attrs.get(attr_referent).unwrap().name.clone();
+                if let Some(name) = attr_name {
+                    attr_value_map.insert(name, Some(attr_info.raw.as_str()));
                 }
             }
-            for attr_referent in requested_proof.revealed_attr_groups.keys() {
-                let attr_info = requested_proof
-                    .revealed_attr_groups
-                    .get(attr_referent)
-                    .unwrap();
-                let attr_sub_proof_index = attr_info.sub_proof_index;
-                if pred_sub_proof_index == attr_sub_proof_index {
-                    for name in attr_info.values.keys() {
-                        let raw_val = attr_info.values.get(name).unwrap().raw.as_str();
-                        attr_value_map.insert(name.to_string(), Some(raw_val));
-                    }
+        }
+        for attr_referent in requested_proof.revealed_attr_groups.keys() {
+            let attr_info = requested_proof
+                .revealed_attr_groups
+                .get(attr_referent)
+                .unwrap();
+            let attr_sub_proof_index = attr_info.sub_proof_index;
+            if pred_sub_proof_index == attr_sub_proof_index {
+                for name in attr_info.values.keys() {
+                    let raw_val = attr_info.values.get(name).unwrap().raw.as_str();
+                    attr_value_map.insert(name.to_string(), Some(raw_val));
                 }
             }
+        }
+
+        process_operator(&attr_value_map, query, &filter).map_err(err_map!(
+            "Requested restriction validation failed for \"{}\" predicate",
+            &info.name
+        ))
+    };
+
+    let mut failures = Vec::new();
+
+    for (referent, info) in &requested_attrs {
+        if let Some(ref query) = info.restrictions {
+            if let Err(err) = verify_attribute(referent, info, query) {
+                failures.push(ReferentFailure::attribute(referent, err));
+            }
+        }
+    }
 
-            process_operator(&attr_value_map, query, &filter).map_err(err_map!(
-                "Requested restriction validation failed for \"{}\" predicate",
-                &info.name
-            ))?;
+    for (referent, info) in &pres_req.requested_predicates {
+        if let Some(ref query) = info.restrictions {
+            if let Err(err) = verify_predicate(referent, info, query) {
+                failures.push(ReferentFailure::predicate(referent, err));
+            }
+        }
+        // A not-equal predicate must be proven as a strict comparison with its value
+        if info.p_type == PredicateTypes::NE
+            && !matches!(
+                requested_proof.not_equal_predicates.get(referent),
+                Some(PredicateTypes::LT | PredicateTypes::GT)
+            )
+        {
+            failures.push(ReferentFailure::predicate(
+                referent,
+                err_msg!(
+                    ProofRejected,
+                    "Not-equal predicate \"{}\" is not proven by a strict comparison",
+                    referent
+                ),
+            ));
         }
     }
 
-    Ok(())
+    Ok(failures)
 }
 
 fn is_self_attested(
@@ -764,10 +2444,151 @@ fn process_operator(
                 ))
             }
         }
-        _ => Err(err_msg!(ProofRejected, "unsupported operator",)),
+        Query::Gt(ref tag_name, ref tag_value) => {
+            process_comparison(attr_value_map, "$gt", tag_name, tag_value, filter, |ord| {
+                ord == Ordering::Greater
+            })
+        }
+        Query::Gte(ref tag_name, ref tag_value) => {
+            process_comparison(attr_value_map, "$gte", tag_name, tag_value, filter, |ord| {
+                ord != Ordering::Less
+            })
+        }
+        Query::Lt(ref tag_name, ref tag_value) => {
+            process_comparison(attr_value_map, "$lt", tag_name, tag_value, filter, |ord| {
+                ord == Ordering::Less
+            })
+        }
+        Query::Lte(ref tag_name, ref tag_value) => {
+            process_comparison(attr_value_map, "$lte", tag_name, tag_value, filter, |ord| {
+                ord != Ordering::Greater
+            })
+        }
+        Query::Like(ref tag_name, ref pattern) => {
+            let value = revealed_tag_value(attr_value_map, "$like", tag_name, filter)?;
+            if like_match(&value, pattern) {
+                Ok(())
+            } else {
+                Err(err_msg!(
+                    ProofRejected,
+                    "$like operator validation failed for tag: \"{}\", pattern: \"{}\", actual: \"{}\"",
+                    tag_name,
+                    pattern,
+                    value
+                ))
+            }
+        }
+        Query::Exist(ref tag_names) => {
+            // The value of an attribute exists even when it is not revealed
+            match tag_names
+                .iter()
+                .find(|tag_name| restriction_tag_value(attr_value_map, tag_name, filter).is_err())
+            {
+                None => Ok(()),
+                Some(tag_name) => Err(err_msg!(
+                    ProofRejected,
+                    "$exist operator validation failed for tag: \"{}\"",
+                    tag_name
+                )),
+            }
+        }
+    }
+}
+
+/// Compare the value of a tag with `tag_value`, numerically when both are integers and
+/// lexicographically otherwise
+fn process_comparison(
+    attr_value_map: &HashMap<String, Option<&str>>,
+    operator: &str,
+    tag_name: &str,
+    tag_value: &str,
+    filter: &Filter,
+    accept: impl Fn(Ordering) -> bool,
+) -> Result<()> {
+    let value = revealed_tag_value(attr_value_map, operator, tag_name, filter)?;
+    if accept(compare_tag_values(&value, tag_value)) {
+        Ok(())
+    } else {
+        Err(err_msg!(
+            ProofRejected,
+            "{} operator validation failed for tag: \"{}\", value: \"{}\", actual: \"{}\"",
+            operator,
+            tag_name,
+            tag_value,
+            value
+        ))
     }
 }
 
+/// The value of a tag, which must be known to the verifier for `operator` to be evaluated
+fn revealed_tag_value(
+    attr_value_map: &HashMap<String, Option<&str>>,
+    operator: &str,
+    tag_name: &str,
+    filter: &Filter,
+) -> Result<String> {
+    restriction_tag_value(attr_value_map, tag_name, filter)?.ok_or_else(|| {
+        err_msg!(
+            ProofRejected,
+            "{} operator cannot be evaluated for tag: \"{}\", the attribute is not revealed",
+            operator,
+            tag_name
+        )
+    })
+}
+
+/// The value of a restriction tag for the credential of a referent, or `None` for the value of
+/// an attribute which is not revealed
+fn restriction_tag_value(
+    attr_value_map: &HashMap<String, Option<&str>>,
+    tag_name: &str,
+    filter: &Filter,
+) -> Result<Option<String>> {
+    let value = match tag_name {
+        "schema_id" => filter.schema_id.to_string(),
+        "schema_issuer_id" => filter.schema_issuer_id.to_string(),
+        "schema_name" => filter.schema_name.clone(),
+        "schema_version" => filter.schema_version.clone(),
+        "cred_def_id" => filter.cred_def_id.to_string(),
+        "issuer_id" => filter.issuer_id.to_string(),
+        tag_ @ ("schema_issuer_did" | "issuer_did") => {
+            let value = if tag_ == "issuer_did" {
+                filter.issuer_id.to_string()
+            } else {
+                filter.schema_issuer_id.to_string()
+            };
+            // As for `$eq`, the legacy tags only have a value for legacy identifiers
+            if LEGACY_DID_IDENTIFIER.captures(&value).is_none() {
+                return Err(err_msg!(
+                    ProofRejected,
+                    "\"{}\" value is a legacy identifier tag and therefore only legacy identifiers can be used",
+                    tag_,
+                ));
+            }
+            value
+        }
+        key => {
+            let (attr_name, suffix) = INTERNAL_TAG_MATCHER
+                .captures(key)
+                .and_then(|caps| Some((caps.get(1)?.as_str(), caps.get(2)?.as_str())))
+                .ok_or_else(|| err_msg!("Unknown Filter Type"))?;
+            let revealed_value = attr_value_map.get(attr_name).ok_or_else(|| {
+                err_msg!(
+                    ProofRejected,
+                    "Attribute \"{}\" is not requested for the referent",
+                    attr_name
+                )
+            })?;
+            return Ok(if suffix == "marker" {
+                Some("1".to_owned())
+            } else {
+                revealed_value.map(ToOwned::to_owned)
+            });
+        }
+    };
+    Ok(Some(value))
+}
+
 fn process_filter(
     attr_value_map: &HashMap<String, Option<&str>>,
     tag: &str,
@@ -1176,6 +2997,101 @@ mod tests {
         assert!(_process_operator("zip", &op, &filter, Some("NOT HERE")).is_err());
     }
 
+    #[test]
+    fn test_process_op_gt() {
+        let filter = filter();
+        let mut op = Query::Gt(schema_version_tag(), "1.2.0".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Gt(schema_version_tag(), SCHEMA_VERSION.to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        // Integers are compared numerically
+        op = Query::Gt(attr_tag_value(), "9".to_string());
+        _process_operator("zip", &op, &filter, Some("10")).unwrap();
+        assert!(_process_operator("zip", &op, &filter, Some("8")).is_err());
+
+        // The value of an attribute which is not revealed cannot be compared
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+    }
+
+    #[test]
+    fn test_process_op_gte() {
+        let filter = filter();
+        let mut op = Query::Gte(schema_version_tag(), SCHEMA_VERSION.to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Gte(schema_version_tag(), "1.3".to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Gte(attr_tag_value(), "18".to_string());
+        _process_operator("zip", &op, &filter, Some("18")).unwrap();
+        assert!(_process_operator("zip", &op, &filter, Some("9")).is_err());
+    }
+
+    #[test]
+    fn test_process_op_lt() {
+        let filter = filter();
+        let mut op = Query::Lt(schema_version_tag(), "2.0".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Lt(schema_version_tag(), SCHEMA_VERSION.to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Lt(attr_tag_value(), "10".to_string());
+        _process_operator("zip", &op, &filter, Some("9")).unwrap();
+        assert!(_process_operator("zip", &op, &filter, Some("10")).is_err());
+    }
+
+    #[test]
+    fn test_process_op_lte() {
+        let filter = filter();
+        let mut op = Query::Lte(cred_def_id_tag(), CRED_DEF_ID.to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Lte(cred_def_id_tag(), "344".to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Lte(attr_tag_value(), "-1".to_string());
+        _process_operator("zip", &op, &filter, Some("-5")).unwrap();
+        assert!(_process_operator("zip", &op, &filter, Some("0")).is_err());
+    }
+
+    #[test]
+    fn test_process_op_like() {
+        let filter = filter();
+        let mut op = Query::Like(schema_name_tag(), "Schema%".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Like(schema_name_tag(), "%Nam_".to_string());
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Like(schema_name_tag(), "Other%".to_string());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Like(attr_tag_value(), "12%".to_string());
+        _process_operator("zip", &op, &filter, Some("12345")).unwrap();
+        assert!(_process_operator("zip", &op, &filter, Some("54321")).is_err());
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+    }
+
+    #[test]
+    fn test_process_op_exist() {
+        let filter = filter();
+        let mut op = Query::Exist(vec![schema_name_tag(), issuer_did_tag(), attr_tag()]);
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        // The value of an attribute exists even when it is not revealed
+        op = Query::Exist(vec![attr_tag_value()]);
+        _process_operator("zip", &op, &filter, None).unwrap();
+
+        op = Query::Exist(vec![schema_name_tag(), bad_attr_tag()]);
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+
+        op = Query::Exist(vec!["attr::other::marker".to_string()]);
+        assert!(_process_operator("zip", &op, &filter, None).is_err());
+    }
+
     fn _received() -> HashMap<String, Identifier> {
         let mut res: HashMap<String, Identifier> = HashMap::new();
         res.insert(
@@ -0,0 +1,603 @@
+use std::collections::HashMap;
+
+use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use crate::data_types::credential::{
+    AttributeValues, Credential, CredentialValues, ShortCredentialValues,
+};
+use crate::data_types::pres_request::{PresentationRequest, PresentationRequestPayload};
+use crate::data_types::presentation::{Presentation, RequestedProof};
+use crate::data_types::w3c::{
+    CredentialSchemaReference, CredentialSignatureProof, DataIntegrityProof, PresentationProof,
+    PresentationProofValue, W3CCredential, W3CPresentation, ANONCREDS_CONTEXT,
+    ANONCREDS_CREDENTIAL_TYPE, ANONCREDS_DEFINITION_TYPE, ANONCREDS_PRESENTATION_TYPE, W3C_CONTEXT,
+    W3C_CREDENTIAL_TYPE, W3C_PRESENTATION_TYPE,
+};
+use crate::error::Result;
+use crate::services::helpers::encode_credential_attribute;
+use crate::utils::validation::Validatable;
+
+/// Convert an AnonCreds credential into a W3C Verifiable Credential
+///
+/// The raw attribute values are exposed as the `credentialSubject` while the signature material
+/// is carried by a `DataIntegrityProof` using the `AnonCredsProof2023` cryptosuite. The
+/// conversion is lossless, so [`credential_from_w3c`] returns the original credential.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::prover;
+/// use anoncreds::types::MakeCredentialValues;
+/// use anoncreds::w3c;
+///
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let credential_offer =
+///     issuer::create_credential_offer("did:web:xyz/resource/schema",
+///                                     "did:web:xyz/resource/cred-def",
+///                                     &key_correctness_proof,
+///                                     ).expect("Unable to create Credential Offer");
+///
+/// let link_secret =
+///     prover::create_link_secret().expect("Unable to create link secret");
+///
+/// let (credential_request, credential_request_metadata) =
+///     prover::create_credential_request(Some("entropy"),
+///                                       None,
+///                                       &cred_def,
+///                                       &link_secret,
+///                                       "my-secret-id",
+///                                       &credential_offer,
+///                                       ).expect("Unable to create credential request");
+///
+/// let mut credential_values = MakeCredentialValues::default();
+/// credential_values.add_raw("name", "john").expect("Unable to add credential value");
+/// credential_values.add_raw("age", "28").expect("Unable to add credential value");
+///
+/// let mut credential =
+///     issuer::create_credential(&cred_def,
+///                               &cred_def_priv,
+///                               &credential_offer,
+///                               &credential_request,
+///                               credential_values.into(),
+///                               None,
+///                               None,
+///                               None
+///                               ).expect("Unable to create credential");
+///
+/// let w3c_credential = w3c::credential_to_w3c(&credential, &cred_def)
+///     .expect("Unable to convert credential");
+///
+/// assert_eq!(w3c_credential.credential_subject.get("name").unwrap(), "john");
+/// ```
+pub fn credential_to_w3c(
+    credential: &Credential,
+    cred_def: &CredentialDefinition,
+) -> Result<W3CCredential> {
+    trace!(
+        "credential_to_w3c >>> credential: {:?}, cred_def: {:?}",
+        credential,
+        cred_def
+    );
+
+    credential.validate()?;
+    if credential.schema_id != cred_def.schema_id {
+        return Err(err_msg!(
+            "Credential schema {} does not match the credential definition schema {}",
+            credential.schema_id,
+            cred_def.schema_id
+        ));
+    }
+
+    let mut credential_subject = HashMap::with_capacity(credential.values.0.len());
+    let mut encoded = HashMap::new();
+    for (name, value) in &credential.values.0 {
+        if encode_credential_attribute(&value.raw)? != value.encoded {
+            encoded.insert(name.clone(), value.encoded.clone());
+        }
+        credential_subject.insert(name.clone(), value.raw.clone());
+    }
+
+    let proof = CredentialSignatureProof {
+        signature: credential.signature.try_clone()?,
+        signature_correctness_proof: credential.signature_correctness_proof.try_clone()?,
+        rev_reg: credential.rev_reg.clone(),
+        witness: credential.witness.clone(),
+        encoded,
+    };
+
+    let w3c_credential = W3CCredential {
+        context: vec![W3C_CONTEXT.to_string(), ANONCREDS_CONTEXT.to_string()],
+        types: vec![
+            W3C_CREDENTIAL_TYPE.to_string(),
+            ANONCREDS_CREDENTIAL_TYPE.to_string(),
+        ],
+        issuer: cred_def.issuer_id.clone(),
+        valid_from: None,
+        credential_schema: CredentialSchemaReference {
+            type_: ANONCREDS_DEFINITION_TYPE.to_string(),
+            definition: credential.cred_def_id.clone(),
+            schema: credential.schema_id.clone(),
+            revocation: credential.rev_reg_id.clone(),
+        },
+        credential_subject,
+        proof: DataIntegrityProof::new_credential_proof(credential.cred_def_id.clone(), &proof)?,
+    };
+
+    trace!("credential_to_w3c <<< w3c_credential: {:?}", w3c_credential);
+
+    Ok(w3c_credential)
+}
+
+/// Convert a W3C Verifiable Credential created by [`credential_to_w3c`] back into an AnonCreds
+/// credential
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::issuer;
+/// use anoncreds::prover;
+/// use anoncreds::types::MakeCredentialValues;
+/// use anoncreds::w3c;
+///
+/// use anoncreds::types::CredentialDefinitionConfig;
+/// use anoncreds::types::SignatureType;
+///
+/// let attribute_names: &[&str] = &["name", "age"];
+/// let schema = issuer::create_schema("schema name",
+///                                    "1.0",
+///                                    "did:web:xyz",
+///                                    attribute_names.into()
+///                                    ).expect("Unable to create schema");
+///
+/// let (cred_def, cred_def_priv, key_correctness_proof) =
+///     issuer::create_credential_definition("did:web:xyz/resource/schema",
+///                                          &schema,
+///                                          "did:web:xyz",
+///                                          "default-tag",
+///                                          SignatureType::CL,
+///                                          CredentialDefinitionConfig::default()
+///                                          ).expect("Unable to create Credential Definition");
+///
+/// let credential_offer =
+///     issuer::create_credential_offer("did:web:xyz/resource/schema",
+///                                     "did:web:xyz/resource/cred-def",
+///                                     &key_correctness_proof,
+///                                     ).expect("Unable to create Credential Offer");
+///
+/// let link_secret =
+///     prover::create_link_secret().expect("Unable to create link secret");
+///
+/// let (credential_request, credential_request_metadata) =
+///     prover::create_credential_request(Some("entropy"),
+///                                       None,
+///                                       &cred_def,
+///                                       &link_secret,
+///                                       "my-secret-id",
+///                                       &credential_offer,
+///                                       ).expect("Unable to create credential request");
+///
+/// let mut credential_values = MakeCredentialValues::default();
+/// credential_values.add_raw("name", "john").expect("Unable to add credential value");
+/// credential_values.add_raw("age", "28").expect("Unable to add credential value");
+///
+/// let mut credential =
+///     issuer::create_credential(&cred_def,
+///                               &cred_def_priv,
+///                               &credential_offer,
+///                               &credential_request,
+///                               credential_values.into(),
+///                               None,
+///                               None,
+///                               None
+///                               ).expect("Unable to create credential");
+///
+/// let w3c_credential = w3c::credential_to_w3c(&credential, &cred_def)
+///     .expect("Unable to convert credential");
+///
+/// let mut credential = w3c::credential_from_w3c(&w3c_credential)
+///     .expect("Unable to convert credential");
+///
+/// prover::process_credential(&mut credential,
+///                            &credential_request_metadata,
+///                            &link_secret,
+///                            &cred_def,
+///                            None
+///                            ).expect("Unable to process the credential");
+/// ```
+pub fn credential_from_w3c(w3c_credential: &W3CCredential) -> Result<Credential> {
+    trace!(
+        "credential_from_w3c >>> w3c_credential: {:?}",
+        w3c_credential
+    );
+
+    w3c_credential.validate()?;
+    let proof = w3c_credential.proof.get_credential_proof()?;
+
+    if let Some(name) = proof
+        .encoded
+        .keys()
+        .find(|name| !w3c_credential.credential_subject.contains_key(*name))
+    {
+        return Err(err_msg!(
+            "Encoded value provided for unknown attribute: {}",
+            name
+        ));
+    }
+
+    let mut values = HashMap::with_capacity(w3c_credential.credential_subject.len());
+    for (name, raw) in &w3c_credential.credential_subject {
+        let encoded = match proof.encoded.get(name) {
+            Some(encoded) => encoded.clone(),
+            None => encode_credential_attribute(raw)?,
+        };
+        values.insert(
+            name.clone(),
+            AttributeValues {
+                raw: raw.clone(),
+                encoded,
+            },
+        );
+    }
+
+    let credential = Credential {
+        schema_id: w3c_credential.credential_schema.schema.clone(),
+        cred_def_id: w3c_credential.credential_schema.definition.clone(),
+        rev_reg_id: w3c_credential.credential_schema.revocation.clone(),
+        values: CredentialValues(values),
+        signature: proof.signature,
+        signature_correctness_proof: proof.signature_correctness_proof,
+        rev_reg: proof.rev_reg,
+        witness: proof.witness,
+    };
+    credential.validate()?;
+
+    trace!("credential_from_w3c <<< credential: {:?}", credential);
+
+    Ok(credential)
+}
+
+/// Convert a presentation into a W3C Verifiable Presentation
+///
+/// Each presented credential becomes an entry of `verifiableCredential` carrying its sub-proof
+/// and revealed attributes. The aggregated proof, requested proof and identifiers are carried by
+/// the presentation proof, whose challenge is the presentation request nonce.
+pub fn presentation_to_w3c(
+    presentation: &Presentation,
+    pres_req: &PresentationRequest,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+) -> Result<W3CPresentation> {
+    trace!(
+        "presentation_to_w3c >>> presentation: {:?}, pres_req: {:?}, cred_defs: {:?}",
+        presentation,
+        pres_req,
+        cred_defs
+    );
+
+    let pres_req_val = pres_req.value();
+    if presentation.identifiers.len() != presentation.proof.proofs.len() {
+        return Err(err_msg!(
+            "Presentation identifiers do not match the number of sub-proofs"
+        ));
+    }
+
+    let mut verifiable_credential = Vec::with_capacity(presentation.identifiers.len());
+    for (sub_proof_index, (identifier, sub_proof)) in presentation
+        .identifiers
+        .iter()
+        .zip(&presentation.proof.proofs)
+        .enumerate()
+    {
+        let cred_def = cred_defs.get(&identifier.cred_def_id).ok_or_else(|| {
+            err_msg!(
+                "Credential Definition not provided for ID: {}",
+                identifier.cred_def_id
+            )
+        })?;
+        verifiable_credential.push(W3CCredential {
+            context: vec![W3C_CONTEXT.to_string(), ANONCREDS_CONTEXT.to_string()],
+            types: vec![
+                W3C_CREDENTIAL_TYPE.to_string(),
+                ANONCREDS_CREDENTIAL_TYPE.to_string(),
+            ],
+            issuer: cred_def.issuer_id.clone(),
+            valid_from: None,
+            credential_schema: CredentialSchemaReference {
+                type_: ANONCREDS_DEFINITION_TYPE.to_string(),
+                definition: identifier.cred_def_id.clone(),
+                schema: identifier.schema_id.clone(),
+                revocation: identifier.rev_reg_id.clone(),
+            },
+            credential_subject: revealed_attributes(
+                pres_req_val,
+                &presentation.requested_proof,
+                sub_proof_index as u32,
+            )?,
+            proof: DataIntegrityProof::new_sub_proof(identifier.cred_def_id.clone(), sub_proof)?,
+        });
+    }
+
+    let mut proof = match serde_json::to_value(&presentation.proof)? {
+        serde_json::Value::Object(proof) => proof,
+        _ => return Err(err_msg!(Unexpected, "Invalid presentation proof")),
+    };
+    let aggregated_proof = proof.remove("aggregated_proof").ok_or_else(|| {
+        err_msg!(
+            Unexpected,
+            "Presentation proof is missing the aggregated proof"
+        )
+    })?;
+    let proof_value = PresentationProofValue {
+        aggregated_proof: serde_json::from_value(aggregated_proof)?,
+        requested_proof: presentation.requested_proof.clone(),
+        identifiers: presentation.identifiers.clone(),
+    };
+
+    let w3c_presentation = W3CPresentation {
+        context: vec![W3C_CONTEXT.to_string(), ANONCREDS_CONTEXT.to_string()],
+        types: vec![
+            W3C_PRESENTATION_TYPE.to_string(),
+            ANONCREDS_PRESENTATION_TYPE.to_string(),
+        ],
+        verifiable_credential,
+        proof: PresentationProof::new(pres_req_val.nonce.to_string(), &proof_value)?,
+    };
+
+    trace!(
+        "presentation_to_w3c <<< w3c_presentation: {:?}",
+        w3c_presentation
+    );
+
+    Ok(w3c_presentation)
+}
+
+/// Convert a W3C Verifiable Presentation created by [`presentation_to_w3c`] back into a
+/// presentation
+///
+/// The credential entries must match the presentation identifiers, their issuers must be the
+/// issuers of their credential definitions in `cred_defs`, and their subjects must hold exactly
+/// the attributes revealed for `pres_req`.
+pub fn presentation_from_w3c(
+    w3c_presentation: &W3CPresentation,
+    pres_req: &PresentationRequest,
+    cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+) -> Result<Presentation> {
+    trace!(
+        "presentation_from_w3c >>> w3c_presentation: {:?}, pres_req: {:?}, cred_defs: {:?}",
+        w3c_presentation,
+        pres_req,
+        cred_defs
+    );
+
+    w3c_presentation.validate()?;
+    let pres_req_val = pres_req.value();
+    let proof_value = w3c_presentation.proof.get_value()?;
+
+    if w3c_presentation.verifiable_credential.len() != proof_value.identifiers.len() {
+        return Err(err_msg!(
+            "Presentation credentials do not match the presentation identifiers"
+        ));
+    }
+
+    let mut sub_proofs = Vec::with_capacity(proof_value.identifiers.len());
+    for (sub_proof_index, (credential, identifier)) in w3c_presentation
+        .verifiable_credential
+        .iter()
+        .zip(&proof_value.identifiers)
+        .enumerate()
+    {
+        let schema_ref = &credential.credential_schema;
+        if schema_ref.definition != identifier.cred_def_id
+            || schema_ref.schema != identifier.schema_id
+            || schema_ref.revocation != identifier.rev_reg_id
+        {
+            return Err(err_msg!(
+                "Presentation credential {} does not match its identifier",
+                sub_proof_index
+            ));
+        }
+        let cred_def = cred_defs.get(&schema_ref.definition).ok_or_else(|| {
+            err_msg!(
+                "Credential Definition not provided for ID: {}",
+                schema_ref.definition
+            )
+        })?;
+        if credential.issuer != cred_def.issuer_id {
+            return Err(err_msg!(
+                "Presentation credential {} issuer does not match its credential definition",
+                sub_proof_index
+            ));
+        }
+        let revealed = revealed_attributes(
+            pres_req_val,
+            &proof_value.requested_proof,
+            sub_proof_index as u32,
+        )?;
+        if credential.credential_subject != revealed {
+            return Err(err_msg!(
+                "Presentation credential {} subject does not match the revealed attributes",
+                sub_proof_index
+            ));
+        }
+        sub_proofs.push(credential.proof.get_sub_proof()?);
+    }
+
+    let mut proof = serde_json::Map::new();
+    proof.insert("proofs".to_string(), serde_json::to_value(sub_proofs)?);
+    proof.insert(
+        "aggregated_proof".to_string(),
+        serde_json::to_value(proof_value.aggregated_proof)?,
+    );
+    let presentation = Presentation {
+        proof: serde_json::from_value(serde_json::Value::Object(proof))?,
+        requested_proof: proof_value.requested_proof,
+        identifiers: proof_value.identifiers,
+    };
+
+    trace!("presentation_from_w3c <<< presentation: {:?}", presentation);
+
+    Ok(presentation)
+}
+
+/// The raw values revealed by the sub-proof `sub_proof_index`, keyed by attribute name
+fn revealed_attributes(
+    pres_req_val: &PresentationRequestPayload,
+    requested_proof: &RequestedProof,
+    sub_proof_index: u32,
+) -> Result<ShortCredentialValues> {
+    let mut revealed = ShortCredentialValues::new();
+    let mut insert =
+        |name: &str, raw: &str| match revealed.insert(name.to_string(), raw.to_string()) {
+            Some(previous) if previous != raw => Err(err_msg!(
+                "Conflicting values revealed for attribute: {}",
+                name
+            )),
+            _ => Ok(()),
+        };
+
+    for (referent, info) in &requested_proof.revealed_attrs {
+        if info.sub_proof_index != sub_proof_index {
+            continue;
+        }
+        let name = pres_req_val
+            .requested_attributes
+            .get(referent)
+            .and_then(|attr| attr.name.as_deref())
+            .ok_or_else(|| {
+                err_msg!(
+                    "Revealed attribute {} not found in the presentation request",
+                    referent
+                )
+            })?;
+        insert(name, &info.raw)?;
+    }
+    for group in requested_proof.revealed_attr_groups.values() {
+        if group.sub_proof_index != sub_proof_index {
+            continue;
+        }
+        for (name, value) in &group.values {
+            insert(name, &value.raw)?;
+        }
+    }
+
+    Ok(revealed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::services::{issuer, prover};
+    use crate::types::{CredentialDefinitionConfig, MakeCredentialValues, SignatureType};
+
+    const SCHEMA_ID: &str = "did:web:xyz/resource/schema";
+    const CRED_DEF_ID: &str = "did:web:xyz/resource/cred-def";
+
+    fn _credential() -> (Credential, CredentialDefinition) {
+        let schema = issuer::create_schema(
+            "schema name",
+            "1.0",
+            "did:web:xyz",
+            (&["name", "age"][..]).into(),
+        )
+        .unwrap();
+        let (cred_def, cred_def_priv, key_correctness_proof) =
+            issuer::create_credential_definition(
+                SCHEMA_ID,
+                &schema,
+                "did:web:xyz",
+                "default-tag",
+                SignatureType::CL,
+                CredentialDefinitionConfig::default(),
+            )
+            .unwrap();
+        let cred_offer =
+            issuer::create_credential_offer(SCHEMA_ID, CRED_DEF_ID, &key_correctness_proof)
+                .unwrap();
+        let link_secret = prover::create_link_secret().unwrap();
+        let (cred_request, _) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            &cred_def,
+            &link_secret,
+            "default",
+            &cred_offer,
+        )
+        .unwrap();
+
+        let mut values = MakeCredentialValues::default();
+        values.add_raw("name", "john").unwrap();
+        values.add_encoded("age", "twenty-eight", "28".to_string());
+        let credential = issuer::create_credential(
+            &cred_def,
+            &cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            values.into(),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        (credential, cred_def)
+    }
+
+    #[test]
+    fn test_credential_w3c_round_trip() {
+        let (credential, cred_def) = _credential();
+
+        let w3c_credential = credential_to_w3c(&credential, &cred_def).unwrap();
+        assert_eq!(w3c_credential.issuer, cred_def.issuer_id);
+        assert_eq!(w3c_credential.credential_schema.definition.0, CRED_DEF_ID);
+        assert_eq!(w3c_credential.credential_subject["age"], "twenty-eight");
+
+        let proof = w3c_credential.proof.get_credential_proof().unwrap();
+        assert_eq!(
+            proof.encoded,
+            HashMap::from([("age".to_string(), "28".to_string())])
+        );
+
+        let json = serde_json::to_string(&w3c_credential).unwrap();
+        let w3c_credential: W3CCredential = serde_json::from_str(&json).unwrap();
+        let restored = credential_from_w3c(&w3c_credential).unwrap();
+        assert_eq!(
+            serde_json::to_value(&restored).unwrap(),
+            serde_json::to_value(&credential).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_credential_from_w3c_rejects_invalid_proof() {
+        let (credential, cred_def) = _credential();
+        let w3c_credential = credential_to_w3c(&credential, &cred_def).unwrap();
+
+        let mut wrong_suite = w3c_credential.clone();
+        wrong_suite.proof.cryptosuite = "eddsa-2022".to_string();
+        assert!(credential_from_w3c(&wrong_suite).is_err());
+
+        let mut wrong_method = w3c_credential.clone();
+        wrong_method.proof.verification_method = "did:web:xyz/resource/other".try_into().unwrap();
+        assert!(credential_from_w3c(&wrong_method).is_err());
+
+        let mut missing_attribute = w3c_credential;
+        missing_attribute.credential_subject.remove("age");
+        assert!(credential_from_w3c(&missing_attribute).is_err());
+    }
+}
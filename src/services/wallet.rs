@@ -0,0 +1,152 @@
+use std::collections::{BTreeMap, HashMap};
+
+use super::helpers::{build_credential_tags, match_credential_tags};
+use super::storage::HolderStore;
+use super::types::{Credential, CredentialCandidate, CredentialRevocationState};
+use crate::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use crate::data_types::schema::{Schema, SchemaId};
+use crate::error::Result;
+use crate::utils::query::Query;
+
+/// Credentials held by a prover, searchable by the same tags as the `restrictions` of a
+/// presentation request
+///
+/// Each credential is indexed on insertion by its identifiers, the names and versions of its
+/// schema, its issuer and its attributes, with the `attr::<name>::marker` and
+/// `attr::<name>::value` tags. The schema and credential definition tags are only indexed when
+/// they are provided along with the credential.
+///
+/// # Example
+///
+/// ```rust
+/// use anoncreds::types::Query;
+/// use anoncreds::wallet::CredentialWallet;
+///
+/// let wallet = CredentialWallet::new();
+/// let query: Query = serde_json::from_value(serde_json::json!({
+///     "schema_name": "gvt",
+///     "attr::age::value": {"$gte": "18"}
+/// }))
+/// .expect("Unable to parse query");
+///
+/// assert!(wallet.search(&query).is_empty());
+/// ```
+#[derive(Debug, Default)]
+pub struct CredentialWallet {
+    credentials: BTreeMap<String, StoredCredential>,
+}
+
+#[derive(Debug)]
+struct StoredCredential {
+    credential: Credential,
+    tags: HashMap<String, String>,
+    rev_state: Option<CredentialRevocationState>,
+}
+
+impl CredentialWallet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load the credentials of a [`HolderStore`], indexed with their schemas and credential
+    /// definitions found in `schemas` and `cred_defs`
+    pub fn load(
+        store: &impl HolderStore,
+        schemas: &HashMap<&SchemaId, &Schema>,
+        cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>,
+    ) -> Result<Self> {
+        let mut wallet = Self::new();
+        for id in store.credential_ids()? {
+            let credential = store
+                .credential(&id)?
+                .ok_or_else(|| err_msg!(InvalidState, "Stored credential {} is missing", id))?;
+            let schema = schemas.get(&credential.schema_id).copied();
+            let cred_def = cred_defs.get(&credential.cred_def_id).copied();
+            wallet.insert(id, credential, schema, cred_def);
+        }
+        Ok(wallet)
+    }
+
+    /// Add a credential under the identifier `id`, returning the credential it replaces
+    pub fn insert(
+        &mut self,
+        id: impl Into<String>,
+        credential: Credential,
+        schema: Option<&Schema>,
+        cred_def: Option<&CredentialDefinition>,
+    ) -> Option<Credential> {
+        let tags = build_credential_tags(&credential, schema, cred_def);
+        self.credentials
+            .insert(
+                id.into(),
+                StoredCredential {
+                    credential,
+                    tags,
+                    rev_state: None,
+                },
+            )
+            .map(|stored| stored.credential)
+    }
+
+    /// Set the revocation state a credential is presented with
+    pub fn set_rev_state(&mut self, id: &str, rev_state: CredentialRevocationState) -> Result<()> {
+        let stored = self
+            .credentials
+            .get_mut(id)
+            .ok_or_else(|| err_msg!("Unknown credential {}", id))?;
+        stored.rev_state = Some(rev_state);
+        Ok(())
+    }
+
+    pub fn remove(&mut self, id: &str) -> Option<Credential> {
+        self.credentials.remove(id).map(|stored| stored.credential)
+    }
+
+    #[must_use]
+    pub fn get(&self, id: &str) -> Option<&Credential> {
+        self.credentials.get(id).map(|stored| &stored.credential)
+    }
+
+    /// The tags a credential is searched by
+    #[must_use]
+    pub fn tags(&self, id: &str) -> Option<&HashMap<String, String>> {
+        self.credentials.get(id).map(|stored| &stored.tags)
+    }
+
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.credentials.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.credentials.is_empty()
+    }
+
+    /// The credentials with their identifiers, in the order of the identifiers
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Credential)> {
+        self.credentials
+            .iter()
+            .map(|(id, stored)| (id.as_str(), &stored.credential))
+    }
+
+    /// The credentials whose tags match `query`, in the order of their identifiers
+    #[must_use]
+    pub fn search(&self, query: &Query) -> Vec<(&str, &Credential)> {
+        self.credentials
+            .iter()
+            .filter(|(_, stored)| match_credential_tags(query, &stored.tags))
+            .map(|(id, stored)| (id.as_str(), &stored.credential))
+            .collect()
+    }
+
+    /// The credentials with their revocation states, to be matched against a presentation
+    /// request by [`prover::match_credentials`](crate::prover::match_credentials)
+    #[must_use]
+    pub fn candidates(&self) -> Vec<CredentialCandidate<'_>> {
+        self.credentials
+            .values()
+            .map(|stored| CredentialCandidate::new(&stored.credential, stored.rev_state.as_ref()))
+            .collect()
+    }
+}
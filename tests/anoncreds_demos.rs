@@ -1,12 +1,31 @@
-use anoncreds::data_types::cred_def::CredentialDefinitionId;
+use anoncreds::data_types::cred_def::{CredentialDefinition, CredentialDefinitionId};
+use anoncreds::data_types::date::Date;
+use anoncreds::data_types::issuer_id::IssuerId;
+use anoncreds::data_types::pres_exchange::PresentationDefinition;
+use anoncreds::data_types::pres_request::{PredicateInfo, PredicateTypes};
 use anoncreds::data_types::rev_reg::RevocationRegistryId;
 use anoncreds::data_types::rev_reg_def::RevocationRegistryDefinitionId;
 use anoncreds::data_types::schema::SchemaId;
+use anoncreds::encoding::DateEncoder;
 use anoncreds::issuer;
+use anoncreds::pres_exchange;
+use anoncreds::pres_request_builder::{PresentationRequestBuilder, Restrictions};
 use anoncreds::prover;
-use anoncreds::tails::{TailsFileReader, TailsFileWriter};
-use anoncreds::types::{CredentialRevocationConfig, PresentCredentials};
+use anoncreds::resolver::InMemoryResolver;
+use anoncreds::rev_reg_set::RevocationRegistrySet;
+use anoncreds::storage::{HolderStore, InMemoryStore};
+use anoncreds::tails::{TailsBufferReader, TailsFileReader, TailsFileWriter};
+use anoncreds::types::{
+    Credential, CredentialBatchRevocationConfig, CredentialCandidate, CredentialRevocationConfig,
+    CredentialRevocationState, MakeCredentialValues, PresentCredentials, Presentation,
+    PresentationRequest, Query, RegistryType, RevocationRegistryAllocator, W3CCredential,
+    W3CPresentation,
+};
 use anoncreds::verifier;
+use anoncreds::w3c;
+use anoncreds::wallet::CredentialWallet;
+use anoncreds::ErrorKind;
+use futures_executor::block_on;
 use serde_json::json;
 use std::{
     collections::{BTreeSet, HashMap},
@@ -191,6 +210,340 @@ fn anoncreds_demo_works_for_single_issuer_single_prover() {
     assert!(valid);
 }
 
+#[test]
+fn anoncreds_demo_works_for_batch_verification() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema)]);
+    let cred_defs = HashMap::from([(&gvt_cred_def_id, &gvt_cred_def)]);
+
+    let pres_request = fixtures::create_presentation_request();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &pres_request,
+        &prover_wallet,
+        None,
+        None,
+    );
+
+    // Presentations can be verified in a batch sharing the parsed keys, a presentation replayed
+    // for a request with another nonce is rejected
+    let mut replayed_request = serde_json::to_value(&pres_request).unwrap();
+    replayed_request["nonce"] = json!(verifier::generate_nonce().unwrap());
+    let replayed_request = serde_json::from_value(replayed_request).unwrap();
+
+    let batch_verifier = verifier::BatchVerifier::new(schemas, cred_defs, None, None)
+        .expect("Error creating batch verifier");
+    let results = batch_verifier.verify(&[
+        (&presentation, &pres_request),
+        (&presentation, &replayed_request),
+        (&presentation, &pres_request),
+    ]);
+    let results: Vec<bool> = results
+        .into_iter()
+        .map(|res| res.expect("Error verifying presentation"))
+        .collect();
+    assert_eq!(results, vec![true, false, true]);
+
+    let reports = batch_verifier.verify_detailed(&[(&presentation, &replayed_request)]);
+    assert!(reports[0].proof.is_failed());
+}
+
+#[test]
+fn anoncreds_demo_works_for_like_restriction() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema)]);
+    let cred_defs = HashMap::from([(&gvt_cred_def_id, &gvt_cred_def)]);
+
+    // Verifier restricts a revealed attribute to the credentials of schemas matching a pattern
+    let create_request = |pattern: &str| -> PresentationRequest {
+        let nonce =
+            verifier::generate_nonce().expect("Error generating presentation request nonce");
+        serde_json::from_value(json!({
+            "nonce": nonce,
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{
+                    "name":"name",
+                    "restrictions": { "schema_name": { "$like": pattern } }
+                }
+            }
+        }))
+        .expect("Error creating proof request")
+    };
+    let present = |pres_request: &PresentationRequest| {
+        let mut present = PresentCredentials::default();
+        present
+            .add_credential(&prover_wallet.credentials[0], None, None)
+            .add_requested_attribute("attr1_referent", true);
+        prover::create_presentation(
+            pres_request,
+            present,
+            None,
+            &prover_wallet.link_secret,
+            &schemas,
+            &cred_defs,
+        )
+        .expect("Error creating presentation")
+    };
+
+    let pres_request = create_request("Gov%");
+    let presentation = present(&pres_request);
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+
+    let pres_request = create_request("Employee%");
+    let presentation = present(&pres_request);
+    assert!(verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .is_err());
+}
+
+#[test]
+fn anoncreds_demo_works_for_object_resolver() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+
+    // Schemas and credential definitions are resolved on demand instead of gathered up front
+    let mut resolver = InMemoryResolver::new();
+    resolver
+        .add_schema(gvt_schema_id, gvt_schema)
+        .add_cred_def(gvt_cred_def_id, gvt_cred_def);
+
+    let pres_request = fixtures::create_presentation_request();
+    let mut present = PresentCredentials::default();
+    {
+        let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+        cred1.add_requested_attribute("attr1_referent", true);
+        cred1.add_requested_attribute("attr2_referent", false);
+        cred1.add_requested_attribute("attr4_referent", true);
+        cred1.add_requested_predicate("predicate1_referent");
+    }
+    let presentation = prover::create_presentation_with_resolver(
+        &pres_request,
+        present,
+        Some(HashMap::from([(
+            "attr3_referent".to_string(),
+            "8-800-300".to_string(),
+        )])),
+        &prover_wallet.link_secret,
+        &resolver,
+    )
+    .expect("Error creating presentation");
+
+    let valid =
+        verifier::verify_presentation_with_resolver(&presentation, &pres_request, &resolver, None)
+            .expect("Error verifying presentation");
+    assert!(valid);
+
+    let valid = block_on(verifier::verify_presentation_with_async_resolver(
+        &presentation,
+        &pres_request,
+        &resolver,
+        None,
+    ))
+    .expect("Error verifying presentation");
+    assert!(valid);
+}
+
+#[test]
+fn anoncreds_demo_works_for_presentation_definition() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema)]);
+    let cred_defs = HashMap::from([(&gvt_cred_def_id, &gvt_cred_def)]);
+
+    // Verifier publishes a DIF presentation definition instead of a presentation request
+    let definition: PresentationDefinition = serde_json::from_value(json!({
+        "id": "gvt_definition",
+        "input_descriptors": [{
+            "id": "gvt",
+            "constraints": {
+                "fields": [
+                    { "path": ["$.credentialSubject.name"] },
+                    { "path": ["$.credentialSubject.sex"] },
+                    {
+                        "path": ["$.credentialSubject.age"],
+                        "filter": { "type": "number", "minimum": 18 }
+                    },
+                    {
+                        "path": ["$.credentialSchema.definition"],
+                        "filter": {
+                            "type": "string",
+                            "const": gvt_cred_def_id.to_string()
+                        }
+                    }
+                ]
+            }
+        }]
+    }))
+    .expect("Error parsing presentation definition");
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    let pres_request = pres_exchange::create_presentation_request(&definition, nonce)
+        .expect("Error translating presentation definition");
+
+    let mut present = PresentCredentials::default();
+    {
+        let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+        cred1.add_requested_attribute("gvt", true);
+        cred1.add_requested_predicate("gvt_predicate_0");
+    }
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    let submission = pres_exchange::create_presentation_submission(&definition, &presentation)
+        .expect("Error creating presentation submission");
+    assert_eq!(submission.definition_id, "gvt_definition");
+    assert_eq!(
+        submission.descriptor_map[0]
+            .path_nested
+            .as_ref()
+            .unwrap()
+            .path,
+        "$.identifiers[0]"
+    );
+
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+}
+
+#[test]
+fn anoncreds_demo_works_for_presentation_definition_answered_by_one_credential() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let (dl_schema, dl_schema_id, dl_cred_def, dl_cred_def_id) =
+        fixtures::issue_credential("DL", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema), (&dl_schema_id, &dl_schema)]);
+    let cred_defs = HashMap::from([
+        (&gvt_cred_def_id, &gvt_cred_def),
+        (&dl_cred_def_id, &dl_cred_def),
+    ]);
+
+    // The name and the age of the descriptor must come from the same credential
+    let definition: PresentationDefinition = serde_json::from_value(json!({
+        "id": "adult_definition",
+        "input_descriptors": [{
+            "id": "adult",
+            "constraints": {
+                "fields": [
+                    { "path": ["$.credentialSubject.name"] },
+                    {
+                        "path": ["$.credentialSubject.age"],
+                        "filter": { "type": "number", "minimum": 18 }
+                    }
+                ]
+            }
+        }]
+    }))
+    .expect("Error parsing presentation definition");
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    let pres_request = pres_exchange::create_presentation_request(&definition, nonce)
+        .expect("Error translating presentation definition");
+
+    // The name is taken from the DL credential, the age from the GVT credential
+    let present = || {
+        let mut present = PresentCredentials::default();
+        present
+            .add_credential(&prover_wallet.credentials[1], None, None)
+            .add_requested_attribute("adult", true);
+        present
+            .add_credential(&prover_wallet.credentials[0], None, None)
+            .add_requested_predicate("adult_predicate_0");
+        present
+    };
+    assert!(prover::create_presentation(
+        &pres_request,
+        present(),
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .is_err());
+
+    // A prover ignoring the binding is rejected by the verifier
+    let mut unbound_request = serde_json::to_value(&pres_request).unwrap();
+    unbound_request
+        .as_object_mut()
+        .unwrap()
+        .remove("same_credential_referents");
+    let unbound_request: PresentationRequest =
+        serde_json::from_value(unbound_request).expect("Error creating proof request");
+    let presentation = prover::create_presentation(
+        &unbound_request,
+        present(),
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    assert!(verifier::verify_presentation(
+        &presentation,
+        &unbound_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation"));
+    let report = verifier::verify_presentation_detailed(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(!report.verified);
+    assert!(report
+        .all_failures()
+        .any(|failure| failure.code == verifier::FailureCode::CredentialMismatch));
+    assert!(pres_exchange::create_presentation_submission(&definition, &presentation).is_err());
+}
+
 #[test]
 fn anoncreds_demo_works_with_revocation_for_single_issuer_single_prover() {
     // Create Prover pseudo wallet and link secret
@@ -418,84 +771,428 @@ fn anoncreds_demo_works_with_revocation_for_single_issuer_single_prover() {
 }
 
 #[test]
-fn anoncreds_demo_works_for_multiple_issuer_single_prover() {
-    let mut prover_wallet = ProverWallet::default();
-
-    // Issuer 1 creates Schema - would be published to the ledger
-    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
-
-    // Issuer 1 create credential definition
-    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
-        fixtures::create_cred_def(&gvt_schema, false);
+fn anoncreds_demo_works_with_revocation_for_w3c_credential() {
+    let mut cred = fixtures::RevocableCredential::issue();
 
-    // Issuer 2 creates Schema - would be published to the ledger
-    let (emp_schema, emp_schema_id) = fixtures::create_schema("EMP");
+    // The credential is exchanged in its W3C form, which keeps the revocation registry
+    let issued_cred = cred.prover_wallet.credentials.pop().unwrap();
+    let w3c_cred =
+        w3c::credential_to_w3c(&issued_cred, &cred.cred_def).expect("Error converting credential");
+    assert_eq!(
+        w3c_cred.credential_schema.revocation.as_ref(),
+        issued_cred.rev_reg_id.as_ref()
+    );
+    let w3c_cred_json = serde_json::to_string(&w3c_cred).expect("Error serializing credential");
+
+    let w3c_cred: W3CCredential =
+        serde_json::from_str(&w3c_cred_json).expect("Error deserializing credential");
+    let recv_cred = w3c::credential_from_w3c(&w3c_cred).expect("Error converting credential");
+    assert_eq!(recv_cred.rev_reg_id, issued_cred.rev_reg_id);
+    cred.prover_wallet.credentials.push(recv_cred);
+
+    // The converted credential still proves non revocation
+    let tails_reader = TailsFileReader::new_tails_reader(&cred.rev_reg_def.value.tails_location);
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued),
+        Some(&rev_state),
+    );
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &cred.pres_request,
+        &schemas,
+        &cred_defs,
+        Some(&cred.rev_reg_defs()),
+        Some(vec![&cred.issued_list]),
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+}
 
-    // Issuer 1 create credential definition
-    let ((emp_cred_def, emp_cred_def_priv, emp_cred_key_correctness_proof), emp_cred_def_id) =
-        fixtures::create_cred_def(&emp_schema, false);
+#[test]
+fn anoncreds_demo_works_with_revocation_for_verifier_context() {
+    let cred = fixtures::RevocableCredential::issue();
+    let tails_reader = TailsFileReader::new_tails_reader(&cred.rev_reg_def.value.tails_location);
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued),
+        Some(&rev_state),
+    );
 
-    let gvt_cred_offer = issuer::create_credential_offer(
-        gvt_schema_id,
-        gvt_cred_def_id,
-        &gvt_cred_key_correctness_proof,
+    // The same inputs can be prepared once in a context shared by many verifications
+    let context = verifier::VerifierContext::new(
+        schemas.clone(),
+        cred_defs.clone(),
+        Some(cred.rev_reg_defs()),
+        Some(vec![&cred.issued_list]),
     )
-    .expect("Unable to create credential offer");
-
-    let (gvt_cred_request, gvt_cred_request_metadata) = prover::create_credential_request(
-        Some("entropy"),
+    .expect("Error creating verifier context");
+    let valid = context
+        .verify_presentation(&presentation, &cred.pres_request, None)
+        .expect("Error verifying presentation");
+    assert!(valid);
+    let valid = verifier::verify_presentation_with_context(
+        &presentation,
+        &cred.pres_request,
+        &context,
         None,
-        &gvt_cred_def,
-        &prover_wallet.link_secret,
-        "default",
-        &gvt_cred_offer,
     )
-    .expect("Error creating credential request");
+    .expect("Error verifying presentation");
+    assert!(valid);
 
-    // Issuer creates a credential
-    let gvt_cred_values = fixtures::credential_values("GVT");
+    // A batch verifier wraps the context
+    let batch_verifier = verifier::BatchVerifier::from(context);
+    let results = batch_verifier.verify(&[(&presentation, &cred.pres_request)]);
+    assert!(results[0].as_ref().expect("Error verifying presentation"));
 
-    let gvt_issue_cred = issuer::create_credential(
-        &gvt_cred_def,
-        &gvt_cred_def_priv,
-        &gvt_cred_offer,
-        &gvt_cred_request,
-        gvt_cred_values.into(),
-        None,
-        None,
+    // Missing inputs are reported when creating the context
+    assert!(verifier::VerifierContext::new(HashMap::new(), cred_defs.clone(), None, None).is_err());
+    assert!(verifier::VerifierContext::new(
+        schemas,
+        cred_defs,
         None,
+        Some(vec![&cred.issued_list])
     )
-    .expect("Error creating credential");
+    .is_err());
+}
 
-    let mut gvt_recv_cred = gvt_issue_cred;
-    prover::process_credential(
-        &mut gvt_recv_cred,
-        &gvt_cred_request_metadata,
-        &prover_wallet.link_secret,
-        &gvt_cred_def,
+#[test]
+fn anoncreds_demo_works_with_revocation_for_tails_buffer_reader() {
+    let cred = fixtures::RevocableCredential::issue();
+
+    // The tails file can be provided from any source, here from memory, and checked before use
+    let tails_data =
+        std::fs::read(&cred.rev_reg_def.value.tails_location).expect("Error reading tails file");
+    let tails_reader = TailsBufferReader::new_tails_reader(tails_data.clone()).with_verification();
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let rev_reg_defs = cred.rev_reg_defs();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued),
+        Some(&rev_state),
+    );
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &cred.pres_request,
+        &schemas,
+        &cred_defs,
+        Some(&rev_reg_defs),
+        Some(vec![&cred.issued_list]),
         None,
     )
-    .expect("Error processing credential");
-    prover_wallet.credentials.push(gvt_recv_cred);
+    .expect("Error verifying presentation");
+    assert!(valid);
 
-    let emp_cred_offer = issuer::create_credential_offer(
-        emp_schema_id,
-        emp_cred_def_id,
-        &emp_cred_key_correctness_proof,
+    // Issuer revokes the credential with the same reader
+    let revoked_list = cred.revoke(Some(&tails_reader));
+    let rev_state = prover::create_or_update_revocation_state_with_tails_reader(
+        &tails_reader,
+        &cred.rev_reg_def,
+        &revoked_list,
+        fixtures::GVT_REV_IDX,
+        Some(&rev_state),
+        Some(&cred.issued_list),
     )
-    .expect("Unable to create credential offer");
-
-    let (emp_cred_request, emp_cred_request_metadata) = prover::create_credential_request(
-        Some("entropy"),
+    .expect("Error updating revocation state");
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued + 1),
+        Some(&rev_state),
+    );
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &cred.pres_request,
+        &schemas,
+        &cred_defs,
+        Some(&rev_reg_defs),
+        Some(vec![&cred.issued_list, &revoked_list]),
         None,
-        &emp_cred_def,
-        &prover_wallet.link_secret,
-        "default",
-        &emp_cred_offer,
     )
-    .expect("Error creating credential request");
+    .expect("Error verifying presentation");
+    assert!(!valid);
 
-    let emp_cred_values = fixtures::credential_values("EMP");
+    // A tampered tails file is rejected before use
+    let mut tampered = tails_data;
+    tampered[10] ^= 1;
+    let tails_reader = TailsBufferReader::new_tails_reader(tampered).with_verification();
+    assert_eq!(
+        prover::create_or_update_revocation_state_with_tails_reader(
+            &tails_reader,
+            &cred.rev_reg_def,
+            &cred.issued_list,
+            fixtures::GVT_REV_IDX,
+            None,
+            None,
+        )
+        .unwrap_err()
+        .kind(),
+        ErrorKind::InvalidTailsFile
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_with_revocation_status_list_delta() {
+    let cred = fixtures::RevocableCredential::issue();
+    let tails_reader = TailsFileReader::new_tails_reader(&cred.rev_reg_def.value.tails_location);
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let revoked_list = cred.revoke(None);
+
+    // Holders following a registry which publishes deltas can update from the delta directly
+    let delta = cred
+        .issued_list
+        .diff(&revoked_list)
+        .expect("Error computing revocation status list delta");
+    assert!(delta.issued.is_empty());
+    assert_eq!(delta.revoked, BTreeSet::from([fixtures::GVT_REV_IDX]));
+    let mut applied_status_list = cred.issued_list.clone();
+    applied_status_list
+        .apply_delta(&delta)
+        .expect("Error applying revocation status list delta");
+    assert_eq!(json!(applied_status_list), json!(revoked_list));
+
+    let delta_rev_state = prover::update_revocation_state_with_delta(
+        &tails_reader,
+        &cred.rev_reg_def,
+        &rev_state,
+        fixtures::GVT_REV_IDX,
+        &delta,
+    )
+    .expect("Error updating revocation state from delta");
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued + 1),
+        Some(&delta_rev_state),
+    );
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &cred.pres_request,
+        &schemas,
+        &cred_defs,
+        Some(&cred.rev_reg_defs()),
+        Some(vec![&cred.issued_list, &revoked_list]),
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(!valid);
+}
+
+#[test]
+fn anoncreds_demo_verification_report_for_revoked_credential() {
+    let cred = fixtures::RevocableCredential::issue();
+    let tails_reader = TailsFileReader::new_tails_reader(&cred.rev_reg_def.value.tails_location);
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let revoked_list = cred.revoke(None);
+    let rev_state = prover::create_or_update_revocation_state_with_tails_reader(
+        &tails_reader,
+        &cred.rev_reg_def,
+        &revoked_list,
+        fixtures::GVT_REV_IDX,
+        Some(&rev_state),
+        Some(&cred.issued_list),
+    )
+    .expect("Error updating revocation state");
+
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued + 1),
+        Some(&rev_state),
+    );
+    let report = verifier::verify_presentation_detailed(
+        &presentation,
+        &cred.pres_request,
+        &schemas,
+        &cred_defs,
+        Some(&cred.rev_reg_defs()),
+        Some(vec![&cred.issued_list, &revoked_list]),
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(!report.verified);
+    assert!(report.sub_proofs[0].timestamp_in_interval.is_passed());
+    assert_eq!(
+        report.sub_proofs[0]
+            .non_revocation
+            .failure()
+            .map(|f| f.code),
+        Some(verifier::FailureCode::InvalidProof)
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_with_revocation_for_async_resolver() {
+    let cred = fixtures::RevocableCredential::issue();
+    let tails_reader = TailsFileReader::new_tails_reader(&cred.rev_reg_def.value.tails_location);
+    let schemas = cred.schemas();
+    let cred_defs = cred.cred_defs();
+    let rev_state = cred.create_rev_state(&tails_reader);
+    let presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued),
+        Some(&rev_state),
+    );
+
+    let revoked_list = cred.revoke(None);
+    let rev_state = prover::create_or_update_revocation_state_with_tails_reader(
+        &tails_reader,
+        &cred.rev_reg_def,
+        &revoked_list,
+        fixtures::GVT_REV_IDX,
+        Some(&rev_state),
+        Some(&cred.issued_list),
+    )
+    .expect("Error updating revocation state");
+    let revoked_presentation = fixtures::create_presentation(
+        &schemas,
+        &cred_defs,
+        &cred.pres_request,
+        &cred.prover_wallet,
+        Some(cred.time_issued + 1),
+        Some(&rev_state),
+    );
+    drop((schemas, cred_defs));
+
+    // The revocation status lists referenced by the presentations can be resolved on demand
+    let mut resolver = InMemoryResolver::new();
+    resolver
+        .add_schema(cred.schema_id, cred.schema)
+        .add_cred_def(cred.cred_def_id, cred.cred_def)
+        .add_rev_reg_def(cred.rev_reg_def_id, cred.rev_reg_def)
+        .add_rev_status_list(cred.issued_list)
+        .expect("Error adding revocation status list")
+        .add_rev_status_list(revoked_list)
+        .expect("Error adding revocation status list");
+    let valid = block_on(verifier::verify_presentation_with_async_resolver(
+        &presentation,
+        &cred.pres_request,
+        &resolver,
+        None,
+    ))
+    .expect("Error verifying presentation");
+    assert!(valid);
+
+    let valid = block_on(verifier::verify_presentation_with_async_resolver(
+        &revoked_presentation,
+        &cred.pres_request,
+        &resolver,
+        None,
+    ))
+    .expect("Error verifying presentation");
+    assert!(!valid);
+}
+
+#[test]
+fn anoncreds_demo_works_for_multiple_issuer_single_prover() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // Issuer 1 creates Schema - would be published to the ledger
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+
+    // Issuer 1 create credential definition
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, false);
+
+    // Issuer 2 creates Schema - would be published to the ledger
+    let (emp_schema, emp_schema_id) = fixtures::create_schema("EMP");
+
+    // Issuer 1 create credential definition
+    let ((emp_cred_def, emp_cred_def_priv, emp_cred_key_correctness_proof), emp_cred_def_id) =
+        fixtures::create_cred_def(&emp_schema, false);
+
+    let gvt_cred_offer = issuer::create_credential_offer(
+        gvt_schema_id,
+        gvt_cred_def_id,
+        &gvt_cred_key_correctness_proof,
+    )
+    .expect("Unable to create credential offer");
+
+    let (gvt_cred_request, gvt_cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &gvt_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &gvt_cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    // Issuer creates a credential
+    let gvt_cred_values = fixtures::credential_values("GVT");
+
+    let gvt_issue_cred = issuer::create_credential(
+        &gvt_cred_def,
+        &gvt_cred_def_priv,
+        &gvt_cred_offer,
+        &gvt_cred_request,
+        gvt_cred_values.into(),
+        None,
+        None,
+        None,
+    )
+    .expect("Error creating credential");
+
+    let mut gvt_recv_cred = gvt_issue_cred;
+    prover::process_credential(
+        &mut gvt_recv_cred,
+        &gvt_cred_request_metadata,
+        &prover_wallet.link_secret,
+        &gvt_cred_def,
+        None,
+    )
+    .expect("Error processing credential");
+    prover_wallet.credentials.push(gvt_recv_cred);
+
+    let emp_cred_offer = issuer::create_credential_offer(
+        emp_schema_id,
+        emp_cred_def_id,
+        &emp_cred_key_correctness_proof,
+    )
+    .expect("Unable to create credential offer");
+
+    let (emp_cred_request, emp_cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &emp_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &emp_cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    let emp_cred_values = fixtures::credential_values("EMP");
 
     let emp_issue_cred = issuer::create_credential(
         &emp_cred_def,
@@ -594,6 +1291,177 @@ fn anoncreds_demo_works_for_multiple_issuer_single_prover() {
     assert!(valid);
 }
 
+#[test]
+fn anoncreds_demo_works_for_credential_matching() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let (emp_schema, emp_schema_id, emp_cred_def, emp_cred_def_id) =
+        fixtures::issue_credential("EMP", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema), (&emp_schema_id, &emp_schema)]);
+    let cred_defs = HashMap::from([
+        (&gvt_cred_def_id, &gvt_cred_def),
+        (&emp_cred_def_id, &emp_cred_def),
+    ]);
+    let pres_request =
+        fixtures::create_multiple_issuer_presentation_request(&gvt_cred_def_id, &emp_cred_def_id);
+
+    // Prover selects the credentials for the request automatically
+    let candidates: Vec<CredentialCandidate> = prover_wallet
+        .credentials
+        .iter()
+        .map(|cred| CredentialCandidate::new(cred, None))
+        .collect();
+    let matches = prover::match_credentials(&pres_request, &candidates, &schemas, &cred_defs);
+
+    for referent in ["attr1_referent", "attr3_referent"] {
+        let found = matches.attribute_candidates(referent);
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].cred.cred_def_id, gvt_cred_def_id);
+    }
+    let found = matches.attribute_candidates("attr2_referent");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].cred.cred_def_id, emp_cred_def_id);
+    assert_eq!(matches.predicate_candidates("predicate1_referent").len(), 1);
+    assert!(matches.unmatched_attributes().is_empty());
+    assert!(matches.unmatched_predicates().is_empty());
+
+    let presentation = prover::create_presentation(
+        &pres_request,
+        matches.to_present_credentials(),
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+}
+
+#[test]
+fn anoncreds_demo_works_for_w3c_presentation() {
+    let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let (emp_schema, emp_schema_id, emp_cred_def, emp_cred_def_id) =
+        fixtures::issue_credential("EMP", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema), (&emp_schema_id, &emp_schema)]);
+    let cred_defs = HashMap::from([
+        (&gvt_cred_def_id, &gvt_cred_def),
+        (&emp_cred_def_id, &emp_cred_def),
+    ]);
+    let pres_request =
+        fixtures::create_multiple_issuer_presentation_request(&gvt_cred_def_id, &emp_cred_def_id);
+
+    let mut present = PresentCredentials::default();
+    let mut gvt_cred = present.add_credential(&prover_wallet.credentials[0], None, None);
+    gvt_cred.add_requested_attribute("attr1_referent", true);
+    gvt_cred.add_requested_attribute("attr3_referent", true);
+    gvt_cred.add_requested_predicate("predicate1_referent");
+
+    let mut emp_cred = present.add_credential(&prover_wallet.credentials[1], None, None);
+    emp_cred.add_requested_attribute("attr2_referent", true);
+
+    // Prover sends the presentation as a W3C Verifiable Presentation
+    let w3c_presentation = prover::create_w3c_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    assert_eq!(w3c_presentation.verifiable_credential.len(), 2);
+    let w3c_presentation_json =
+        serde_json::to_string(&w3c_presentation).expect("Error serializing presentation");
+
+    let w3c_presentation: W3CPresentation =
+        serde_json::from_str(&w3c_presentation_json).expect("Error deserializing presentation");
+    for credential in &w3c_presentation.verifiable_credential {
+        if credential.credential_schema.definition == gvt_cred_def_id {
+            assert_eq!(credential.credential_subject["name"], "Alex");
+            assert_eq!(credential.credential_subject["height"], "175");
+            assert!(!credential.credential_subject.contains_key("age"));
+        } else {
+            assert_eq!(credential.credential_subject["role"], "Developer");
+        }
+    }
+    let valid = verifier::verify_w3c_presentation(
+        &w3c_presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+
+    // A presentation for another request is not valid
+    let mut other_request = serde_json::to_value(&pres_request).unwrap();
+    other_request["nonce"] =
+        json!(verifier::generate_nonce().expect("Error generating presentation request nonce"));
+    let other_request: PresentationRequest =
+        serde_json::from_value(other_request).expect("Error creating proof request");
+    let valid = verifier::verify_w3c_presentation(
+        &w3c_presentation,
+        &other_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(!valid);
+
+    // The revealed attributes cannot be altered
+    let mut altered_presentation = w3c_presentation;
+    altered_presentation.verifiable_credential[0]
+        .credential_subject
+        .insert("name".to_string(), "Mallory".to_string());
+    assert!(verifier::verify_w3c_presentation(
+        &altered_presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .is_err());
+
+    // Nor can the issuer of a credential
+    let mut altered_presentation: W3CPresentation =
+        serde_json::from_str(&w3c_presentation_json).expect("Error deserializing presentation");
+    altered_presentation.verifiable_credential[0].issuer =
+        IssuerId::new_unchecked("mallory:id/path=bar");
+    assert!(verifier::verify_w3c_presentation(
+        &altered_presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .is_err());
+    assert!(w3c::presentation_from_w3c(&altered_presentation, &pres_request, &cred_defs).is_err());
+}
+
 #[test]
 fn anoncreds_demo_proof_does_not_verify_with_wrong_attr_and_predicates() {
     // Create Prover pseudo wallet and link secret
@@ -724,24 +1592,242 @@ fn anoncreds_demo_proof_does_not_verify_with_wrong_attr_and_predicates() {
 }
 
 #[test]
-fn anoncreds_demo_works_for_requested_attribute_in_upper_case() {
-    // Create Prover pseudo wallet and link secret
+fn anoncreds_demo_verification_report_for_missing_referent() {
     let mut prover_wallet = ProverWallet::default();
+    let (gvt_schema, gvt_schema_id, gvt_cred_def, gvt_cred_def_id) =
+        fixtures::issue_credential("GVT", &mut prover_wallet);
+    let schemas = HashMap::from([(&gvt_schema_id, &gvt_schema)]);
+    let cred_defs = HashMap::from([(&gvt_cred_def_id, &gvt_cred_def)]);
+    let pres_request = fixtures::create_presentation_request();
 
-    // Create schema
-    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
-
-    // Create credential definition
-    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
-        fixtures::create_cred_def(&gvt_schema, false);
-
-    // Issuer creates a Credential Offer
-    let cred_offer = issuer::create_credential_offer(
-        gvt_schema_id,
-        gvt_cred_def_id,
-        &gvt_cred_key_correctness_proof,
+    let mut present = PresentCredentials::default();
+    {
+        // We do not add `attr2_referent` here
+        let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+        cred1.add_requested_attribute("attr1_referent", true);
+        cred1.add_requested_attribute("attr4_referent", true);
+        cred1.add_requested_predicate("predicate1_referent");
+    }
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        Some(HashMap::from([(
+            "attr3_referent".to_string(),
+            "8-800-300".to_string(),
+        )])),
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
     )
-    .expect("Error creating credential offer");
+    .expect("Error creating presentation");
+
+    // The report points at the referent missing from the presentation
+    let report = verifier::verify_presentation_detailed(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+
+    assert!(!report.verified);
+    assert_eq!(
+        report.requested_attributes["attr2_referent"]
+            .status
+            .failure()
+            .map(|f| f.code),
+        Some(verifier::FailureCode::MissingReferent)
+    );
+    assert_eq!(
+        report.requested_attributes["attr3_referent"].kind,
+        verifier::ReferentKind::SelfAttested
+    );
+    assert!(!report.requested_attributes["attr1_referent"]
+        .status
+        .is_failed());
+    assert!(!report.proof.is_passed());
+}
+
+#[test]
+fn anoncreds_demo_verification_report_for_unsatisfied_restrictions() {
+    // Create Prover pseudo wallet and link secret
+    let mut prover_wallet = ProverWallet::default();
+
+    // Create schema
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+
+    // Create credential definition
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, false);
+
+    // Issuer creates a Credential Offer
+    let cred_offer = issuer::create_credential_offer(
+        gvt_schema_id,
+        gvt_cred_def_id,
+        &gvt_cred_key_correctness_proof,
+    )
+    .expect("Error creating credential offer");
+
+    // Prover creates a Credential Request
+    let (cred_request, cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &gvt_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    // Issuer creates a credential
+    let cred_values = fixtures::credential_values("GVT");
+    let issue_cred = issuer::create_credential(
+        &gvt_cred_def,
+        &gvt_cred_def_priv,
+        &cred_offer,
+        &cred_request,
+        cred_values.into(),
+        None,
+        None,
+        None,
+    )
+    .expect("Error creating credential");
+
+    // Prover receives the credential and processes it
+    let mut recv_cred = issue_cred;
+    prover::process_credential(
+        &mut recv_cred,
+        &cred_request_metadata,
+        &prover_wallet.link_secret,
+        &gvt_cred_def,
+        None,
+    )
+    .expect("Error processing credential");
+    prover_wallet.credentials.push(recv_cred);
+
+    // Verifier creates a presentation request, the predicate is restricted to another issuer
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    let pres_request = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"name",
+                "restrictions": {"cred_def_id": gvt_cred_def_id}
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{
+                "name":"age",
+                "p_type":">=",
+                "p_value":18,
+                "restrictions": {"issuer_id": fixtures::EMP_ISSUER_ID}
+            }
+        }
+    }))
+    .expect("Error creating proof request");
+
+    // Prover creates presentation
+    let mut present = PresentCredentials::default();
+    {
+        let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+        cred1.add_requested_attribute("attr1_referent", true);
+        cred1.add_requested_predicate("predicate1_referent");
+    }
+
+    let mut schemas = HashMap::new();
+    let gvt_schema_id = SchemaId::new_unchecked(gvt_schema_id);
+    schemas.insert(&gvt_schema_id, &gvt_schema);
+
+    let mut cred_defs = HashMap::new();
+    let gvt_cred_def_id = CredentialDefinitionId::new_unchecked(gvt_cred_def_id);
+    cred_defs.insert(&gvt_cred_def_id, &gvt_cred_def);
+
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    );
+    assert!(valid.is_err());
+
+    let report = verifier::verify_presentation_detailed(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+
+    // The cryptographic proof is valid, only the restriction of the predicate is not met
+    assert!(!report.verified);
+    assert!(report.proof.is_passed());
+    assert!(report.requested_attributes["attr1_referent"]
+        .status
+        .is_passed());
+    assert_eq!(
+        report.requested_predicates["predicate1_referent"]
+            .status
+            .failure()
+            .map(|f| f.code),
+        Some(verifier::FailureCode::RestrictionNotSatisfied)
+    );
+
+    let sub_proof = &report.sub_proofs[0];
+    assert!(sub_proof.cl_proof.is_passed());
+    assert_eq!(
+        sub_proof.restrictions.failure().map(|f| f.code),
+        Some(verifier::FailureCode::RestrictionNotSatisfied)
+    );
+    assert_eq!(sub_proof.non_revocation, verifier::CheckStatus::Skipped);
+    assert_eq!(
+        report.all_failures().map(|f| f.code).collect::<Vec<_>>(),
+        vec![
+            verifier::FailureCode::RestrictionNotSatisfied,
+            verifier::FailureCode::RestrictionNotSatisfied
+        ]
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_for_requested_attribute_in_upper_case() {
+    // Create Prover pseudo wallet and link secret
+    let mut prover_wallet = ProverWallet::default();
+
+    // Create schema
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+
+    // Create credential definition
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, false);
+
+    // Issuer creates a Credential Offer
+    let cred_offer = issuer::create_credential_offer(
+        gvt_schema_id,
+        gvt_cred_def_id,
+        &gvt_cred_key_correctness_proof,
+    )
+    .expect("Error creating credential offer");
 
     // Prover creates a Credential Request
     let (cred_request, cred_request_metadata) = prover::create_credential_request(
@@ -1491,150 +2577,6 @@ fn anoncreds_works_for_multiple_requested_predicates_from_one_credential() {
     wallet::close_and_delete_wallet(prover_wallet_handle, &prover_wallet_config).unwrap();
 }
 
-// #[test]
-// fn anoncreds_works_for_cred_def_rotation() {
-//     Setup::empty();
-
-//     //1. Create Issuer wallet, gets wallet handle
-//     let (issuer_wallet_handle, issuer_wallet_config) =
-//         wallet::create_and_open_default_wallet("anoncreds_works_for_cred_def_rotation_issuer")
-//             .unwrap();
-
-//     //2. Create Prover wallet, gets wallet handle
-//     let (prover_wallet_handle, prover_wallet_config) =
-//         wallet::create_and_open_default_wallet("anoncreds_works_for_cred_def_rotation_prover")
-//             .unwrap();
-
-//     //3. Issuer creates Schema and Credential Definition
-//     let (schema_id, schema_json, cred_def_id, cred_def_json) =
-//         anoncreds::multi_steps_issuer_preparation(
-//             issuer_wallet_handle,
-//             ISSUER_DID,
-//             GVT_SCHEMA_NAME,
-//             GVT_SCHEMA_ATTRIBUTES,
-//         );
-
-//     //4. Prover creates Link Secret
-//     anoncreds::prover_create_link_secret(prover_wallet_handle, COMMON_LINK_SECRET).unwrap();
-
-//     //5. Issuance credential for Prover
-//     anoncreds::multi_steps_create_credential(
-//         COMMON_LINK_SECRET,
-//         prover_wallet_handle,
-//         issuer_wallet_handle,
-//         CREDENTIAL1_ID,
-//         &anoncreds::gvt_credential_values_json(),
-//         &cred_def_id,
-//         &cred_def_json,
-//     );
-
-//     //6. Proof request
-//     let nonce = anoncreds::generate_nonce().unwrap();
-//     let proof_req_json = json!({
-//         "nonce": nonce,
-//         "name":"proof_req_1",
-//         "version":"0.1",
-//         "requested_attributes":{
-//             "attr1_referent":{
-//                 "name":"name"
-//             }
-//         },
-//         "requested_predicates":{
-//             "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
-//         }
-//     })
-//     .to_string();
-
-//     //7. Prover gets Credentials for Proof Request
-//     let credentials_json =
-//         anoncreds::prover_get_credentials_for_proof_req(prover_wallet_handle, &proof_req_json)
-//             .unwrap();
-//     let credential =
-//         anoncreds::get_credential_for_attr_referent(&credentials_json, "attr1_referent");
-
-//     //8. Prover creates Proof
-//     let requested_credentials_json = json!({
-//         "self_attested_attributes": {},
-//         "requested_attributes": {
-//             "attr1_referent": { "cred_id": credential.referent, "revealed":true }
-//         },
-//         "requested_predicates": {
-//             "predicate1_referent": { "cred_id": credential.referent }
-//         },
-//     })
-//     .to_string();
-
-//     let schemas_json =
-//         json!({schema_id.as_str(): serde_json::from_str::<Schema>(&schema_json).unwrap()})
-//             .to_string();
-//     let cred_defs_json = json!({cred_def_id.as_str(): serde_json::from_str::<CredentialDefinition>(&cred_def_json).unwrap()}).to_string();
-//     let rev_states_json = json!({}).to_string();
-
-//     let proof_json = anoncreds::prover_create_proof(
-//         prover_wallet_handle,
-//         &proof_req_json,
-//         &requested_credentials_json,
-//         COMMON_LINK_SECRET,
-//         &schemas_json,
-//         &cred_defs_json,
-//         &rev_states_json,
-//     )
-//     .unwrap();
-
-//     //9. Verifier verifies proof
-//     let rev_reg_defs_json = json!({}).to_string();
-//     let rev_regs_json = json!({}).to_string();
-
-//     let valid = anoncreds::verifier_verify_proof(
-//         &proof_req_json,
-//         &proof_json,
-//         &schemas_json,
-//         &cred_defs_json,
-//         &rev_reg_defs_json,
-//         &rev_regs_json,
-//     )
-//     .unwrap();
-//     assert!(valid);
-
-//     //10. Issuer rotate cred def
-//     let new_cred_def_json =
-//         anoncreds::issuer_rotate_credential_def_start(issuer_wallet_handle, &cred_def_id, None)
-//             .unwrap();
-//     anoncreds::issuer_rotate_credential_def_apply(issuer_wallet_handle, &cred_def_id).unwrap();
-
-//     //11. Prover generate proof wit rotated cred def but old credential
-//     let schemas_json =
-//         json!({schema_id.as_str(): serde_json::from_str::<Schema>(&schema_json).unwrap()})
-//             .to_string();
-//     let cred_defs_json = json!({cred_def_id.as_str(): serde_json::from_str::<CredentialDefinition>(&new_cred_def_json).unwrap()}).to_string();
-
-//     let proof_json = anoncreds::prover_create_proof(
-//         prover_wallet_handle,
-//         &proof_req_json,
-//         &requested_credentials_json,
-//         COMMON_LINK_SECRET,
-//         &schemas_json,
-//         &cred_defs_json,
-//         &rev_states_json,
-//     )
-//     .unwrap();
-
-//     //12. Verifier verifies proof
-//     let valid = anoncreds::verifier_verify_proof(
-//         &proof_req_json,
-//         &proof_json,
-//         &schemas_json,
-//         &cred_defs_json,
-//         &rev_reg_defs_json,
-//         &rev_regs_json,
-//     )
-//     .unwrap();
-//     assert!(!valid);
-
-//     wallet::close_and_delete_wallet(issuer_wallet_handle, &issuer_wallet_config).unwrap();
-//     wallet::close_and_delete_wallet(prover_wallet_handle, &prover_wallet_config).unwrap();
-// }
-
 #[test]
 fn anoncreds_works_for_different_predicate_types() {
     Setup::empty();
@@ -2850,3 +3792,1673 @@ fn anoncreds_works_for_restrictions_as_empty_array() {
     wallet::close_and_delete_wallet(prover_wallet_handle, &prover_wallet_config).unwrap();
 }
 */
+
+#[test]
+fn anoncreds_demo_works_for_date_predicates() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // Issuer creates a driver license with a birth date and an expiry date
+    let (dl_schema, dl_schema_id) = fixtures::create_schema("DL");
+    let ((dl_cred_def, dl_cred_def_priv, dl_cred_key_correctness_proof), dl_cred_def_id) =
+        fixtures::create_cred_def(&dl_schema, false);
+
+    let cred_offer = issuer::create_credential_offer(
+        dl_schema_id,
+        dl_cred_def_id,
+        &dl_cred_key_correctness_proof,
+    )
+    .expect("Error creating credential offer");
+
+    let (cred_request, cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &dl_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    let issue_cred = issuer::create_credential(
+        &dl_cred_def,
+        &dl_cred_def_priv,
+        &cred_offer,
+        &cred_request,
+        fixtures::credential_values("DL").into(),
+        None,
+        None,
+        None,
+    )
+    .expect("Error creating credential");
+
+    let mut recv_cred = issue_cred;
+    prover::process_credential(
+        &mut recv_cred,
+        &cred_request_metadata,
+        &prover_wallet.link_secret,
+        &dl_cred_def,
+        None,
+    )
+    .expect("Error processing credential");
+    prover_wallet.credentials.push(recv_cred);
+
+    let mut schemas = HashMap::new();
+    let dl_schema_id = SchemaId::new_unchecked(dl_schema_id);
+    schemas.insert(&dl_schema_id, &dl_schema);
+
+    let mut cred_defs = HashMap::new();
+    let dl_cred_def_id = CredentialDefinitionId::new_unchecked(dl_cred_def_id);
+    cred_defs.insert(&dl_cred_def_id, &dl_cred_def);
+
+    // Verifier checks the holder is an adult with a valid license on a given day
+    let today: Date = "2026-10-18".parse().expect("Error parsing date");
+    let create_request = |age: u16| -> PresentationRequest {
+        serde_json::from_value(json!({
+            "nonce": verifier::generate_nonce().expect("Error generating presentation request nonce"),
+            "name": "pres_req_1",
+            "version": "0.1",
+            "requested_attributes": {
+                "attr1_referent": { "name": "name" }
+            },
+            "requested_predicates": {
+                "predicate1_referent": PredicateInfo::age_at_least("birthdate", age, today)
+                    .expect("Error creating predicate"),
+                "predicate2_referent": PredicateInfo::expires_after("expiry", today),
+                "predicate3_referent": PredicateInfo::born_before("birthdate", today)
+            }
+        }))
+        .expect("Error creating proof request")
+    };
+    let present = || {
+        let mut present = PresentCredentials::default();
+        {
+            let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+            cred1.add_requested_attribute("attr1_referent", true);
+            cred1.add_requested_predicate("predicate1_referent");
+            cred1.add_requested_predicate("predicate2_referent");
+            cred1.add_requested_predicate("predicate3_referent");
+        }
+        present
+    };
+
+    let pres_request = create_request(18);
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present(),
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+
+    // The holder is not old enough for the predicate to be satisfied
+    assert!(prover::create_presentation(
+        &create_request(30),
+        present(),
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .is_err());
+
+    // Verifier decodes the revealed dates
+    let pres_request = serde_json::from_value(json!({
+        "nonce": verifier::generate_nonce().expect("Error generating presentation request nonce"),
+        "name": "pres_req_2",
+        "version": "0.1",
+        "requested_attributes": {
+            "attr1_referent": { "name": "birthdate" },
+            "attr2_referent": { "names": ["name", "expiry"] }
+        }
+    }))
+    .expect("Error creating proof request");
+    let mut present = PresentCredentials::default();
+    {
+        let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+        cred1.add_requested_attribute("attr1_referent", true);
+        cred1.add_requested_attribute("attr2_referent", true);
+    }
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+    assert_eq!(
+        verifier::revealed_date(&presentation, "attr1_referent")
+            .expect("Error decoding revealed date"),
+        Date::new(2000, 2, 29).unwrap()
+    );
+    assert_eq!(
+        verifier::revealed_group_date(&presentation, "attr2_referent", "expiry")
+            .expect("Error decoding revealed date"),
+        Date::new(2030, 1, 31).unwrap()
+    );
+    assert!(verifier::revealed_group_date(&presentation, "attr2_referent", "name").is_err());
+
+    // Verifier knowing the date encoding also checks the revealed raw values
+    let context = verifier::VerifierContext::new(schemas.clone(), cred_defs.clone(), None, None)
+        .expect("Error creating verifier context")
+        .with_attribute_encoder("birthdate", DateEncoder)
+        .with_attribute_encoder("expiry", DateEncoder);
+    let valid = context
+        .verify_presentation(&presentation, &pres_request, None)
+        .expect("Error verifying presentation");
+    assert!(valid);
+
+    let mut tampered = presentation;
+    tampered
+        .requested_proof
+        .revealed_attrs
+        .get_mut("attr1_referent")
+        .unwrap()
+        .raw = "1990-02-28".to_string();
+    assert!(context
+        .verify_presentation(&tampered, &pres_request, None)
+        .is_err());
+}
+
+#[test]
+fn anoncreds_demo_works_for_equal_attributes_across_issuers() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // Three issuers each issue a credential holding a name
+    let issued = ["GVT", "EMP", "DL"]
+        .iter()
+        .map(|name| {
+            let (schema, schema_id) = fixtures::create_schema(name);
+            let ((cred_def, cred_def_priv, cred_key_correctness_proof), cred_def_id) =
+                fixtures::create_cred_def(&schema, false);
+            let cred_def_id = CredentialDefinitionId::new_unchecked(cred_def_id);
+            let cred_offer = issuer::create_credential_offer(
+                schema_id,
+                cred_def_id.to_string(),
+                &cred_key_correctness_proof,
+            )
+            .expect("Error creating credential offer");
+            let (cred_request, cred_request_metadata) = prover::create_credential_request(
+                Some("entropy"),
+                None,
+                &cred_def,
+                &prover_wallet.link_secret,
+                "default",
+                &cred_offer,
+            )
+            .expect("Error creating credential request");
+            let mut recv_cred = issuer::create_credential(
+                &cred_def,
+                &cred_def_priv,
+                &cred_offer,
+                &cred_request,
+                fixtures::credential_values(name).into(),
+                None,
+                None,
+                None,
+            )
+            .expect("Error creating credential");
+            prover::process_credential(
+                &mut recv_cred,
+                &cred_request_metadata,
+                &prover_wallet.link_secret,
+                &cred_def,
+                None,
+            )
+            .expect("Error processing credential");
+            prover_wallet.credentials.push(recv_cred);
+            (
+                schema,
+                SchemaId::new_unchecked(schema_id),
+                cred_def,
+                cred_def_id,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let schemas = issued
+        .iter()
+        .map(|(schema, schema_id, _, _)| (schema_id, schema))
+        .collect::<HashMap<_, _>>();
+    let cred_defs = issued
+        .iter()
+        .map(|(_, _, cred_def, cred_def_id)| (cred_def_id, cred_def))
+        .collect::<HashMap<_, _>>();
+
+    // Verifier asks for the sex from one credential and the expiry from another, both
+    // issued to the same hidden name
+    let pres_request: PresentationRequest = serde_json::from_value(json!({
+        "nonce": verifier::generate_nonce().expect("Error generating presentation request nonce"),
+        "name": "pres_req_1",
+        "version": "0.1",
+        "requested_attributes": {
+            "attr1_referent": { "name": "sex" },
+            "attr2_referent": { "name": "expiry" }
+        },
+        "equal_attributes": [{ "name": "name", "referents": ["attr1_referent", "attr2_referent"] }]
+    }))
+    .expect("Error creating proof request");
+
+    // GVT and DL credentials were issued to "Alex"
+    let mut present = PresentCredentials::default();
+    present
+        .add_credential(&prover_wallet.credentials[0], None, None)
+        .add_requested_attribute("attr1_referent", true);
+    present
+        .add_credential(&prover_wallet.credentials[2], None, None)
+        .add_requested_attribute("attr2_referent", true);
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+    assert!(!presentation
+        .requested_proof
+        .revealed_attrs
+        .values()
+        .any(|attr| attr.raw == "Alex"));
+
+    // EMP credential was issued to "John": the proof is created but does not verify
+    let mut pres_request_emp = serde_json::to_value(&pres_request).unwrap();
+    pres_request_emp["requested_attributes"]["attr2_referent"] = json!({ "name": "role" });
+    let pres_request_emp: PresentationRequest =
+        serde_json::from_value(pres_request_emp).expect("Error creating proof request");
+    let mut present = PresentCredentials::default();
+    present
+        .add_credential(&prover_wallet.credentials[0], None, None)
+        .add_requested_attribute("attr1_referent", true);
+    present
+        .add_credential(&prover_wallet.credentials[1], None, None)
+        .add_requested_attribute("attr2_referent", true);
+    let presentation = prover::create_presentation(
+        &pres_request_emp,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    assert!(verifier::verify_presentation(
+        &presentation,
+        &pres_request_emp,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .is_err());
+
+    // An equal attribute cannot be revealed
+    let mut pres_request_reveal = serde_json::to_value(&pres_request).unwrap();
+    pres_request_reveal["requested_attributes"]["attr2_referent"] = json!({ "name": "name" });
+    let pres_request_reveal: PresentationRequest =
+        serde_json::from_value(pres_request_reveal).expect("Error creating proof request");
+    let mut present = PresentCredentials::default();
+    present
+        .add_credential(&prover_wallet.credentials[0], None, None)
+        .add_requested_attribute("attr1_referent", true);
+    present
+        .add_credential(&prover_wallet.credentials[2], None, None)
+        .add_requested_attribute("attr2_referent", true);
+    assert!(prover::create_presentation(
+        &pres_request_reveal,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .is_err());
+}
+
+#[test]
+fn anoncreds_demo_works_for_equal_attributes_with_credentials_outside_the_group() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // GVT and DL credentials are issued to "Alex", EMP to "John", and a pet license without
+    // any name
+    let pet_schema = issuer::create_schema(
+        "pet license",
+        "1.0",
+        "did:example:pet",
+        vec!["species".to_string()].into(),
+    )
+    .expect("Error creating schema");
+    let fixture_schemas = ["GVT", "EMP", "DL"]
+        .iter()
+        .map(|name| {
+            let (schema, schema_id) = fixtures::create_schema(name);
+            (schema, schema_id, Some(*name))
+        })
+        .chain(std::iter::once((
+            pet_schema,
+            "did:example:pet/pet-license",
+            None,
+        )))
+        .collect::<Vec<_>>();
+    let issued = fixture_schemas
+        .into_iter()
+        .map(|(schema, schema_id, fixture)| {
+            let ((cred_def, cred_def_priv, cred_key_correctness_proof), cred_def_id) =
+                if fixture.is_some() {
+                    fixtures::create_cred_def(&schema, false)
+                } else {
+                    (
+                        issuer::create_credential_definition(
+                            schema_id,
+                            &schema,
+                            "did:example:pet",
+                            "default",
+                            anoncreds::types::SignatureType::CL,
+                            anoncreds::types::CredentialDefinitionConfig {
+                                support_revocation: false,
+                            },
+                        )
+                        .expect("Error creating credential definition"),
+                        "did:example:pet/pet-license/default",
+                    )
+                };
+            let cred_def_id = CredentialDefinitionId::new_unchecked(cred_def_id);
+            let cred_offer = issuer::create_credential_offer(
+                schema_id,
+                cred_def_id.to_string(),
+                &cred_key_correctness_proof,
+            )
+            .expect("Error creating credential offer");
+            let (cred_request, cred_request_metadata) = prover::create_credential_request(
+                Some("entropy"),
+                None,
+                &cred_def,
+                &prover_wallet.link_secret,
+                "default",
+                &cred_offer,
+            )
+            .expect("Error creating credential request");
+            let cred_values = fixture.map_or_else(
+                || {
+                    let mut pet_cred_values = MakeCredentialValues::default();
+                    pet_cred_values
+                        .add_raw("species", "cat")
+                        .expect("Error encoding attribute");
+                    pet_cred_values
+                },
+                fixtures::credential_values,
+            );
+            let mut recv_cred = issuer::create_credential(
+                &cred_def,
+                &cred_def_priv,
+                &cred_offer,
+                &cred_request,
+                cred_values.into(),
+                None,
+                None,
+                None,
+            )
+            .expect("Error creating credential");
+            prover::process_credential(
+                &mut recv_cred,
+                &cred_request_metadata,
+                &prover_wallet.link_secret,
+                &cred_def,
+                None,
+            )
+            .expect("Error processing credential");
+            prover_wallet.credentials.push(recv_cred);
+            (
+                schema,
+                SchemaId::new_unchecked(schema_id),
+                cred_def,
+                cred_def_id,
+            )
+        })
+        .collect::<Vec<_>>();
+
+    let schemas = issued
+        .iter()
+        .map(|(schema, schema_id, _, _)| (schema_id, schema))
+        .collect::<HashMap<_, _>>();
+    let cred_defs = issued
+        .iter()
+        .map(|(_, _, cred_def, cred_def_id)| (cred_def_id, cred_def))
+        .collect::<HashMap<_, _>>();
+
+    // Verifier asks for the names of the GVT and DL credentials to be equal, and for a role and
+    // a species from other credentials
+    let pres_request: PresentationRequest = serde_json::from_value(json!({
+        "nonce": verifier::generate_nonce().expect("Error generating presentation request nonce"),
+        "name": "pres_req_1",
+        "version": "0.1",
+        "requested_attributes": {
+            "attr1_referent": { "name": "sex" },
+            "attr2_referent": { "name": "expiry" },
+            "attr3_referent": { "name": "role" },
+            "attr4_referent": { "name": "species" }
+        },
+        "equal_attributes": [{ "name": "name", "referents": ["attr1_referent", "attr2_referent"] }]
+    }))
+    .expect("Error creating proof request");
+
+    let mut present = PresentCredentials::default();
+    for (cred_idx, referent) in [
+        (0, "attr1_referent"),
+        (1, "attr3_referent"),
+        (2, "attr2_referent"),
+        (3, "attr4_referent"),
+    ] {
+        present
+            .add_credential(&prover_wallet.credentials[cred_idx], None, None)
+            .add_requested_attribute(referent, true);
+    }
+    let presentation = prover::create_presentation(
+        &pres_request,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+    assert_eq!(
+        presentation.requested_proof.revealed_attrs["attr3_referent"].raw,
+        "Developer"
+    );
+    assert_eq!(
+        presentation.requested_proof.revealed_attrs["attr4_referent"].raw,
+        "cat"
+    );
+
+    // A credential lacking the equal attribute cannot be presented for a referent of its group
+    let mut pres_request_pet = serde_json::to_value(&pres_request).unwrap();
+    pres_request_pet["equal_attributes"][0]["referents"] =
+        json!(["attr1_referent", "attr4_referent"]);
+    let pres_request_pet: PresentationRequest =
+        serde_json::from_value(pres_request_pet).expect("Error creating proof request");
+    let mut present = PresentCredentials::default();
+    for (cred_idx, referent) in [
+        (0, "attr1_referent"),
+        (1, "attr3_referent"),
+        (2, "attr2_referent"),
+        (3, "attr4_referent"),
+    ] {
+        present
+            .add_credential(&prover_wallet.credentials[cred_idx], None, None)
+            .add_requested_attribute(referent, true);
+    }
+    assert!(prover::create_presentation(
+        &pres_request_pet,
+        present,
+        None,
+        &prover_wallet.link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .is_err());
+}
+
+#[test]
+fn anoncreds_demo_works_for_range_and_not_equal_predicates() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // Issuer creates a GVT credential, with age 28 and height 175
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, false);
+
+    let cred_offer = issuer::create_credential_offer(
+        gvt_schema_id,
+        gvt_cred_def_id,
+        &gvt_cred_key_correctness_proof,
+    )
+    .expect("Error creating credential offer");
+
+    let (cred_request, cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &gvt_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    let mut recv_cred = issuer::create_credential(
+        &gvt_cred_def,
+        &gvt_cred_def_priv,
+        &cred_offer,
+        &cred_request,
+        fixtures::credential_values("GVT").into(),
+        None,
+        None,
+        None,
+    )
+    .expect("Error creating credential");
+    prover::process_credential(
+        &mut recv_cred,
+        &cred_request_metadata,
+        &prover_wallet.link_secret,
+        &gvt_cred_def,
+        None,
+    )
+    .expect("Error processing credential");
+    prover_wallet.credentials.push(recv_cred);
+
+    let mut schemas = HashMap::new();
+    let gvt_schema_id = SchemaId::new_unchecked(gvt_schema_id);
+    schemas.insert(&gvt_schema_id, &gvt_schema);
+
+    let mut cred_defs = HashMap::new();
+    let gvt_cred_def_id = CredentialDefinitionId::new_unchecked(gvt_cred_def_id);
+    cred_defs.insert(&gvt_cred_def_id, &gvt_cred_def);
+
+    let create_request = |age_range: (i32, i32), age_excluded: i32| -> PresentationRequest {
+        serde_json::from_value(json!({
+            "nonce": verifier::generate_nonce().expect("Error generating presentation request nonce"),
+            "name": "pres_req_1",
+            "version": "0.1",
+            "requested_predicates": {
+                "predicate1_referent": PredicateInfo::range("age", age_range.0, age_range.1),
+                "predicate2_referent": PredicateInfo::not_equal("age", age_excluded),
+                "predicate3_referent": {
+                    "name": "height",
+                    "p_type": "!=",
+                    "p_value": 170
+                }
+            }
+        }))
+        .expect("Error creating proof request")
+    };
+    let create_presentation = |pres_request: &PresentationRequest| {
+        let mut present = PresentCredentials::default();
+        {
+            let mut cred1 = present.add_credential(&prover_wallet.credentials[0], None, None);
+            cred1.add_requested_predicate("predicate1_referent");
+            cred1.add_requested_predicate("predicate2_referent");
+            cred1.add_requested_predicate("predicate3_referent");
+        }
+        prover::create_presentation(
+            pres_request,
+            present,
+            None,
+            &prover_wallet.link_secret,
+            &schemas,
+            &cred_defs,
+        )
+    };
+    let verify = |presentation: &Presentation, pres_request: &PresentationRequest| {
+        verifier::verify_presentation(
+            presentation,
+            pres_request,
+            &schemas,
+            &cred_defs,
+            None,
+            None,
+            None,
+        )
+    };
+
+    let pres_request = create_request((18, 30), 30);
+    let presentation = create_presentation(&pres_request).expect("Error creating presentation");
+    assert!(verify(&presentation, &pres_request).expect("Error verifying presentation"));
+    assert_eq!(
+        presentation.requested_proof.not_equal_predicates["predicate2_referent"],
+        PredicateTypes::LT
+    );
+    assert_eq!(
+        presentation.requested_proof.not_equal_predicates["predicate3_referent"],
+        PredicateTypes::GT
+    );
+
+    // The comparison claimed for a not-equal predicate must be the one proven
+    for p_type in [PredicateTypes::GT, PredicateTypes::LE] {
+        let mut tampered: Presentation =
+            serde_json::from_value(serde_json::to_value(&presentation).unwrap()).unwrap();
+        tampered
+            .requested_proof
+            .not_equal_predicates
+            .insert("predicate2_referent".to_string(), p_type);
+        assert!(!matches!(verify(&tampered, &pres_request), Ok(true)));
+    }
+
+    // The age is out of the range, or equal to the excluded value
+    assert!(create_presentation(&create_request((30, 40), 30)).is_err());
+    assert!(create_presentation(&create_request((18, 30), 28)).is_err());
+}
+
+#[test]
+fn anoncreds_demo_works_for_cred_def_rotation() {
+    let mut prover_wallet = ProverWallet::default();
+
+    // Issuer creates a GVT credential definition, then rotates its keys
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((old_cred_def, old_cred_def_priv, old_cred_key_correctness_proof), old_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, false);
+    let (new_cred_def, new_cred_def_priv, new_cred_key_correctness_proof) =
+        issuer::rotate_credential_definition(old_cred_def_id, &old_cred_def, &gvt_schema)
+            .expect("Error rotating credential definition");
+    let new_cred_def_id = "creddef:government:v2";
+    assert_eq!(new_cred_def.tag, format!("{}-v2", old_cred_def.tag));
+    assert_eq!(new_cred_def.schema_id, old_cred_def.schema_id);
+
+    // Prover receives a credential issued before the rotation, and another one after it
+    for (cred_def, cred_def_priv, cred_key_correctness_proof, cred_def_id) in [
+        (
+            &old_cred_def,
+            &old_cred_def_priv,
+            &old_cred_key_correctness_proof,
+            old_cred_def_id,
+        ),
+        (
+            &new_cred_def,
+            &new_cred_def_priv,
+            &new_cred_key_correctness_proof,
+            new_cred_def_id,
+        ),
+    ] {
+        let cred_offer =
+            issuer::create_credential_offer(gvt_schema_id, cred_def_id, cred_key_correctness_proof)
+                .expect("Error creating credential offer");
+
+        let (cred_request, cred_request_metadata) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            cred_def,
+            &prover_wallet.link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+
+        let mut recv_cred = issuer::create_credential(
+            cred_def,
+            cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            fixtures::credential_values("GVT").into(),
+            None,
+            None,
+            None,
+        )
+        .expect("Error creating credential");
+        prover::process_credential(
+            &mut recv_cred,
+            &cred_request_metadata,
+            &prover_wallet.link_secret,
+            cred_def,
+            None,
+        )
+        .expect("Error processing credential");
+        prover_wallet.credentials.push(recv_cred);
+    }
+    let (old_credential, new_credential) =
+        (&prover_wallet.credentials[0], &prover_wallet.credentials[1]);
+
+    let mut schemas = HashMap::new();
+    let gvt_schema_id = SchemaId::new_unchecked(gvt_schema_id);
+    schemas.insert(&gvt_schema_id, &gvt_schema);
+
+    let mut cred_defs = HashMap::new();
+    let old_cred_def_id = CredentialDefinitionId::new_unchecked(old_cred_def_id);
+    let new_cred_def_id = CredentialDefinitionId::new_unchecked(new_cred_def_id);
+    cred_defs.insert(&old_cred_def_id, &old_cred_def);
+    cred_defs.insert(&new_cred_def_id, &new_cred_def);
+
+    let chain = verifier::credential_definition_chain(&new_cred_def_id, &cred_defs)
+        .expect("Error resolving rotation chain");
+    assert_eq!(
+        chain,
+        vec![new_cred_def_id.clone(), old_cred_def_id.clone()]
+    );
+
+    // The chain stops at credential definitions which are not known
+    let known_cred_defs = HashMap::from([(&new_cred_def_id, &new_cred_def)]);
+    assert_eq!(
+        verifier::credential_definition_chain(&new_cred_def_id, &known_cred_defs)
+            .expect("Error resolving rotation chain"),
+        vec![new_cred_def_id.clone()]
+    );
+
+    // Another issuer cannot claim to rotate the GVT credential definition
+    let (emp_schema, _) = fixtures::create_schema("EMP");
+    let ((emp_cred_def, _, _), _) = fixtures::create_cred_def(&emp_schema, false);
+    let (forged_cred_def, _, _) = issuer::rotate_credential_definition(
+        old_cred_def_id.to_string(),
+        &emp_cred_def,
+        &emp_schema,
+    )
+    .expect("Error rotating credential definition");
+    let forged_cred_def_id = CredentialDefinitionId::new_unchecked("creddef:employee:v2");
+    let mut forged_cred_defs = cred_defs.clone();
+    forged_cred_defs.insert(&forged_cred_def_id, &forged_cred_def);
+    assert!(verifier::credential_definition_chain(&forged_cred_def_id, &forged_cred_defs).is_err());
+
+    let create_request = |restrictions: Restrictions| {
+        PresentationRequestBuilder::new("pres_req_1", "0.1")
+            .requested_attribute("name", restrictions.clone())
+            .requested_predicate(PredicateInfo::range("age", 18, 150), restrictions)
+            .build()
+            .expect("Error creating proof request")
+    };
+    let create_presentation =
+        |pres_request: &PresentationRequest,
+         attr_credential: &Credential,
+         predicate_credential: &Credential,
+         cred_defs: &HashMap<&CredentialDefinitionId, &CredentialDefinition>| {
+            let mut present = PresentCredentials::default();
+            present
+                .add_credential(attr_credential, None, None)
+                .add_requested_attribute("attr1_referent", true);
+            present
+                .add_credential(predicate_credential, None, None)
+                .add_requested_predicate("predicate1_referent");
+            prover::create_presentation(
+                pres_request,
+                present,
+                None,
+                &prover_wallet.link_secret,
+                &schemas,
+                cred_defs,
+            )
+        };
+    let verify = |pres_request: &PresentationRequest,
+                  attr_credential: &Credential,
+                  predicate_credential: &Credential| {
+        create_presentation(
+            pres_request,
+            attr_credential,
+            predicate_credential,
+            &cred_defs,
+        )
+        .and_then(|presentation| {
+            verifier::verify_presentation(
+                &presentation,
+                pres_request,
+                &schemas,
+                &cred_defs,
+                None,
+                None,
+                None,
+            )
+        })
+    };
+
+    // Credentials of any credential definition of the rotation chain are accepted, alone or mixed
+    let pres_request = create_request(Restrictions::new().from_any_cred_def(&chain));
+    for (attr_credential, predicate_credential) in [
+        (old_credential, old_credential),
+        (new_credential, new_credential),
+        (old_credential, new_credential),
+        (new_credential, old_credential),
+    ] {
+        assert!(verify(&pres_request, attr_credential, predicate_credential)
+            .expect("Error verifying presentation"));
+    }
+
+    // Credentials issued before the rotation are rejected when only the new keys are accepted
+    let pres_request = create_request(Restrictions::new().from_cred_def(&new_cred_def_id));
+    assert!(verify(&pres_request, new_credential, new_credential)
+        .expect("Error verifying presentation"));
+    assert!(!matches!(
+        verify(&pres_request, old_credential, new_credential),
+        Ok(true)
+    ));
+
+    // A credential issued before the rotation is not valid under the new keys
+    let pres_request = create_request(Restrictions::new());
+    let mut rotated_cred_defs = HashMap::new();
+    rotated_cred_defs.insert(&old_cred_def_id, &new_cred_def);
+    let valid = create_presentation(
+        &pres_request,
+        old_credential,
+        old_credential,
+        &rotated_cred_defs,
+    )
+    .and_then(|presentation| {
+        verifier::verify_presentation(
+            &presentation,
+            &pres_request,
+            &schemas,
+            &rotated_cred_defs,
+            None,
+            None,
+            None,
+        )
+    });
+    assert!(!matches!(valid, Ok(true)));
+}
+
+#[test]
+fn anoncreds_demo_works_for_revocation_index_allocation() {
+    let mut prover_wallet = ProverWallet::default();
+
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, true);
+
+    // This will create a tails file locally in the .tmp dir
+    let tf_path = "../.tmp";
+    create_dir(tf_path)
+        .or_else(|e| -> Result<(), std::io::Error> {
+            println!(
+                "Tail file path creation error but test can still proceed {}",
+                e
+            );
+            Ok(())
+        })
+        .unwrap();
+    let mut tf = TailsFileWriter::new(Some(tf_path.to_owned()));
+
+    // Issuer creates revocation registries holding two credentials each
+    let mut create_registry = |tag: &str| {
+        let (rev_reg_def, rev_reg_def_priv) = issuer::create_revocation_registry_def(
+            &gvt_cred_def,
+            gvt_cred_def_id,
+            GVT_ISSUER_ID,
+            tag,
+            RegistryType::CL_ACCUM,
+            3,
+            &mut tf,
+        )
+        .expect("Error creating revocation registry definition");
+        let rev_reg_def_id = format!("revreg:government/{tag}");
+        let rev_status_list = issuer::create_revocation_status_list(
+            rev_reg_def_id.as_str(),
+            &rev_reg_def,
+            GVT_ISSUER_ID,
+            Some(10),
+            true,
+        )
+        .expect("Error creating revocation status list");
+        (
+            RevocationRegistryDefinitionId::new_unchecked(rev_reg_def_id),
+            (rev_reg_def, rev_reg_def_priv, rev_status_list),
+        )
+    };
+
+    let mut registries = HashMap::new();
+    let (rev_reg_def_id, registry) = create_registry("reg1");
+    let mut allocator = RevocationRegistryAllocator::new(rev_reg_def_id.to_string(), &registry.0)
+        .expect("Error creating revocation index allocator");
+    registries.insert(rev_reg_def_id, registry);
+
+    // Issuer issues credentials with the allocated indices, rolling over to new registries
+    let mut allocated = Vec::new();
+    for _ in 0..5 {
+        let (rev_reg_def_id, registry_idx) = allocator
+            .allocate_or_roll_over(|| {
+                let (rev_reg_def_id, registry) =
+                    create_registry(&format!("reg{}", registries.len() + 1));
+                let rev_reg_def = registry.0.clone();
+                registries.insert(rev_reg_def_id.clone(), registry);
+                Ok((rev_reg_def_id, rev_reg_def))
+            })
+            .expect("Error allocating revocation index");
+        let (rev_reg_def, rev_reg_def_priv, rev_status_list) = &registries[&rev_reg_def_id];
+
+        let cred_offer = issuer::create_credential_offer(
+            gvt_schema_id,
+            gvt_cred_def_id,
+            &gvt_cred_key_correctness_proof,
+        )
+        .expect("Error creating credential offer");
+        let (cred_request, cred_request_metadata) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            &gvt_cred_def,
+            &prover_wallet.link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+        let mut recv_cred = issuer::create_credential(
+            &gvt_cred_def,
+            &gvt_cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            fixtures::credential_values("GVT").into(),
+            Some(RevocationRegistryId::new_unchecked(
+                rev_reg_def_id.to_string(),
+            )),
+            Some(rev_status_list),
+            Some(CredentialRevocationConfig {
+                reg_def: rev_reg_def,
+                reg_def_private: rev_reg_def_priv,
+                registry_idx,
+                tails_reader: TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location),
+            }),
+        )
+        .expect("Error creating credential");
+        prover::process_credential(
+            &mut recv_cred,
+            &cred_request_metadata,
+            &prover_wallet.link_secret,
+            &gvt_cred_def,
+            Some(rev_reg_def),
+        )
+        .expect("Error processing credential");
+        prover_wallet.credentials.push(recv_cred);
+
+        allocated.push((rev_reg_def_id.to_string(), registry_idx));
+    }
+
+    assert_eq!(
+        allocated,
+        [
+            ("revreg:government/reg1".to_string(), 1),
+            ("revreg:government/reg1".to_string(), 2),
+            ("revreg:government/reg2".to_string(), 1),
+            ("revreg:government/reg2".to_string(), 2),
+            ("revreg:government/reg3".to_string(), 1),
+        ]
+    );
+    assert_eq!(
+        allocator.previous_rev_reg_def_ids(),
+        [
+            RevocationRegistryDefinitionId::new_unchecked("revreg:government/reg1"),
+            RevocationRegistryDefinitionId::new_unchecked("revreg:government/reg2"),
+        ]
+    );
+
+    // The allocator is persisted next to the revocation status list
+    let json = serde_json::to_string(&allocator).expect("Error serializing allocator");
+    let mut allocator: RevocationRegistryAllocator =
+        serde_json::from_str(&json).expect("Error deserializing allocator");
+    assert!(allocator.is_used(1));
+    assert_eq!(allocator.remaining(), 1);
+
+    // An index is never handed out twice, and a full registry is reported
+    assert!(allocator.mark_used(1).is_err());
+    assert_eq!(allocator.allocate().expect("Error allocating index"), 2);
+    assert_eq!(
+        allocator.allocate().unwrap_err().kind(),
+        ErrorKind::RevocationRegistryFull
+    );
+}
+
+#[test]
+fn anoncreds_demo_works_for_revocation_registry_set() {
+    let mut prover_wallet = ProverWallet::default();
+
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, true);
+
+    // This will create a tails file locally in the .tmp dir
+    let tf_path = "../.tmp";
+    create_dir(tf_path)
+        .or_else(|e| -> Result<(), std::io::Error> {
+            println!(
+                "Tail file path creation error but test can still proceed {}",
+                e
+            );
+            Ok(())
+        })
+        .unwrap();
+    let mut tf = TailsFileWriter::new(Some(tf_path.to_owned()));
+
+    // Issuer manages two revocation registries holding two credentials each, the first one
+    // issuing its credentials by default and the second one on demand
+    let mut rev_reg_set =
+        RevocationRegistrySet::new(gvt_cred_def_id).expect("Error creating registry set");
+    let mut initial_lists = HashMap::new();
+    for (tag, issuance_by_default) in [("reg1", true), ("reg2", false)] {
+        let (rev_reg_def, rev_reg_def_priv) = issuer::create_revocation_registry_def(
+            &gvt_cred_def,
+            gvt_cred_def_id,
+            GVT_ISSUER_ID,
+            tag,
+            RegistryType::CL_ACCUM,
+            3,
+            &mut tf,
+        )
+        .expect("Error creating revocation registry definition");
+        let rev_reg_def_id = format!("revreg:government/{tag}");
+        let rev_status_list = issuer::create_revocation_status_list(
+            rev_reg_def_id.as_str(),
+            &rev_reg_def,
+            GVT_ISSUER_ID,
+            Some(10),
+            issuance_by_default,
+        )
+        .expect("Error creating revocation status list");
+        initial_lists.insert(
+            RevocationRegistryDefinitionId::new_unchecked(rev_reg_def_id.as_str()),
+            rev_status_list.clone(),
+        );
+        rev_reg_set
+            .add_registry(
+                rev_reg_def_id.as_str(),
+                rev_reg_def,
+                rev_reg_def_priv,
+                rev_status_list,
+            )
+            .expect("Error adding revocation registry");
+    }
+    assert_eq!(rev_reg_set.remaining(), 4);
+
+    // Issuer issues credentials, routed to the registries with free indices
+    let mut issued = Vec::new();
+    for _ in 0..4 {
+        let cred_offer = issuer::create_credential_offer(
+            gvt_schema_id,
+            gvt_cred_def_id,
+            &gvt_cred_key_correctness_proof,
+        )
+        .expect("Error creating credential offer");
+        let (cred_request, cred_request_metadata) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            &gvt_cred_def,
+            &prover_wallet.link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+        let (mut recv_cred, rev_reg_def_id, registry_idx) = rev_reg_set
+            .create_credential(
+                &gvt_cred_def,
+                &gvt_cred_def_priv,
+                &cred_offer,
+                &cred_request,
+                fixtures::credential_values("GVT").into(),
+                |_, rev_reg_def| {
+                    Ok(TailsFileReader::new_tails_reader(
+                        &rev_reg_def.value.tails_location,
+                    ))
+                },
+            )
+            .expect("Error creating credential");
+        prover::process_credential(
+            &mut recv_cred,
+            &cred_request_metadata,
+            &prover_wallet.link_secret,
+            &gvt_cred_def,
+            Some(
+                rev_reg_set
+                    .rev_reg_def(&rev_reg_def_id)
+                    .expect("Error getting revocation registry definition"),
+            ),
+        )
+        .expect("Error processing credential");
+        prover_wallet.credentials.push(recv_cred);
+        issued.push((rev_reg_def_id.to_string(), registry_idx));
+    }
+    assert_eq!(
+        issued,
+        [
+            ("revreg:government/reg1".to_string(), 1),
+            ("revreg:government/reg1".to_string(), 2),
+            ("revreg:government/reg2".to_string(), 1),
+            ("revreg:government/reg2".to_string(), 2),
+        ]
+    );
+
+    // Every registry is full
+    let cred_offer = issuer::create_credential_offer(
+        gvt_schema_id,
+        gvt_cred_def_id,
+        &gvt_cred_key_correctness_proof,
+    )
+    .expect("Error creating credential offer");
+    let (cred_request, _) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &gvt_cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &cred_offer,
+    )
+    .expect("Error creating credential request");
+    assert_eq!(
+        rev_reg_set
+            .create_credential(
+                &gvt_cred_def,
+                &gvt_cred_def_priv,
+                &cred_offer,
+                &cred_request,
+                fixtures::credential_values("GVT").into(),
+                |_, _| unreachable!("No registry has a free revocation index"),
+            )
+            .unwrap_err()
+            .kind(),
+        ErrorKind::RevocationRegistryFull
+    );
+
+    // Issuer revokes a credential of each registry, and publishes the updated status lists
+    let reg1 = RevocationRegistryDefinitionId::new_unchecked("revreg:government/reg1");
+    let reg2 = RevocationRegistryDefinitionId::new_unchecked("revreg:government/reg2");
+    let reg1_tails_reader = TailsFileReader::new_tails_reader(
+        &rev_reg_set
+            .rev_reg_def(&reg1)
+            .expect("Error getting revocation registry definition")
+            .value
+            .tails_location,
+    );
+    rev_reg_set
+        .revoke(&reg1, 2, Some(&reg1_tails_reader))
+        .expect("Error revoking credential");
+    rev_reg_set
+        .revoke(&reg2, 1, None)
+        .expect("Error revoking credential");
+    assert!(rev_reg_set
+        .revoke(
+            &RevocationRegistryDefinitionId::new_unchecked("revreg:government/reg3"),
+            1,
+            None
+        )
+        .is_err());
+
+    let updated_lists = rev_reg_set.update_status_lists(20);
+    assert_eq!(updated_lists.len(), 2);
+    let delta = initial_lists[&reg1]
+        .diff(&updated_lists[0])
+        .expect("Error computing revocation status list delta");
+    assert_eq!(delta.to_timestamp, Some(20));
+    assert_eq!(delta.issued, BTreeSet::new());
+    assert_eq!(delta.revoked, BTreeSet::from([2]));
+    // Only the credential which was not revoked remains issued on demand
+    let delta = initial_lists[&reg2]
+        .diff(&updated_lists[1])
+        .expect("Error computing revocation status list delta");
+    assert_eq!(delta.to_timestamp, Some(20));
+    assert_eq!(delta.issued, BTreeSet::from([2]));
+    assert_eq!(delta.revoked, BTreeSet::new());
+
+    // Nothing changed since the status lists were published
+    assert!(rev_reg_set.update_status_lists(30).is_empty());
+}
+
+#[test]
+fn anoncreds_demo_works_for_credential_issued_after_other_issuances_and_revocations() {
+    let link_secret = prover::create_link_secret().expect("Error creating link secret");
+
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, true);
+
+    // This will create a tails file locally in the .tmp dir
+    let tf_path = "../.tmp";
+    create_dir(tf_path)
+        .or_else(|e| -> Result<(), std::io::Error> {
+            println!(
+                "Tail file path creation error but test can still proceed {}",
+                e
+            );
+            Ok(())
+        })
+        .unwrap();
+    let mut tf = TailsFileWriter::new(Some(tf_path.to_owned()));
+    let ((gvt_rev_reg_def, gvt_rev_reg_def_priv), gvt_rev_reg_def_id) =
+        fixtures::create_rev_reg_def(&gvt_cred_def, &mut tf);
+
+    // The witness of a credential must cover the credentials issued and revoked before it, or
+    // the prover rejects the credential as not matching the accumulator
+    for issuance_by_default in [true, false] {
+        let mut rev_status_list = fixtures::create_revocation_status_list(
+            &gvt_rev_reg_def,
+            Some(10),
+            issuance_by_default,
+        );
+        for (registry_idx, revoke) in [(1, true), (2, false), (3, false)] {
+            let cred_offer = issuer::create_credential_offer(
+                gvt_schema_id,
+                gvt_cred_def_id,
+                &gvt_cred_key_correctness_proof,
+            )
+            .expect("Error creating credential offer");
+            let (cred_request, cred_request_metadata) = prover::create_credential_request(
+                Some("entropy"),
+                None,
+                &gvt_cred_def,
+                &link_secret,
+                "default",
+                &cred_offer,
+            )
+            .expect("Error creating credential request");
+            let mut recv_cred = issuer::create_credential(
+                &gvt_cred_def,
+                &gvt_cred_def_priv,
+                &cred_offer,
+                &cred_request,
+                fixtures::credential_values("GVT").into(),
+                Some(RevocationRegistryId::new_unchecked(gvt_rev_reg_def_id)),
+                Some(&rev_status_list),
+                Some(CredentialRevocationConfig {
+                    reg_def: &gvt_rev_reg_def,
+                    reg_def_private: &gvt_rev_reg_def_priv,
+                    registry_idx,
+                    tails_reader: TailsFileReader::new_tails_reader(
+                        &gvt_rev_reg_def.value.tails_location,
+                    ),
+                }),
+            )
+            .expect("Error creating credential");
+            prover::process_credential(
+                &mut recv_cred,
+                &cred_request_metadata,
+                &link_secret,
+                &gvt_cred_def,
+                Some(&gvt_rev_reg_def),
+            )
+            .expect("Error processing credential");
+
+            rev_status_list = issuer::update_revocation_status_list(
+                None,
+                Some(BTreeSet::from([registry_idx])),
+                None,
+                &gvt_rev_reg_def,
+                &rev_status_list,
+            )
+            .expect("Error updating revocation status list");
+            if revoke {
+                rev_status_list = issuer::update_revocation_status_list(
+                    None,
+                    None,
+                    Some(BTreeSet::from([registry_idx])),
+                    &gvt_rev_reg_def,
+                    &rev_status_list,
+                )
+                .expect("Error updating revocation status list");
+            }
+        }
+    }
+}
+
+#[test]
+fn anoncreds_demo_works_for_credential_batch_issuance() {
+    let link_secret = prover::create_link_secret().expect("Error creating link secret");
+
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let ((gvt_cred_def, gvt_cred_def_priv, gvt_cred_key_correctness_proof), gvt_cred_def_id) =
+        fixtures::create_cred_def(&gvt_schema, true);
+
+    // This will create a tails file locally in the .tmp dir
+    let tf_path = "../.tmp";
+    create_dir(tf_path)
+        .or_else(|e| -> Result<(), std::io::Error> {
+            println!(
+                "Tail file path creation error but test can still proceed {}",
+                e
+            );
+            Ok(())
+        })
+        .unwrap();
+    let mut tf = TailsFileWriter::new(Some(tf_path.to_owned()));
+
+    // Issuer creates a revocation registry issuing its credentials on demand
+    let ((gvt_rev_reg_def, gvt_rev_reg_def_priv), gvt_rev_reg_def_id) =
+        fixtures::create_rev_reg_def(&gvt_cred_def, &mut tf);
+    let initial_status_list =
+        fixtures::create_revocation_status_list(&gvt_rev_reg_def, Some(10), false);
+    let mut allocator = RevocationRegistryAllocator::new(gvt_rev_reg_def_id, &gvt_rev_reg_def)
+        .expect("Error creating revocation index allocator");
+
+    let mut schemas = HashMap::new();
+    let schema_id = SchemaId::new_unchecked(gvt_schema_id);
+    schemas.insert(&schema_id, &gvt_schema);
+    let mut cred_defs = HashMap::new();
+    let cred_def_id = CredentialDefinitionId::new_unchecked(gvt_cred_def_id);
+    cred_defs.insert(&cred_def_id, &gvt_cred_def);
+    let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked(gvt_rev_reg_def_id);
+    let rev_reg_def_map = HashMap::from([(&rev_reg_def_id, &gvt_rev_reg_def)]);
+
+    let create_requests = |count: usize| {
+        (0..count)
+            .map(|_| {
+                let cred_offer = issuer::create_credential_offer(
+                    gvt_schema_id,
+                    gvt_cred_def_id,
+                    &gvt_cred_key_correctness_proof,
+                )
+                .expect("Error creating credential offer");
+                let (cred_request, cred_request_metadata) = prover::create_credential_request(
+                    Some("entropy"),
+                    None,
+                    &gvt_cred_def,
+                    &link_secret,
+                    "default",
+                    &cred_offer,
+                )
+                .expect("Error creating credential request");
+                (cred_offer, cred_request, cred_request_metadata)
+            })
+            .collect::<Vec<_>>()
+    };
+
+    // Issuer issues batches of credentials, updating the revocation status list once per batch
+    let mut rev_status_list = initial_status_list.clone();
+    let mut rev_indices = Vec::new();
+    for (batch_size, timestamp) in [(3, 20), (2, 30)] {
+        let requests = create_requests(batch_size);
+        let (credentials, updated_list) = issuer::create_credentials_batch(
+            &gvt_cred_def,
+            &gvt_cred_def_priv,
+            requests
+                .iter()
+                .map(|(cred_offer, cred_request, _)| {
+                    (
+                        cred_offer,
+                        cred_request,
+                        fixtures::credential_values("GVT").into(),
+                    )
+                })
+                .collect(),
+            Some(CredentialBatchRevocationConfig {
+                reg_def: &gvt_rev_reg_def,
+                reg_def_private: &gvt_rev_reg_def_priv,
+                rev_status_list: &rev_status_list,
+                allocator: &mut allocator,
+                tails_reader: TailsFileReader::new_tails_reader(
+                    &gvt_rev_reg_def.value.tails_location,
+                ),
+            }),
+        )
+        .expect("Error creating credentials");
+        rev_status_list = issuer::update_revocation_status_list_timestamp_only(
+            timestamp,
+            &updated_list.expect("Missing revocation status list"),
+        );
+
+        // The witness of each credential of the batch is valid for the updated accumulator
+        for (mut recv_cred, (_, _, cred_request_metadata)) in credentials.into_iter().zip(&requests)
+        {
+            prover::process_credential(
+                &mut recv_cred,
+                cred_request_metadata,
+                &link_secret,
+                &gvt_cred_def,
+                Some(&gvt_rev_reg_def),
+            )
+            .expect("Error processing credential");
+            rev_indices.push(recv_cred.signature.extract_index());
+
+            let nonce =
+                verifier::generate_nonce().expect("Error generating presentation request nonce");
+            let pres_request = serde_json::from_value(json!({
+                "nonce": nonce,
+                "name":"pres_req_1",
+                "version":"0.1",
+                "requested_attributes":{
+                    "attr1_referent":{"name":"name"}
+                },
+                "requested_predicates":{},
+                "non_revoked": {"from": timestamp, "to": timestamp}
+            }))
+            .expect("Error creating proof request");
+            let rev_state = CredentialRevocationState {
+                witness: recv_cred.witness.clone().expect("Missing witness"),
+                rev_reg: recv_cred
+                    .rev_reg
+                    .clone()
+                    .expect("Missing revocation registry"),
+                timestamp,
+            };
+            let mut present = PresentCredentials::default();
+            present
+                .add_credential(&recv_cred, Some(timestamp), Some(&rev_state))
+                .add_requested_attribute("attr1_referent", true);
+            let presentation = prover::create_presentation(
+                &pres_request,
+                present,
+                None,
+                &link_secret,
+                &schemas,
+                &cred_defs,
+            )
+            .expect("Error creating presentation");
+
+            let valid = verifier::verify_presentation(
+                &presentation,
+                &pres_request,
+                &schemas,
+                &cred_defs,
+                Some(&rev_reg_def_map),
+                Some(vec![&rev_status_list]),
+                None,
+            )
+            .expect("Error verifying presentation");
+            assert!(valid);
+        }
+    }
+
+    // The credentials were assigned consecutive revocation indices, all issued in the status list
+    assert_eq!(rev_indices, [Some(1), Some(2), Some(3), Some(4), Some(5)]);
+    assert_eq!(allocator.remaining(), 4);
+    let delta = initial_status_list
+        .diff(&rev_status_list)
+        .expect("Error computing revocation status list delta");
+    assert_eq!(delta.issued, BTreeSet::from([1, 2, 3, 4, 5]));
+    assert!(delta.revoked.is_empty());
+
+    // A batch which does not fit in the registry keeps no revocation index
+    let requests = create_requests(5);
+    let err = issuer::create_credentials_batch(
+        &gvt_cred_def,
+        &gvt_cred_def_priv,
+        requests
+            .iter()
+            .map(|(cred_offer, cred_request, _)| {
+                (
+                    cred_offer,
+                    cred_request,
+                    fixtures::credential_values("GVT").into(),
+                )
+            })
+            .collect(),
+        Some(CredentialBatchRevocationConfig {
+            reg_def: &gvt_rev_reg_def,
+            reg_def_private: &gvt_rev_reg_def_priv,
+            rev_status_list: &rev_status_list,
+            allocator: &mut allocator,
+            tails_reader: TailsFileReader::new_tails_reader(&gvt_rev_reg_def.value.tails_location),
+        }),
+    )
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::RevocationRegistryFull);
+    assert_eq!(allocator.remaining(), 4);
+}
+
+#[test]
+fn anoncreds_demo_works_for_credential_wallet_search() {
+    let mut store = InMemoryStore::new();
+    let link_secret = prover::create_link_secret().expect("Error creating link secret");
+    store
+        .store_link_secret("default", &link_secret)
+        .expect("Error storing link secret");
+
+    let (gvt_schema, gvt_schema_id) = fixtures::create_schema("GVT");
+    let (gvt_cred_def_parts, gvt_cred_def_id) = fixtures::create_cred_def(&gvt_schema, false);
+    let (emp_schema, emp_schema_id) = fixtures::create_schema("EMP");
+    let (emp_cred_def_parts, emp_cred_def_id) = fixtures::create_cred_def(&emp_schema, false);
+
+    // Prover receives a credential from each issuer, and keeps it in its store
+    for (name, schema_id, cred_def_id, (cred_def, cred_def_priv, key_correctness_proof)) in [
+        ("GVT", gvt_schema_id, gvt_cred_def_id, &gvt_cred_def_parts),
+        ("EMP", emp_schema_id, emp_cred_def_id, &emp_cred_def_parts),
+    ] {
+        let cred_offer =
+            issuer::create_credential_offer(schema_id, cred_def_id, key_correctness_proof)
+                .expect("Error creating credential offer");
+        let (cred_request, cred_request_metadata) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            cred_def,
+            &link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+        store
+            .store_cred_request_metadata(name, &cred_request_metadata)
+            .expect("Error storing credential request metadata");
+
+        let mut recv_cred = issuer::create_credential(
+            cred_def,
+            cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            fixtures::credential_values(name).into(),
+            None,
+            None,
+            None,
+        )
+        .expect("Error creating credential");
+        let cred_request_metadata = store
+            .cred_request_metadata(name)
+            .expect("Error reading credential request metadata")
+            .expect("Missing credential request metadata");
+        prover::process_credential(
+            &mut recv_cred,
+            &cred_request_metadata,
+            &link_secret,
+            cred_def,
+            None,
+        )
+        .expect("Error processing credential");
+        store
+            .store_credential(name, &recv_cred)
+            .expect("Error storing credential");
+        assert!(store
+            .remove_cred_request_metadata(name)
+            .expect("Error removing credential request metadata"));
+    }
+
+    let mut schemas = HashMap::new();
+    let gvt_schema_id = SchemaId::new_unchecked(gvt_schema_id);
+    schemas.insert(&gvt_schema_id, &gvt_schema);
+    let emp_schema_id = SchemaId::new_unchecked(emp_schema_id);
+    schemas.insert(&emp_schema_id, &emp_schema);
+    let mut cred_defs = HashMap::new();
+    let gvt_cred_def_id = CredentialDefinitionId::new_unchecked(gvt_cred_def_id);
+    cred_defs.insert(&gvt_cred_def_id, &gvt_cred_def_parts.0);
+    let emp_cred_def_id = CredentialDefinitionId::new_unchecked(emp_cred_def_id);
+    cred_defs.insert(&emp_cred_def_id, &emp_cred_def_parts.0);
+
+    // Prover searches its credentials with the restriction language of presentation requests
+    let wallet = CredentialWallet::load(&store, &schemas, &cred_defs)
+        .expect("Error loading credential wallet");
+    assert_eq!(wallet.len(), 2);
+    let search = |query: serde_json::Value| {
+        let query: Query = serde_json::from_value(query).expect("Error parsing query");
+        wallet
+            .search(&query)
+            .into_iter()
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(search(json!({})), ["EMP", "GVT"]);
+    assert_eq!(search(json!({ "attr::name::marker": "1" })), ["EMP", "GVT"]);
+    assert_eq!(
+        search(json!({ "attr::age::value": { "$gte": "18" } })),
+        ["GVT"]
+    );
+    assert_eq!(
+        search(json!({ "schema_name": { "$like": "Employee%" } })),
+        ["EMP"]
+    );
+    assert_eq!(
+        search(json!({ "$not": { "cred_def_id": gvt_cred_def_id } })),
+        ["EMP"]
+    );
+    assert_eq!(
+        search(json!({ "$exist": ["attr::role::marker", "attr::age::marker"] })),
+        Vec::<&str>::new()
+    );
+
+    // The same credentials are matched against a presentation request
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    let pres_request: PresentationRequest = serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"role",
+                "restrictions": { "schema_name": "Employee Schema" }
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        }
+    }))
+    .expect("Error creating proof request");
+    let candidates = wallet.candidates();
+    let matches = prover::match_credentials(&pres_request, &candidates, &schemas, &cred_defs);
+    assert!(matches.unmatched_attributes().is_empty());
+    assert!(matches.unmatched_predicates().is_empty());
+
+    let presentation = prover::create_presentation(
+        &pres_request,
+        matches.to_present_credentials(),
+        None,
+        &link_secret,
+        &schemas,
+        &cred_defs,
+    )
+    .expect("Error creating presentation");
+    let valid = verifier::verify_presentation(
+        &presentation,
+        &pres_request,
+        &schemas,
+        &cred_defs,
+        None,
+        None,
+        None,
+    )
+    .expect("Error verifying presentation");
+    assert!(valid);
+}
@@ -1,18 +1,26 @@
-use std::collections::HashMap;
+use std::{
+    collections::{BTreeSet, HashMap},
+    fs::create_dir,
+};
 
 use anoncreds::{
     data_types::{
         cred_def::{CredentialDefinition, CredentialDefinitionId},
+        rev_reg::RevocationRegistryId,
+        rev_reg_def::RevocationRegistryDefinitionId,
         schema::{Schema, SchemaId},
     },
     issuer, prover,
-    tails::TailsFileWriter,
+    tails::{TailsFileReader, TailsFileWriter, TailsReader},
     types::{
-        CredentialDefinitionPrivate, CredentialKeyCorrectnessProof, CredentialRevocationState,
-        MakeCredentialValues, PresentCredentials, Presentation, PresentationRequest,
-        RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate, RevocationStatusList,
+        CredentialDefinitionPrivate, CredentialKeyCorrectnessProof, CredentialRevocationConfig,
+        CredentialRevocationState, MakeCredentialValues, PresentCredentials, Presentation,
+        PresentationRequest, RevocationRegistryDefinition, RevocationRegistryDefinitionPrivate,
+        RevocationStatusList,
     },
+    verifier,
 };
+use serde_json::json;
 
 use super::storage::ProverWallet;
 
@@ -48,6 +56,17 @@ pub const EMP_REV_REG_TAG: &str = "revregemployeetag";
 pub const EMP_REV_IDX: u32 = 9;
 pub const EMP_REV_MAX_CRED_NUM: u32 = 10;
 
+// Driver license credential related fixtures
+pub const DL_SCHEMA_NAME: &str = "Driver License Schema";
+pub const DL_SCHEMA_ID: &str = "schema:driverlicense";
+pub const DL_SCHEMA_VERSION: &str = "1.0";
+pub const DL_SCHEMA_ATTRIBUTES: &[&str; 3] = &["name", "birthdate", "expiry"];
+
+pub const DL_CRED_DEF_ID: &str = "creddef:driverlicense";
+pub const DL_CRED_DEF_TAG: &str = "driverlicensetag";
+
+pub const DL_ISSUER_ID: &str = "dmv:id/path=bar";
+
 // Create a `GVT` or `EMP` schema
 pub fn create_schema(name: &str) -> (Schema, &str) {
     match name {
@@ -71,6 +90,16 @@ pub fn create_schema(name: &str) -> (Schema, &str) {
             .expect("error while creating EMP schema"),
             EMP_SCHEMA_ID,
         ),
+        "DL" => (
+            issuer::create_schema(
+                DL_SCHEMA_NAME,
+                DL_SCHEMA_VERSION,
+                DL_ISSUER_ID,
+                DL_SCHEMA_ATTRIBUTES[..].into(),
+            )
+            .expect("error while creating DL schema"),
+            DL_SCHEMA_ID,
+        ),
         unsupported => panic!("Unsupported schema. {unsupported}"),
     }
 }
@@ -111,6 +140,18 @@ pub fn create_cred_def(
             .expect("error while creating EMP cred def"),
             EMP_CRED_DEF_ID,
         ),
+        DL_SCHEMA_NAME => (
+            issuer::create_credential_definition(
+                DL_SCHEMA_ID,
+                schema,
+                DL_ISSUER_ID,
+                DL_CRED_DEF_TAG,
+                anoncreds::types::SignatureType::CL,
+                anoncreds::types::CredentialDefinitionConfig { support_revocation },
+            )
+            .expect("error while creating DL cred def"),
+            DL_CRED_DEF_ID,
+        ),
         unsupported => panic!("Unsupported schema name. {unsupported}"),
     }
 }
@@ -255,6 +296,319 @@ pub fn credential_values(name: &str) -> MakeCredentialValues {
                 .expect("Error encoding attribute");
             emp_cred_values
         }
+        "DL" => {
+            let mut dl_cred_values = MakeCredentialValues::default();
+            dl_cred_values
+                .add_raw("name", "Alex")
+                .expect("Error encoding attribute");
+            dl_cred_values.add_date(
+                "birthdate",
+                "2000-02-29".parse().expect("Error parsing date"),
+            );
+            dl_cred_values.add_date("expiry", "2030-01-31".parse().expect("Error parsing date"));
+            dl_cred_values
+        }
         unsupported => panic!("Unsupported credential values. {unsupported}"),
     }
 }
+
+// A revocable `GVT` credential issued at `GVT_REV_IDX` and stored in the prover wallet
+pub struct RevocableCredential<'a> {
+    pub prover_wallet: ProverWallet<'a>,
+    pub schema_id: SchemaId,
+    pub schema: Schema,
+    pub cred_def_id: CredentialDefinitionId,
+    pub cred_def: CredentialDefinition,
+    pub rev_reg_def_id: RevocationRegistryDefinitionId,
+    pub rev_reg_def: RevocationRegistryDefinition,
+    // Status list published at `time_issued`, after the credential is issued
+    pub issued_list: RevocationStatusList,
+    pub time_issued: u64,
+    // Presentation request answered by `create_presentation`, with a non revocation interval
+    pub pres_request: PresentationRequest,
+}
+
+impl<'a> RevocableCredential<'a> {
+    pub fn issue() -> Self {
+        let mut prover_wallet = ProverWallet::default();
+        let (schema, schema_id) = create_schema("GVT");
+        let ((cred_def, cred_def_priv, cred_key_correctness_proof), cred_def_id) =
+            create_cred_def(&schema, true);
+
+        // This will create a tails file locally in the .tmp dir
+        let tf_path = "../.tmp";
+        create_dir(tf_path)
+            .or_else(|e| -> Result<(), std::io::Error> {
+                println!(
+                    "Tail file path creation error but test can still proceed {}",
+                    e
+                );
+                Ok(())
+            })
+            .unwrap();
+        let mut tf = TailsFileWriter::new(Some(tf_path.to_owned()));
+        let ((rev_reg_def, rev_reg_def_priv), rev_reg_def_id) =
+            create_rev_reg_def(&cred_def, &mut tf);
+
+        let time_created = 12;
+        let initial_list = create_revocation_status_list(&rev_reg_def, Some(time_created), true);
+
+        let cred_offer =
+            issuer::create_credential_offer(schema_id, cred_def_id, &cred_key_correctness_proof)
+                .expect("Error creating credential offer");
+        let schema_id = SchemaId::new_unchecked(schema_id);
+        let cred_def_id = CredentialDefinitionId::new_unchecked(cred_def_id);
+
+        let (cred_request, cred_request_metadata) = prover::create_credential_request(
+            Some("entropy"),
+            None,
+            &cred_def,
+            &prover_wallet.link_secret,
+            "default",
+            &cred_offer,
+        )
+        .expect("Error creating credential request");
+
+        let rev_reg_def_id = RevocationRegistryDefinitionId::new_unchecked(rev_reg_def_id);
+        let rev_reg_id = RevocationRegistryId::new_unchecked(rev_reg_def_id.clone());
+        let mut recv_cred = issuer::create_credential(
+            &cred_def,
+            &cred_def_priv,
+            &cred_offer,
+            &cred_request,
+            credential_values("GVT").into(),
+            Some(rev_reg_id),
+            Some(&initial_list),
+            Some(CredentialRevocationConfig {
+                reg_def: &rev_reg_def,
+                reg_def_private: &rev_reg_def_priv,
+                registry_idx: GVT_REV_IDX,
+                tails_reader: TailsFileReader::new_tails_reader(&rev_reg_def.value.tails_location),
+            }),
+        )
+        .expect("Error creating credential");
+
+        let time_issued = time_created + 1;
+        let issued_list = issuer::update_revocation_status_list(
+            Some(time_issued),
+            Some(BTreeSet::from([GVT_REV_IDX])),
+            None,
+            &rev_reg_def,
+            &initial_list,
+        )
+        .expect("Error updating revocation status list");
+
+        prover::process_credential(
+            &mut recv_cred,
+            &cred_request_metadata,
+            &prover_wallet.link_secret,
+            &cred_def,
+            Some(&rev_reg_def),
+        )
+        .expect("Error processing credential");
+        prover_wallet.credentials.push(recv_cred);
+
+        let nonce =
+            verifier::generate_nonce().expect("Error generating presentation request nonce");
+        let pres_request = serde_json::from_value(json!({
+            "nonce": nonce,
+            "name":"pres_req_1",
+            "version":"0.1",
+            "requested_attributes":{
+                "attr1_referent":{
+                    "name":"name",
+                    "issuer_id": GVT_ISSUER_ID
+                },
+                "attr2_referent":{
+                    "name":"sex"
+                },
+                "attr3_referent":{"name":"phone"},
+                "attr4_referent":{
+                    "names": ["name", "height"]
+                }
+            },
+            "requested_predicates":{
+                "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+            },
+            "non_revoked": {"from": 10, "to": 200}
+        }))
+        .expect("Error creating proof request");
+
+        Self {
+            prover_wallet,
+            schema_id,
+            schema,
+            cred_def_id,
+            cred_def,
+            rev_reg_def_id,
+            rev_reg_def,
+            issued_list,
+            time_issued,
+            pres_request,
+        }
+    }
+
+    pub fn schemas(&self) -> HashMap<&SchemaId, &Schema> {
+        HashMap::from([(&self.schema_id, &self.schema)])
+    }
+
+    pub fn cred_defs(&self) -> HashMap<&CredentialDefinitionId, &CredentialDefinition> {
+        HashMap::from([(&self.cred_def_id, &self.cred_def)])
+    }
+
+    pub fn rev_reg_defs(
+        &self,
+    ) -> HashMap<&RevocationRegistryDefinitionId, &RevocationRegistryDefinition> {
+        HashMap::from([(&self.rev_reg_def_id, &self.rev_reg_def)])
+    }
+
+    // Create the revocation state of the credential for `issued_list`
+    pub fn create_rev_state(&self, tails_reader: &TailsReader) -> CredentialRevocationState {
+        prover::create_or_update_revocation_state_with_tails_reader(
+            tails_reader,
+            &self.rev_reg_def,
+            &self.issued_list,
+            GVT_REV_IDX,
+            None,
+            None,
+        )
+        .expect("Error creating revocation state")
+    }
+
+    // Revoke the credential, returning the status list published at `time_issued + 1`
+    pub fn revoke(&self, tails_reader: Option<&TailsReader>) -> RevocationStatusList {
+        let default_reader;
+        let tails_reader = match tails_reader {
+            Some(reader) => reader,
+            None => {
+                default_reader =
+                    TailsFileReader::new_tails_reader(&self.rev_reg_def.value.tails_location);
+                &default_reader
+            }
+        };
+        issuer::update_revocation_status_list_with_tails_reader(
+            Some(self.time_issued + 1),
+            None,
+            Some(BTreeSet::from([GVT_REV_IDX])),
+            &self.rev_reg_def,
+            &self.issued_list,
+            tails_reader,
+        )
+        .expect("Error updating revocation status list")
+    }
+}
+
+// Issue a non revocable `GVT`, `EMP` or `DL` credential and store it in the prover wallet
+pub fn issue_credential(
+    name: &str,
+    prover_wallet: &mut ProverWallet,
+) -> (
+    Schema,
+    SchemaId,
+    CredentialDefinition,
+    CredentialDefinitionId,
+) {
+    let (schema, schema_id) = create_schema(name);
+    let ((cred_def, cred_def_priv, cred_key_correctness_proof), cred_def_id) =
+        create_cred_def(&schema, false);
+
+    let cred_offer =
+        issuer::create_credential_offer(schema_id, cred_def_id, &cred_key_correctness_proof)
+            .expect("Error creating credential offer");
+    let schema_id = SchemaId::new_unchecked(schema_id);
+    let cred_def_id = CredentialDefinitionId::new_unchecked(cred_def_id);
+
+    let (cred_request, cred_request_metadata) = prover::create_credential_request(
+        Some("entropy"),
+        None,
+        &cred_def,
+        &prover_wallet.link_secret,
+        "default",
+        &cred_offer,
+    )
+    .expect("Error creating credential request");
+
+    let mut recv_cred = issuer::create_credential(
+        &cred_def,
+        &cred_def_priv,
+        &cred_offer,
+        &cred_request,
+        credential_values(name).into(),
+        None,
+        None,
+        None,
+    )
+    .expect("Error creating credential");
+
+    prover::process_credential(
+        &mut recv_cred,
+        &cred_request_metadata,
+        &prover_wallet.link_secret,
+        &cred_def,
+        None,
+    )
+    .expect("Error processing credential");
+    prover_wallet.credentials.push(recv_cred);
+
+    (schema, schema_id, cred_def, cred_def_id)
+}
+
+// Create the presentation request answered by `create_presentation`
+pub fn create_presentation_request() -> PresentationRequest {
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"pres_req_1",
+        "version":"0.1",
+        "requested_attributes":{
+            "attr1_referent":{
+                "name":"name"
+            },
+            "attr2_referent":{
+                "name":"sex"
+            },
+            "attr3_referent":{"name":"phone"},
+            "attr4_referent":{
+                "names": ["name", "height"]
+            }
+        },
+        "requested_predicates":{
+            "predicate1_referent":{"name":"age","p_type":">=","p_value":18}
+        }
+    }))
+    .expect("Error creating proof request")
+}
+
+// Create a presentation request for the `GVT` and `EMP` credentials of two issuers
+pub fn create_multiple_issuer_presentation_request(
+    gvt_cred_def_id: &CredentialDefinitionId,
+    emp_cred_def_id: &CredentialDefinitionId,
+) -> PresentationRequest {
+    let nonce = verifier::generate_nonce().expect("Error generating presentation request nonce");
+    serde_json::from_value(json!({
+        "nonce": nonce,
+        "name":"proof_req_1",
+        "version":"0.1",
+        "requested_attributes": {
+            "attr1_referent": {
+                "name":"name",
+                "restrictions": { "cred_def_id": gvt_cred_def_id, "attr::name::value": "Alex" }
+            },
+            "attr2_referent": {
+                "name":"role",
+                "restrictions": { "cred_def_id": emp_cred_def_id }
+            },
+            "attr3_referent": {
+                "name": "height",
+                "restrictions": { "cred_def_id": gvt_cred_def_id, "attr::height::value": "175" },
+            }
+        },
+        "requested_predicates": {
+            "predicate1_referent": {
+                "name":"age", "p_type":">=", "p_value":18,
+                "restrictions": { "cred_def_id": gvt_cred_def_id, "attr::name::value": "Alex", "attr::height::value": "175" }
+            },
+        },
+    }))
+    .expect("Error creating proof request")
+}
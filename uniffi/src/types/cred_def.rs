@@ -103,7 +103,8 @@ impl TryInto<AnoncredsCredentialDefinition> for CredentialDefinition {
             signature_type: cloned_signature_type,
             tag: cloned_get_tag,
             value: cloned_value,
-            issuer_id: cloned_issuer_id
+            issuer_id: cloned_issuer_id,
+            rotated_from: self.core.rotated_from.clone()
         };
         return Ok(cloned_def)
     }
@@ -125,7 +126,8 @@ impl TryFrom<AnoncredsCredentialDefinition> for CredentialDefinition {
             signature_type: cloned_signature_type,
             tag: cloned_get_tag,
             value: cloned_value,
-            issuer_id: cloned_issuer_id
+            issuer_id: cloned_issuer_id,
+            rotated_from: acr.rotated_from.clone()
         };
         return Ok(CredentialDefinition { core: cloned_def })
     }
@@ -147,7 +149,8 @@ impl TryFrom<&CredentialDefinition> for AnoncredsCredentialDefinition {
             signature_type: cloned_signature_type,
             tag: cloned_get_tag,
             value: cloned_value,
-            issuer_id: cloned_issuer_id
+            issuer_id: cloned_issuer_id,
+            rotated_from: def.core.rotated_from.clone()
         })
     }
 }
@@ -26,6 +26,7 @@ enum ErrorCode
   InvalidUserRevocId = 6,
   ProofRejected = 7,
   RevocationRegistryFull = 8,
+  InvalidTailsFile = 9,
 };
 #ifndef __cplusplus
 typedef size_t ErrorCode;
@@ -358,6 +359,10 @@ ErrorCode anoncreds_credential_request_metadata_from_json(struct ByteBuffer json
 
 ErrorCode anoncreds_encode_credential_attributes(FfiStrList attr_raw_values, const char **result_p);
 
+ErrorCode anoncreds_encode_credential_attributes_with_encoders(FfiStrList attr_raw_values,
+                                                               FfiStrList attr_encoders,
+                                                               const char **result_p);
+
 ErrorCode anoncreds_generate_nonce(const char **nonce_p);
 
 ErrorCode anoncreds_get_current_error(const char **error_json_p);
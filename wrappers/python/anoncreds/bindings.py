@@ -695,12 +695,26 @@ def create_credential(
 
 
 def encode_credential_attributes(
-    attr_raw_values: Mapping[str, str]
+    attr_raw_values: Mapping[str, str],
+    attr_encoders: Optional[Mapping[str, str]] = None,
 ) -> Mapping[str, str]:
     attr_keys = list(attr_raw_values.keys())
     raw_values_list = FfiStrList.create(str(attr_raw_values[k]) for k in attr_keys)
     result = StrBuffer()
-    do_call("anoncreds_encode_credential_attributes", raw_values_list, byref(result))
+    if attr_encoders:
+        encoders_list = FfiStrList.create(
+            attr_encoders.get(k, "legacy") for k in attr_keys
+        )
+        do_call(
+            "anoncreds_encode_credential_attributes_with_encoders",
+            raw_values_list,
+            encoders_list,
+            byref(result),
+        )
+    else:
+        do_call(
+            "anoncreds_encode_credential_attributes", raw_values_list, byref(result)
+        )
     return dict(zip(attr_keys, str(result).split(",")))
 
 
@@ -13,6 +13,7 @@ class AnoncredsErrorCode(IntEnum):
     INVALID_USER_REVOC_ID = 6
     PROOF_REJECTED = 7
     REVOCATION_REGISTRY_FULL = 8
+    INVALID_TAILS_FILE = 9
     WRAPPER = 99
 
 